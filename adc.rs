@@ -0,0 +1,91 @@
+// src/adc.rs
+// Pengaturan ADC per sensor: nilai mentah dari Arduino diubah ke tegangan yang naik seiring intensitas
+// cahaya, sehingga model kalibrasi dan klasifikasi layar utama memakai arah yang sama.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AdcPolarity {
+    // Nilai mentah naik saat cahaya bertambah (gelap = rendah).
+    DarkLow,
+    // Nilai mentah turun saat cahaya bertambah (gelap = tinggi), misalnya photodiode ke VCC dengan pull-down.
+    DarkHigh,
+}
+
+impl AdcPolarity {
+    pub const ALL: [AdcPolarity; 2] = [AdcPolarity::DarkLow, AdcPolarity::DarkHigh];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AdcPolarity::DarkLow => "Gelap = Nilai Rendah",
+            AdcPolarity::DarkHigh => "Gelap = Nilai Tinggi (Terbalik)",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdcSettings {
+    pub resolution_bits: u32,
+    pub reference_voltage: f64,
+    // Dikurangkan dari nilai mentah sebelum dikali gain (dalam satuan hitungan ADC).
+    pub offset: f64,
+    pub gain: f64,
+    pub polarity: AdcPolarity,
+    // Ketidakpastian standar relatif Vref (0,01 = 1%), dipakai propagasi ketidakpastian lux.
+    #[serde(default)]
+    pub reference_tolerance: f64,
+    // Nilai mentah pada skala penuh bila firmware sudah menskalakan hasil ADC (firmware bawaan mengirim
+    // "Scaled 0-1000"); `None` = skala penuh ADC, 2^bit - 1.
+    #[serde(default)]
+    pub full_scale: Option<f64>,
+}
+
+impl AdcSettings {
+    pub const MIN_BITS: u32 = 8;
+    pub const MAX_BITS: u32 = 16;
+
+    pub fn new() -> Self {
+        Self {
+            resolution_bits: 10,
+            reference_voltage: 3.3,
+            offset: 0.0,
+            gain: 1.0,
+            polarity: AdcPolarity::DarkLow,
+            reference_tolerance: 0.01,
+            full_scale: Some(Self::FIRMWARE_FULL_SCALE),
+        }
+    }
+
+    pub const FIRMWARE_FULL_SCALE: f64 = 1000.0;
+
+    // Jumlah nilai mentah bulat yang mungkin (untuk LUT), mencakup skala penuh firmware bila lebih besar.
+    pub fn levels(&self) -> usize {
+        let adc_levels = 1 << self.resolution_bits.clamp(Self::MIN_BITS, Self::MAX_BITS);
+        adc_levels.max(self.max_value() as usize + 1)
+    }
+
+    pub fn max_value(&self) -> f64 {
+        match self.full_scale.filter(|value| *value > 0.0) {
+            Some(value) => value,
+            None => ((1u64 << self.resolution_bits.clamp(Self::MIN_BITS, Self::MAX_BITS)) - 1) as f64,
+        }
+    }
+
+    // Porsi skala penuh setelah offset, gain dan polaritas; 0 = gelap, 1 = skala penuh. Tidak di-clamp.
+    pub fn light_fraction(&self, raw_value: f64) -> f64 {
+        let fraction = (raw_value - self.offset) * self.gain / self.max_value();
+        match self.polarity {
+            AdcPolarity::DarkLow => fraction,
+            AdcPolarity::DarkHigh => 1.0 - fraction,
+        }
+    }
+
+    pub fn to_voltage(&self, raw_value: f64) -> f64 {
+        self.light_fraction(raw_value) * self.reference_voltage
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} bit (0-{}), Vref {} V, offset {}, gain {}, {}",
+            self.resolution_bits, self.max_value(), self.reference_voltage, self.offset, self.gain, self.polarity.label())
+    }
+}
@@ -0,0 +1,219 @@
+// src/calibration.rs
+// Menurunkan konstanta A dan B model V = A · lux^B dari pasangan (nilai photodiode, lux meter referensi)
+// dengan kuadrat terkecil nonlinear (Levenberg–Marquardt, Gauss-Newton teredam).
+
+const MAX_FIT_ITERATIONS: usize = 200;
+const FIT_TOLERANCE: f64 = 1e-12;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrationPoint {
+    pub photodiode_value: f64,
+    // Dihitung dengan pengaturan ADC sensor saat titik dicatat.
+    pub voltage: f64,
+    pub reference_lux: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FitResult {
+    pub calib_a: f64,
+    pub calib_b: f64,
+    // Tegangan terukur dikurangi tegangan model, urut sesuai titik kalibrasi.
+    pub residuals: Vec<f64>,
+    pub r_squared: f64,
+    pub rmse: f64,
+    // s² · (JᵀJ)⁻¹ pada parameter akhir, s² = SSR / (n - 2).
+    pub covariance: Option<[[f64; 2]; 2]>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+fn power_law(calib_a: f64, calib_b: f64, lux: f64) -> f64 {
+    calib_a * lux.powf(calib_b)
+}
+
+fn sum_squared_residuals(points: &[CalibrationPoint], calib_a: f64, calib_b: f64) -> f64 {
+    points.iter()
+        .map(|p| (p.voltage - power_law(calib_a, calib_b, p.reference_lux)).powi(2))
+        .sum()
+}
+
+// Tebakan awal dari regresi linear ln V = ln A + B · ln lux (hanya titik dengan lux dan V positif).
+fn log_linear_guess(points: &[CalibrationPoint]) -> Option<(f64, f64)> {
+    let logs: Vec<(f64, f64)> = points.iter()
+        .filter(|p| p.reference_lux > 0.0 && p.voltage > 0.0)
+        .map(|p| (p.reference_lux.ln(), p.voltage.ln()))
+        .collect();
+    if logs.len() < 2 {
+        return None;
+    }
+
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let calib_b = sxy / sxx;
+    let calib_a = (mean_y - calib_b * mean_x).exp();
+    Some((calib_a, calib_b))
+}
+
+pub fn fit_power_law(points: &[CalibrationPoint]) -> Result<FitResult, String> {
+    if points.len() < 3 {
+        return Err("Minimal 3 pasangan titik kalibrasi dibutuhkan untuk mencocokkan A dan B.".to_string());
+    }
+    if points.iter().any(|p| p.reference_lux < 0.0 || !p.reference_lux.is_finite() || !p.photodiode_value.is_finite() || !p.voltage.is_finite()) {
+        return Err("Lux referensi dan nilai photodiode harus berupa angka tidak negatif.".to_string());
+    }
+    let (mut calib_a, mut calib_b) = log_linear_guess(points)
+        .ok_or("Butuh minimal 2 titik dengan lux dan tegangan positif yang berbeda untuk tebakan awal.")?;
+
+    let mut cost = sum_squared_residuals(points, calib_a, calib_b);
+    let mut lambda = 1e-3;
+    let mut iterations = 0;
+    let mut converged = false;
+
+    while iterations < MAX_FIT_ITERATIONS {
+        iterations += 1;
+
+        // Persamaan normal (JᵀJ + λ·diag(JᵀJ)) δ = Jᵀr untuk parameter (A, B).
+        let (mut jtj, mut jtr) = ([[0.0; 2]; 2], [0.0; 2]);
+        for p in points {
+            let lux_pow = p.reference_lux.powf(calib_b);
+            let d_a = lux_pow;
+            // Batas A · lux^B · ln lux untuk lux → 0 adalah 0 (B > 0).
+            let d_b = if p.reference_lux > 0.0 { calib_a * lux_pow * p.reference_lux.ln() } else { 0.0 };
+            let residual = p.voltage - calib_a * lux_pow;
+            jtj[0][0] += d_a * d_a;
+            jtj[0][1] += d_a * d_b;
+            jtj[1][1] += d_b * d_b;
+            jtr[0] += d_a * residual;
+            jtr[1] += d_b * residual;
+        }
+        jtj[1][0] = jtj[0][1];
+
+        let m00 = jtj[0][0] * (1.0 + lambda);
+        let m11 = jtj[1][1] * (1.0 + lambda);
+        let determinant = m00 * m11 - jtj[0][1] * jtj[1][0];
+        if determinant.abs() < f64::MIN_POSITIVE || !determinant.is_finite() {
+            break;
+        }
+        let delta_a = (m11 * jtr[0] - jtj[0][1] * jtr[1]) / determinant;
+        let delta_b = (m00 * jtr[1] - jtj[1][0] * jtr[0]) / determinant;
+
+        let (new_a, new_b) = (calib_a + delta_a, calib_b + delta_b);
+        let new_cost = sum_squared_residuals(points, new_a, new_b);
+        if new_cost.is_finite() && new_a > 0.0 && new_cost <= cost {
+            let small_step = delta_a.abs() <= FIT_TOLERANCE * (calib_a.abs() + FIT_TOLERANCE)
+                && delta_b.abs() <= FIT_TOLERANCE * (calib_b.abs() + FIT_TOLERANCE);
+            let small_improvement = cost - new_cost <= FIT_TOLERANCE * cost;
+            calib_a = new_a;
+            calib_b = new_b;
+            cost = new_cost;
+            lambda = (lambda * 0.1).max(1e-12);
+            if small_step || small_improvement {
+                converged = true;
+                break;
+            }
+        } else {
+            lambda *= 10.0;
+            if lambda > 1e12 {
+                // Tidak ada langkah yang menurunkan error walau sangat teredam: gagal konvergen.
+                break;
+            }
+        }
+    }
+
+    let residuals: Vec<f64> = points.iter()
+        .map(|p| p.voltage - power_law(calib_a, calib_b, p.reference_lux))
+        .collect();
+    let mean_voltage = points.iter().map(|p| p.voltage).sum::<f64>() / points.len() as f64;
+    let total_sum_squares: f64 = points.iter().map(|p| (p.voltage - mean_voltage).powi(2)).sum();
+    let r_squared = if total_sum_squares > 0.0 { 1.0 - cost / total_sum_squares } else { f64::NAN };
+    let rmse = (cost / points.len() as f64).sqrt();
+    let covariance = parameter_covariance(points, calib_a, calib_b, cost);
+
+    Ok(FitResult { calib_a, calib_b, residuals, r_squared, rmse, covariance, iterations, converged })
+}
+
+fn parameter_covariance(points: &[CalibrationPoint], calib_a: f64, calib_b: f64, cost: f64) -> Option<[[f64; 2]; 2]> {
+    let degrees_of_freedom = points.len().checked_sub(2).filter(|&n| n > 0)? as f64;
+    let mut jtj = [[0.0; 2]; 2];
+    for p in points {
+        let lux_pow = p.reference_lux.powf(calib_b);
+        let d_b = if p.reference_lux > 0.0 { calib_a * lux_pow * p.reference_lux.ln() } else { 0.0 };
+        jtj[0][0] += lux_pow * lux_pow;
+        jtj[0][1] += lux_pow * d_b;
+        jtj[1][1] += d_b * d_b;
+    }
+    let determinant = jtj[0][0] * jtj[1][1] - jtj[0][1] * jtj[0][1];
+    if determinant.abs() < f64::MIN_POSITIVE || !determinant.is_finite() {
+        return None;
+    }
+    let variance = cost / degrees_of_freedom;
+    Some([
+        [variance * jtj[1][1] / determinant, -variance * jtj[0][1] / determinant],
+        [-variance * jtj[0][1] / determinant, variance * jtj[0][0] / determinant],
+    ])
+}
+
+// Titik-titik kurva hasil fit (lux, V) untuk digambar di atas titik kalibrasi.
+pub fn fit_curve(fit: &FitResult, max_lux: f64, samples: usize) -> Vec<[f64; 2]> {
+    (0..=samples)
+        .map(|i| {
+            let lux = max_lux * i as f64 / samples as f64;
+            [lux, power_law(fit.calib_a, fit.calib_b, lux)]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRUE_A: f64 = 0.0123;
+    const TRUE_B: f64 = 0.78;
+    const LUX: [f64; 8] = [0.0, 5.0, 40.0, 150.0, 600.0, 2_000.0, 12_000.0, 60_000.0];
+
+    // Titik sintetis V = A · lux^B dengan gangguan relatif `noise[i]`.
+    fn points(noise: &[f64]) -> Vec<CalibrationPoint> {
+        LUX.iter().zip(noise.iter().cycle())
+            .map(|(&lux, &noise)| {
+                let voltage = power_law(TRUE_A, TRUE_B, lux) * (1.0 + noise);
+                CalibrationPoint { photodiode_value: voltage * 1000.0 / 5.0, voltage, reference_lux: lux }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovers_exact_power_law() {
+        let fit = fit_power_law(&points(&[0.0])).unwrap();
+        assert!(fit.converged, "{:?}", fit);
+        assert!((fit.calib_a - TRUE_A).abs() < 1e-9 * TRUE_A, "{}", fit.calib_a);
+        assert!((fit.calib_b - TRUE_B).abs() < 1e-9, "{}", fit.calib_b);
+        assert!((fit.r_squared - 1.0).abs() < 1e-12, "{}", fit.r_squared);
+        assert!(fit.covariance.is_some());
+    }
+
+    #[test]
+    fn recovers_power_law_with_noise() {
+        let fit = fit_power_law(&points(&[0.002, -0.0016, 0.0008, -0.0024, 0.0014, -0.0006])).unwrap();
+        assert!(fit.converged, "{:?}", fit);
+        assert!((fit.calib_a - TRUE_A).abs() < 0.05 * TRUE_A, "{}", fit.calib_a);
+        assert!((fit.calib_b - TRUE_B).abs() < 0.01, "{}", fit.calib_b);
+        assert!(fit.r_squared > 0.9999, "{}", fit.r_squared);
+        let covariance = fit.covariance.unwrap();
+        assert!(covariance[0][0] > 0.0 && covariance[1][1] > 0.0, "{:?}", covariance);
+        // A dan B sangat berkorelasi; galat tetap dalam beberapa simpangan baku yang dilaporkan.
+        assert!((fit.calib_a - TRUE_A).abs() < 5.0 * covariance[0][0].sqrt(), "{} ± {}", fit.calib_a, covariance[0][0].sqrt());
+        assert!((fit.calib_b - TRUE_B).abs() < 5.0 * covariance[1][1].sqrt(), "{} ± {}", fit.calib_b, covariance[1][1].sqrt());
+        assert_eq!(covariance[0][1], covariance[1][0]);
+    }
+
+    #[test]
+    fn rejects_too_few_points() {
+        assert!(fit_power_law(&points(&[0.0])[..2]).is_err());
+    }
+}
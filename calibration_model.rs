@@ -0,0 +1,235 @@
+// src/calibration_model.rs
+// Model kalibrasi V_out = g(lux) photodiode + penguat. Solver mencari lux dari g(lux) - V_out = 0,
+// jadi setiap model menyediakan turunan, dan invers langsung bila ada.
+
+use serde::{Deserialize, Serialize};
+
+use crate::adc::AdcSettings;
+use crate::measurements::{Measurements, SolveStatus};
+
+// Batas atas pencarian lux: 10× sinar matahari langsung (~100.000 lux) sebagai ruang untuk kalibrasi yang meleset.
+pub const MAX_LUX_BRACKET: f64 = 1_000_000.0;
+
+pub trait CalibrationModel {
+    fn voltage(&self, lux: f64) -> f64;
+
+    fn derivative(&self, lux: f64) -> f64;
+
+    fn second_derivative(&self, lux: f64) -> f64;
+
+    // Invers numerik pada [0, MAX_LUX_BRACKET]; model dengan rumus tertutup menimpanya.
+    fn inverse(&self, v_out: f64) -> Option<f64> {
+        if !v_out.is_finite() {
+            return None;
+        }
+        let f = |lux: f64| self.voltage(lux) - v_out;
+        let upper = Measurements::expand_upper_bracket(f, 0.0, 1.0, MAX_LUX_BRACKET)?;
+        let result = Measurements::safeguarded_newton(f, |lux| self.derivative(lux), 0.0, upper, 0.5 * upper, 1e-9, 200);
+        (result.status == SolveStatus::Converged).then_some(result.root)
+    }
+}
+
+// V = A · lux^B
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PowerLawModel {
+    pub a: f64,
+    pub b: f64,
+}
+
+impl CalibrationModel for PowerLawModel {
+    fn voltage(&self, lux: f64) -> f64 {
+        self.a * lux.powf(self.b)
+    }
+
+    fn derivative(&self, lux: f64) -> f64 {
+        self.a * self.b * lux.powf(self.b - 1.0)
+    }
+
+    fn second_derivative(&self, lux: f64) -> f64 {
+        self.a * self.b * (self.b - 1.0) * lux.powf(self.b - 2.0)
+    }
+
+    fn inverse(&self, v_out: f64) -> Option<f64> {
+        if self.a <= 0.0 || self.b == 0.0 || v_out < 0.0 || !v_out.is_finite() {
+            return None;
+        }
+        let lux = (v_out / self.a).powf(1.0 / self.b);
+        lux.is_finite().then_some(lux)
+    }
+}
+
+// V = offset + slope · ln(1 + lux); 1 + lux agar model tetap terdefinisi pada gelap total.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogarithmicModel {
+    pub offset: f64,
+    pub slope: f64,
+}
+
+impl CalibrationModel for LogarithmicModel {
+    fn voltage(&self, lux: f64) -> f64 {
+        self.offset + self.slope * lux.ln_1p()
+    }
+
+    fn derivative(&self, lux: f64) -> f64 {
+        self.slope / (1.0 + lux)
+    }
+
+    fn second_derivative(&self, lux: f64) -> f64 {
+        -self.slope / (1.0 + lux).powi(2)
+    }
+
+    fn inverse(&self, v_out: f64) -> Option<f64> {
+        if self.slope == 0.0 || !v_out.is_finite() {
+            return None;
+        }
+        let lux = ((v_out - self.offset) / self.slope).exp_m1();
+        (lux.is_finite() && lux >= 0.0).then_some(lux)
+    }
+}
+
+// V = c0 + c1·lux + c2·lux² + ...; tidak punya invers umum sehingga memakai invers numerik.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolynomialModel {
+    pub coefficients: Vec<f64>,
+}
+
+impl CalibrationModel for PolynomialModel {
+    fn voltage(&self, lux: f64) -> f64 {
+        self.coefficients.iter().rev().fold(0.0, |acc, c| acc * lux + c)
+    }
+
+    fn derivative(&self, lux: f64) -> f64 {
+        self.coefficients.iter().enumerate().skip(1).rev()
+            .fold(0.0, |acc, (i, c)| acc * lux + i as f64 * c)
+    }
+
+    fn second_derivative(&self, lux: f64) -> f64 {
+        self.coefficients.iter().enumerate().skip(2).rev()
+            .fold(0.0, |acc, (i, c)| acc * lux + (i * (i - 1)) as f64 * c)
+    }
+}
+
+// Tabel titik (lux, V) yang diinterpolasi linear; di luar tabel memakai segmen ujung.
+// Titik harus urut naik menurut lux dan V agar inversnya tunggal.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PiecewiseModel {
+    pub points: Vec<(f64, f64)>,
+}
+
+impl PiecewiseModel {
+    // Indeks segmen [i, i+1] yang dipakai untuk `x` pada kolom `axis` (0 = lux, 1 = V).
+    fn segment(&self, x: f64, axis: usize) -> Option<usize> {
+        if self.points.len() < 2 {
+            return None;
+        }
+        let value = |point: &(f64, f64)| if axis == 0 { point.0 } else { point.1 };
+        let upper = self.points.partition_point(|point| value(point) <= x);
+        Some(upper.clamp(1, self.points.len() - 1) - 1)
+    }
+
+    fn slope(&self, i: usize) -> f64 {
+        let ((x0, y0), (x1, y1)) = (self.points[i], self.points[i + 1]);
+        (y1 - y0) / (x1 - x0)
+    }
+}
+
+impl CalibrationModel for PiecewiseModel {
+    fn voltage(&self, lux: f64) -> f64 {
+        match self.segment(lux, 0) {
+            Some(i) => self.points[i].1 + self.slope(i) * (lux - self.points[i].0),
+            None => f64::NAN,
+        }
+    }
+
+    fn derivative(&self, lux: f64) -> f64 {
+        self.segment(lux, 0).map(|i| self.slope(i)).unwrap_or(f64::NAN)
+    }
+
+    fn second_derivative(&self, _lux: f64) -> f64 {
+        0.0
+    }
+
+    fn inverse(&self, v_out: f64) -> Option<f64> {
+        let i = self.segment(v_out, 1)?;
+        let slope = self.slope(i);
+        if slope <= 0.0 || !slope.is_finite() {
+            return None;
+        }
+        let lux = self.points[i].0 + (v_out - self.points[i].1) / slope;
+        (lux.is_finite() && lux >= 0.0).then_some(lux)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CalibrationModelKind {
+    PowerLaw,
+    Logarithmic,
+    Polynomial,
+    Piecewise,
+}
+
+impl CalibrationModelKind {
+    pub const ALL: [CalibrationModelKind; 4] = [
+        CalibrationModelKind::PowerLaw,
+        CalibrationModelKind::Logarithmic,
+        CalibrationModelKind::Polynomial,
+        CalibrationModelKind::Piecewise,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CalibrationModelKind::PowerLaw => "Power Law (A·lux^B)",
+            CalibrationModelKind::Logarithmic => "Logaritmik (C + D·ln(1+lux))",
+            CalibrationModelKind::Polynomial => "Polinomial",
+            CalibrationModelKind::Piecewise => "Tabel Linear Sepotong-sepotong",
+        }
+    }
+}
+
+// Parameter semua model disimpan sekaligus agar berpindah model di UI tidak menghapus isian sebelumnya.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationSettings {
+    // Profil lama yang tersimpan sebelum ada pengaturan ADC memakai nilai bawaan.
+    #[serde(default = "AdcSettings::new")]
+    pub adc: AdcSettings,
+    pub kind: CalibrationModelKind,
+    pub power_law: PowerLawModel,
+    // Kovarians [[var A, cov AB], [cov AB, var B]] dari fit; `None` bila A dan B diisi manual.
+    #[serde(default)]
+    pub power_law_covariance: Option<[[f64; 2]; 2]>,
+    pub logarithmic: LogarithmicModel,
+    pub polynomial: PolynomialModel,
+    pub piecewise: PiecewiseModel,
+}
+
+impl CalibrationSettings {
+    pub fn new() -> Self {
+        Self {
+            adc: AdcSettings::new(),
+            kind: CalibrationModelKind::PowerLaw,
+            power_law: PowerLawModel { a: 0.0001, b: 1.05 },
+            power_law_covariance: None,
+            logarithmic: LogarithmicModel { offset: 0.0, slope: 0.3 },
+            polynomial: PolynomialModel { coefficients: vec![0.0, 0.0001, 0.0] },
+            piecewise: PiecewiseModel { points: vec![(0.0, 0.0), (1000.0, 0.14), (10000.0, 1.6), (30000.0, 3.3)] },
+        }
+    }
+
+    pub fn model(&self) -> &dyn CalibrationModel {
+        match self.kind {
+            CalibrationModelKind::PowerLaw => &self.power_law,
+            CalibrationModelKind::Logarithmic => &self.logarithmic,
+            CalibrationModelKind::Polynomial => &self.polynomial,
+            CalibrationModelKind::Piecewise => &self.piecewise,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            CalibrationModelKind::PowerLaw => format!("V = {} · lux^{}", self.power_law.a, self.power_law.b),
+            CalibrationModelKind::Logarithmic => format!("V = {} + {} · ln(1 + lux)", self.logarithmic.offset, self.logarithmic.slope),
+            CalibrationModelKind::Polynomial => format!("V = Σ cᵢ·luxⁱ, c = {:?}", self.polynomial.coefficients),
+            CalibrationModelKind::Piecewise => format!("Tabel {} titik (lux, V)", self.piecewise.points.len()),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// src/calibration_profile.rs
+// Profil kalibrasi bernama dan berversi. Profil yang sudah disimpan tidak diubah: menyimpan ulang dengan
+// nama yang sama membuat versi baru, sehingga hasil lux lama tetap bisa ditelusuri ke kalibrasinya.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::calibration_model::CalibrationSettings;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FitStatistics {
+    pub r_squared: f64,
+    pub rmse: f64,
+    pub point_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationProfile {
+    // "<sensor>/<nama>/v<versi>", disimpan bersama setiap hasil lux yang dihitung dengan profil ini.
+    pub id: String,
+    pub name: String,
+    pub version: u32,
+    pub sensor_id: String,
+    pub created_at: DateTime<Utc>,
+    pub reference_instrument: String,
+    pub settings: CalibrationSettings,
+    pub fit: Option<FitStatistics>,
+    pub notes: String,
+}
+
+impl CalibrationProfile {
+    // Versi berikutnya dihitung dari profil bernama sama untuk sensor yang sama.
+    pub fn new(name: &str, sensor_id: &str, settings: CalibrationSettings, existing: &[CalibrationProfile]) -> Self {
+        let version = existing.iter()
+            .filter(|profile| profile.sensor_id == sensor_id && profile.name == name)
+            .map(|profile| profile.version)
+            .max()
+            .unwrap_or(0) + 1;
+        Self {
+            id: format!("{}/{}/v{}", sensor_id, name, version),
+            name: name.to_string(),
+            version,
+            sensor_id: sensor_id.to_string(),
+            created_at: Utc::now(),
+            reference_instrument: String::new(),
+            settings,
+            fit: None,
+            notes: String::new(),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// src/cli.rs
+// Perintah tanpa GUI, mis. `alprog export --output data.csv --from 2025-01-01 --to 2025-01-31`
+// atau `alprog import --input log.csv --sensor COM4 --source sd-card --raw-col 2`.
+
+use std::path::PathBuf;
+
+use crate::csv_import::{self, ImportRow, ImportSettings};
+use crate::export::{self, DecimalSeparator, ExportColumn, ExportFormat, ExportOptions};
+use crate::storage::{self, StorageBackend};
+
+const EXPORT_USAGE: &str = "Penggunaan: alprog export --output <file|folder> [--format csv|jsonl|parquet] [--from YYYY-MM-DD] [--to YYYY-MM-DD]
+    [--sensor <id>] [--series photodiode,lux] [--columns timestamp,sensor_id,source,series,value,iterations]
+    [--tz +07:00] [--decimal dot|comma] [--storage mongodb|sqlite] [--sqlite-path <file>]";
+
+const IMPORT_USAGE: &str = "Penggunaan: alprog import --input <file.csv> --sensor <id> --source <label>
+    [--timestamp-col <n>] [--raw-col <n>] [--lux-col <n>] (nomor kolom mulai 1)
+    [--delimiter ,|;|tab] [--no-header] [--decimal dot|comma] [--time-format <strftime>] [--tz +07:00]
+    [--storage mongodb|sqlite] [--sqlite-path <file>]";
+
+// `None` berarti tidak ada subcommand dan GUI dijalankan seperti biasa.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+    let result = match command.as_str() {
+        "export" => run_export(&args[1..]),
+        "import" => run_import(&args[1..]),
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(message) => {
+            println!("{}", message);
+            0
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    })
+}
+
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn run_export(args: &[String]) -> Result<String, String> {
+    let output = option_value(args, "--output").ok_or_else(|| EXPORT_USAGE.to_string())?;
+    let mut options = ExportOptions::new();
+
+    if let Some(format) = option_value(args, "--format") {
+        options.format = ExportFormat::from_key(format)
+            .ok_or_else(|| format!("Format ekspor tidak dikenal: '{}'", format))?;
+    }
+    if let Some(tz) = option_value(args, "--tz") {
+        options.utc_offset = export::parse_utc_offset(tz)?;
+    }
+    options.from = export::parse_date(option_value(args, "--from").unwrap_or(""), &options.utc_offset, false)?;
+    options.to = export::parse_date(option_value(args, "--to").unwrap_or(""), &options.utc_offset, true)?;
+    options.sensor_id = option_value(args, "--sensor").map(str::to_string);
+
+    if let Some(series) = option_value(args, "--series") {
+        options.series = series.split(',')
+            .map(|key| export::series_from_key(key.trim()).ok_or_else(|| format!("Jenis data tidak dikenal: '{}'", key)))
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(columns) = option_value(args, "--columns") {
+        options.columns = columns.split(',')
+            .map(|key| ExportColumn::from_key(key.trim()).ok_or_else(|| format!("Kolom tidak dikenal: '{}'", key)))
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(decimal) = option_value(args, "--decimal") {
+        options.decimal_separator = match decimal {
+            "dot" | "." => DecimalSeparator::Dot,
+            "comma" | "," => DecimalSeparator::Comma,
+            other => return Err(format!("Pemisah desimal tidak dikenal: '{}'", other)),
+        };
+    }
+
+    let backend = StorageBackend::from_args(args)?;
+    let storage = storage::open_storage(&backend)
+        .map_err(|e| format!("GAGAL membuka penyimpanan {}: {}", backend.label(), e))?;
+
+    // Tegangan Parquet memakai pengaturan ADC dari profil kalibrasi yang terakhir diaktifkan.
+    let profiles = storage.list_calibration_profiles(None)
+        .map_err(|e| format!("GAGAL memuat profil kalibrasi: {}", e))?;
+    for (sensor_id, profile_id) in storage.active_calibration_profiles().map_err(|e| format!("GAGAL memuat profil kalibrasi aktif: {}", e))? {
+        if let Some(profile) = profiles.iter().find(|profile| profile.id == profile_id) {
+            options.adc_by_sensor.insert(sensor_id, profile.settings.adc.clone());
+        }
+    }
+
+    let path = PathBuf::from(output);
+    let count = export::export_to_path(storage.as_ref(), &path, &options)
+        .map_err(|e| format!("GAGAL mengekspor data: {}", e))?;
+    Ok(format!("{} baris diekspor ke {}", count, path.display()))
+}
+
+fn column_index(args: &[String], name: &str) -> Result<Option<usize>, String> {
+    match option_value(args, name) {
+        Some(text) => match text.parse::<usize>() {
+            Ok(n) if n >= 1 => Ok(Some(n - 1)),
+            _ => Err(format!("{} harus nomor kolom mulai dari 1: '{}'", name, text)),
+        },
+        None => Ok(None),
+    }
+}
+
+fn run_import(args: &[String]) -> Result<String, String> {
+    let input = option_value(args, "--input").ok_or_else(|| IMPORT_USAGE.to_string())?;
+    let sensor_id = option_value(args, "--sensor").ok_or_else(|| IMPORT_USAGE.to_string())?;
+    let source = option_value(args, "--source").ok_or_else(|| IMPORT_USAGE.to_string())?;
+
+    let mut settings = ImportSettings::new();
+    settings.delimiter = match option_value(args, "--delimiter").unwrap_or(",") {
+        "," => b',',
+        ";" => b';',
+        "tab" | "\\t" => b'\t',
+        other => return Err(format!("Pemisah kolom tidak dikenal: '{}'", other)),
+    };
+    settings.has_header = !args.iter().any(|arg| arg == "--no-header");
+    settings.decimal_comma = option_value(args, "--decimal") == Some("comma");
+    if let Some(format) = option_value(args, "--time-format") {
+        settings.timestamp_format = format.to_string();
+    }
+    if let Some(tz) = option_value(args, "--tz") {
+        settings.utc_offset = export::parse_utc_offset(tz)?;
+    }
+    settings.mapping.timestamp = column_index(args, "--timestamp-col")?.unwrap_or(0);
+    settings.mapping.raw_value = column_index(args, "--raw-col")?;
+    settings.mapping.lux = column_index(args, "--lux-col")?;
+    if settings.mapping.raw_value.is_none() && settings.mapping.lux.is_none() {
+        return Err("Tentukan minimal --raw-col atau --lux-col.".to_string());
+    }
+
+    let parsed = csv_import::read_csv(&PathBuf::from(input), settings.delimiter, settings.has_header, None)?;
+    let validated = csv_import::validate(&parsed, &settings);
+    let mut rows: Vec<ImportRow> = Vec::new();
+    for row in validated {
+        match row.result {
+            Ok(import_row) => rows.push(import_row),
+            Err(e) => eprintln!("Baris {} dilewati: {}", row.line, e),
+        }
+    }
+    if rows.is_empty() {
+        return Err("Tidak ada baris valid untuk diimpor.".to_string());
+    }
+
+    let backend = StorageBackend::from_args(args)?;
+    let storage = storage::open_storage(&backend)
+        .map_err(|e| format!("GAGAL membuka penyimpanan {}: {}", backend.label(), e))?;
+    let count = storage.insert_imported(sensor_id, source, &rows)
+        .map_err(|e| format!("GAGAL mengimpor data: {}", e))?;
+    Ok(format!("{} baris valid diimpor ({} dokumen) dari {} dengan sumber '{}'", rows.len(), count, input, source))
+}
@@ -0,0 +1,156 @@
+// src/conversion.rs
+// Konversi nilai photodiode ke lux: Newton-Raphson, invers langsung model kalibrasi (rumus tertutup
+// bila ada), atau tabel lookup (LUT) per nilai ADC. NR tetap tersedia sebagai jalur validasi.
+
+use crate::calibration_model::CalibrationSettings;
+use crate::solvers::{LuxProblem, SolverMethod};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConversionMode {
+    NewtonRaphson,
+    Analytic,
+    LookupTable,
+}
+
+impl ConversionMode {
+    pub const ALL: [ConversionMode; 3] = [
+        ConversionMode::NewtonRaphson,
+        ConversionMode::Analytic,
+        ConversionMode::LookupTable,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConversionMode::NewtonRaphson => "Newton-Raphson (Iteratif)",
+            ConversionMode::Analytic => "Invers Langsung Model",
+            ConversionMode::LookupTable => "Tabel Lookup (LUT)",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LuxLookupTable {
+    pub settings: CalibrationSettings,
+    values: Vec<Option<f64>>,
+}
+
+impl LuxLookupTable {
+    pub fn new(settings: &CalibrationSettings) -> Self {
+        let values = (0..settings.adc.levels())
+            .map(|adc| settings.model().inverse(settings.adc.to_voltage(adc as f64)))
+            .collect();
+        Self { settings: settings.clone(), values }
+    }
+
+    pub fn matches(&self, settings: &CalibrationSettings) -> bool {
+        self.settings == *settings
+    }
+
+    // Nilai photodiode pecahan diinterpolasi linear di antara dua entri tabel.
+    pub fn lookup(&self, photodiode_value: f64) -> Option<f64> {
+        if !photodiode_value.is_finite() || photodiode_value < 0.0 || photodiode_value > self.settings.adc.max_value() {
+            return None;
+        }
+        let lower = photodiode_value.floor() as usize;
+        let upper = (lower + 1).min(self.values.len() - 1);
+        let fraction = photodiode_value - lower as f64;
+        let (lux_lower, lux_upper) = (self.values[lower]?, self.values[upper]?);
+        Some(lux_lower + fraction * (lux_upper - lux_lower))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Discrepancy {
+    pub max_abs: f64,
+    pub max_relative: f64,
+    // Nilai photodiode tempat selisih absolut terbesar terjadi.
+    pub at_photodiode_value: f64,
+}
+
+impl Discrepancy {
+    fn new() -> Self {
+        Self { max_abs: 0.0, max_relative: 0.0, at_photodiode_value: 0.0 }
+    }
+
+    fn update(&mut self, photodiode_value: f64, reference: f64, candidate: f64) {
+        let abs = (candidate - reference).abs();
+        if abs > self.max_abs {
+            self.max_abs = abs;
+            self.at_photodiode_value = photodiode_value;
+        }
+        if reference > 0.0 {
+            self.max_relative = self.max_relative.max(abs / reference);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CrossValidation {
+    pub nr_vs_inverse: Discrepancy,
+    // Diuji di tengah dua entri tabel, tempat error interpolasi paling besar.
+    pub lut_vs_inverse: Discrepancy,
+    pub checked_points: usize,
+    // Nilai photodiode yang gagal dihitung salah satu metode.
+    pub failures: Vec<f64>,
+}
+
+// Membandingkan ketiga mode pada seluruh rentang ADC tabel dengan invers langsung model sebagai acuan.
+pub fn cross_validate(lux_problem: &LuxProblem, method: SolverMethod, table: &LuxLookupTable) -> CrossValidation {
+    let mut validation = CrossValidation {
+        nr_vs_inverse: Discrepancy::new(),
+        lut_vs_inverse: Discrepancy::new(),
+        checked_points: 0,
+        failures: Vec::new(),
+    };
+
+    let adc_settings = &table.settings.adc;
+    for adc in 0..adc_settings.levels() {
+        for photodiode_value in [adc as f64, adc as f64 + 0.5] {
+            if photodiode_value > adc_settings.max_value() {
+                continue;
+            }
+            let v_out = adc_settings.to_voltage(photodiode_value);
+            let Some(reference) = lux_problem.model.inverse(v_out) else {
+                validation.failures.push(photodiode_value);
+                continue;
+            };
+
+            let problem = LuxProblem { v_out, ..*lux_problem };
+            let solve_result = problem.solve(method);
+            match (solve_result.is_converged(), table.lookup(photodiode_value)) {
+                (true, Some(lut_lux)) => {
+                    validation.nr_vs_inverse.update(photodiode_value, reference, solve_result.root);
+                    validation.lut_vs_inverse.update(photodiode_value, reference, lut_lux);
+                    validation.checked_points += 1;
+                },
+                _ => validation.failures.push(photodiode_value),
+            }
+        }
+    }
+    validation
+}
+
+// Validasi silang untuk satu sensor, dijalankan di thread latar belakang karena membangun LUT dan
+// menyelesaikan dua titik per level ADC.
+#[derive(Clone, Debug)]
+pub struct CrossValidationRequest {
+    pub settings: CalibrationSettings,
+    pub method: SolverMethod,
+    pub x0: f64,
+    pub tol: f64,
+    pub max_iter: usize,
+}
+
+impl CrossValidationRequest {
+    pub fn run(&self) -> CrossValidation {
+        let table = LuxLookupTable::new(&self.settings);
+        let lux_problem = LuxProblem {
+            model: self.settings.model(),
+            v_out: 0.0,
+            x0: self.x0,
+            tol: self.tol,
+            max_iter: self.max_iter,
+        };
+        cross_validate(&lux_problem, self.method, &table)
+    }
+}
@@ -0,0 +1,171 @@
+// src/crop.rs
+// Profil tanaman dengan target cahaya per tahap pertumbuhan, dan bedengan (tanggal tanam per bedengan)
+// untuk menentukan tahap yang sedang berjalan.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GrowthStage {
+    Germination,
+    Seedling,
+    Vegetative,
+    PreHarvest,
+}
+
+impl GrowthStage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GrowthStage::Germination => "Perkecambahan",
+            GrowthStage::Seedling => "Semai",
+            GrowthStage::Vegetative => "Vegetatif",
+            GrowthStage::PreHarvest => "Menjelang Panen",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TargetStatus {
+    Below,
+    Within,
+    Above,
+}
+
+impl TargetStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TargetStatus::Below => "Di Bawah Target",
+            TargetStatus::Within => "Sesuai Target",
+            TargetStatus::Above => "Di Atas Target",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TargetRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl TargetRange {
+    pub const fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+
+    pub fn evaluate(&self, value: f64) -> TargetStatus {
+        if value < self.min {
+            TargetStatus::Below
+        } else if value > self.max {
+            TargetStatus::Above
+        } else {
+            TargetStatus::Within
+        }
+    }
+
+    pub fn describe(&self, unit: &str) -> String {
+        format!("{}–{} {}", self.min, self.max, unit)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageTargets {
+    pub stage: GrowthStage,
+    // Hari setelah tanam saat tahap ini dimulai.
+    pub start_day: u32,
+    pub lux: TargetRange,
+    // µmol/m²/s.
+    pub ppfd: TargetRange,
+    // mol/m²/hari.
+    pub dli: TargetRange,
+    // Jam terang per hari.
+    pub photoperiod_hours: TargetRange,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CropProfile {
+    pub name: &'static str,
+    pub scientific_name: &'static str,
+    // Terurut menurut `start_day`.
+    pub stages: Vec<StageTargets>,
+}
+
+impl CropProfile {
+    // Selada daun greenhouse. DLI dibatasi ~20 mol/m²/hari karena cahaya berlebih memicu tipburn;
+    // rentang lux setara PPFD untuk sinar matahari (~54 lux per µmol/m²/s).
+    pub fn lettuce() -> Self {
+        Self {
+            name: "Selada",
+            scientific_name: "Lactuca sativa",
+            stages: vec![
+                StageTargets {
+                    stage: GrowthStage::Germination,
+                    start_day: 0,
+                    lux: TargetRange::new(2_700.0, 8_000.0),
+                    ppfd: TargetRange::new(50.0, 150.0),
+                    dli: TargetRange::new(2.0, 6.0),
+                    photoperiod_hours: TargetRange::new(12.0, 24.0),
+                },
+                StageTargets {
+                    stage: GrowthStage::Seedling,
+                    start_day: 4,
+                    lux: TargetRange::new(8_000.0, 13_500.0),
+                    ppfd: TargetRange::new(150.0, 250.0),
+                    dli: TargetRange::new(8.0, 12.0),
+                    photoperiod_hours: TargetRange::new(16.0, 18.0),
+                },
+                StageTargets {
+                    stage: GrowthStage::Vegetative,
+                    start_day: 15,
+                    lux: TargetRange::new(13_500.0, 21_500.0),
+                    ppfd: TargetRange::new(250.0, 400.0),
+                    dli: TargetRange::new(14.0, 17.0),
+                    photoperiod_hours: TargetRange::new(16.0, 18.0),
+                },
+                StageTargets {
+                    stage: GrowthStage::PreHarvest,
+                    start_day: 36,
+                    lux: TargetRange::new(16_000.0, 24_000.0),
+                    ppfd: TargetRange::new(300.0, 450.0),
+                    dli: TargetRange::new(17.0, 20.0),
+                    photoperiod_hours: TargetRange::new(14.0, 16.0),
+                },
+            ],
+        }
+    }
+
+    pub fn all() -> Vec<CropProfile> {
+        vec![CropProfile::lettuce()]
+    }
+
+    pub fn find(name: &str) -> Option<CropProfile> {
+        Self::all().into_iter().find(|crop| crop.name == name)
+    }
+
+    // Tahap untuk hari setelah tanam; sebelum tanggal tanam dianggap perkecambahan.
+    pub fn stage_for(&self, days_after_planting: i64) -> &StageTargets {
+        self.stages.iter()
+            .rev()
+            .find(|stage| days_after_planting >= stage.start_day as i64)
+            .unwrap_or(&self.stages[0])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bed {
+    // Nama bedengan, unik.
+    pub id: String,
+    pub sensor_id: String,
+    // `CropProfile::name`.
+    pub crop: String,
+    pub planting_date: NaiveDate,
+}
+
+impl Bed {
+    pub fn days_after_planting(&self, today: NaiveDate) -> i64 {
+        (today - self.planting_date).num_days()
+    }
+
+    pub fn crop_profile(&self) -> Option<CropProfile> {
+        CropProfile::find(&self.crop)
+    }
+}
@@ -0,0 +1,166 @@
+// src/csv_import.rs
+// Impor log lama (lux meter genggam, logger SD card Arduino) dari file CSV ke penyimpanan.
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use std::path::Path;
+
+pub const PREVIEW_ROWS: usize = 20;
+// Pratinjau di GUI hanya membaca dan memvalidasi baris awal; file lengkap diproses di thread impor.
+pub const PREVIEW_READ_ROWS: usize = 500;
+
+// Dicoba berurutan bila format waktu tidak diisi.
+const TIMESTAMP_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%d-%m-%Y %H:%M:%S",
+];
+
+#[derive(Clone, Debug)]
+pub struct ImportRow {
+    pub timestamp: DateTime<Utc>,
+    pub raw_value: Option<f64>,
+    pub lux: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnMapping {
+    pub timestamp: usize,
+    pub raw_value: Option<usize>,
+    pub lux: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportSettings {
+    pub delimiter: u8,
+    pub has_header: bool,
+    pub decimal_comma: bool,
+    // Kosong berarti deteksi otomatis dari `TIMESTAMP_FORMATS`, RFC 3339 atau Unix timestamp (detik).
+    pub timestamp_format: String,
+    pub utc_offset: FixedOffset,
+    pub mapping: ColumnMapping,
+}
+
+impl ImportSettings {
+    pub fn new() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            decimal_comma: false,
+            timestamp_format: String::new(),
+            utc_offset: FixedOffset::east_opt(7 * 3600).unwrap(), // WIB
+            mapping: ColumnMapping { timestamp: 0, raw_value: Some(1), lux: None },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ParsedCsv {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidatedRow {
+    pub line: usize,
+    pub result: Result<ImportRow, String>,
+}
+
+// `max_rows` membatasi jumlah baris data yang dibaca (untuk pratinjau); `None` membaca seluruh file.
+pub fn read_csv(path: &Path, delimiter: u8, has_header: bool, max_rows: Option<usize>) -> Result<ParsedCsv, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(has_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| format!("GAGAL membuka {}: {}", path.display(), e))?;
+
+    let mut parsed = ParsedCsv::default();
+    if has_header {
+        parsed.headers = reader.headers()
+            .map_err(|e| format!("GAGAL membaca header: {}", e))?
+            .iter()
+            .map(str::to_string)
+            .collect();
+    }
+    for record in reader.records().take(max_rows.unwrap_or(usize::MAX)) {
+        let record = record.map_err(|e| format!("GAGAL membaca baris CSV: {}", e))?;
+        parsed.rows.push(record.iter().map(str::to_string).collect());
+    }
+
+    if !has_header {
+        let width = parsed.rows.iter().map(Vec::len).max().unwrap_or(0);
+        parsed.headers = (1..=width).map(|i| format!("Kolom {}", i)).collect();
+    }
+    Ok(parsed)
+}
+
+fn parse_number(text: &str, decimal_comma: bool) -> Result<Option<f64>, String> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let normalized = if decimal_comma { text.replace('.', "").replace(',', ".") } else { text.to_string() };
+    let value: f64 = normalized.parse().map_err(|_| format!("bukan angka: '{}'", text))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("nilai tidak valid: '{}'", text));
+    }
+    Ok(Some(value))
+}
+
+pub fn parse_timestamp(text: &str, format: &str, offset: &FixedOffset) -> Result<DateTime<Utc>, String> {
+    let local = |naive: NaiveDateTime| offset.from_local_datetime(&naive).single().map(|dt| dt.with_timezone(&Utc));
+
+    if !format.trim().is_empty() {
+        return NaiveDateTime::parse_from_str(text, format.trim())
+            .ok()
+            .and_then(local)
+            .ok_or_else(|| format!("waktu '{}' tidak sesuai format '{}'", text, format.trim()));
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(seconds) = text.parse::<i64>() {
+        return Utc.timestamp_opt(seconds, 0).single().ok_or_else(|| format!("Unix timestamp tidak valid: '{}'", text));
+    }
+    TIMESTAMP_FORMATS.iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(text, fmt).ok())
+        .and_then(local)
+        .ok_or_else(|| format!("format waktu tidak dikenali: '{}'", text))
+}
+
+fn validate_row(row: &[String], settings: &ImportSettings, now: DateTime<Utc>) -> Result<ImportRow, String> {
+    let cell = |index: usize| row.get(index).map(String::as_str).unwrap_or("");
+
+    let timestamp = parse_timestamp(cell(settings.mapping.timestamp), &settings.timestamp_format, &settings.utc_offset)?;
+    if timestamp > now {
+        return Err(format!("waktu berada di masa depan: {}", timestamp));
+    }
+
+    let raw_value = match settings.mapping.raw_value {
+        Some(index) => parse_number(cell(index), settings.decimal_comma)?,
+        None => None,
+    };
+    let lux = match settings.mapping.lux {
+        Some(index) => parse_number(cell(index), settings.decimal_comma)?,
+        None => None,
+    };
+    if raw_value.is_none() && lux.is_none() {
+        return Err("nilai photodiode dan lux kosong".to_string());
+    }
+
+    Ok(ImportRow { timestamp, raw_value, lux })
+}
+
+pub fn validate(parsed: &ParsedCsv, settings: &ImportSettings) -> Vec<ValidatedRow> {
+    let now = Utc::now();
+    // Nomor baris mengikuti file asli (baris 1 adalah header bila ada).
+    let first_line = if settings.has_header { 2 } else { 1 };
+    parsed.rows.iter().enumerate().map(|(i, row)| ValidatedRow {
+        line: first_line + i,
+        result: validate_row(row, settings, now),
+    }).collect()
+}
@@ -1,12 +1,59 @@
-use egui::{Color32, RichText, Ui}; 
-use egui_plot::{Line, Plot, PlotPoints, Legend}; 
-use crate::measurements::Measurements; 
+use egui::{Color32, Grid, RichText, Ui}; 
+use egui_plot::{Bar, BarChart, HLine, Line, LineStyle, MarkerShape, Plot, PlotPoints, Points, Legend, Polygon, VLine}; 
+use crate::dli::{DailyLightIntegral, DliSettings, IntegrationMethod};
+use crate::flicker::{self, FlickerRisk, FlickerSettings, WindowFunction};
+use crate::forecast::{DliProjection, Forecast, ForecastMethod, ForecastSettings};
+use crate::measurements::{Measurements, Value}; 
+use crate::storage::{SeriesKind, StoredRecord};
+use crate::export::source_label;
+use crate::recompute;
+use crate::uncertainty::ConfidenceInterval;
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex}; 
 
 
 pub struct DataGraphicsScreen {
     pub measurements: Arc<Mutex<Measurements>>, 
     pub newton_raphson_lux_measurements: Arc<Mutex<Measurements>>, 
+    // Interval 95% untuk setiap titik lux live (x sama dengan titik lux); `None` memutus pita.
+    lux_intervals: Vec<(f64, Option<ConfidenceInterval>)>,
+    max_data_points: usize,
+    ppfd_measurements: Measurements,
+    // Nilai photodiode setelah rantai filter, digambar bersama nilai mentah.
+    filtered_measurements: Measurements,
+    // Titik yang ditandai detektor outlier: nilai mentah photodiode dan lux-nya.
+    outlier_points: Vec<[f64; 2]>,
+    outlier_lux_points: Vec<[f64; 2]>,
+    // Lux sesaat dari nilai mentah (tanpa filter) untuk FFT; kapasitas lebih besar dari grafik biasa.
+    flicker_measurements: Measurements,
+    // x sampel flicker berasal dari jam perangkat (`micros,nilai`), bukan waktu terima di host.
+    flicker_device_timestamps: bool,
+    pub flicker_settings: FlickerSettings,
+
+    pub history: Arc<Mutex<Vec<StoredRecord>>>,
+    pub history_kind: SeriesKind,
+    pub history_days: u32,
+    pub history_request: bool,
+    // Riwayat lux ditampilkan sebagai PPFD tersimpan (µmol/m²/s).
+    pub history_show_ppfd: bool,
+    // Set hasil hitung ulang yang dibandingkan dengan lux live; `None` menampilkan semua sumber.
+    pub comparison_result_set: Option<String>,
+
+    pub dli_settings: DliSettings,
+    // DLI hari berjalan dari data live, diisi main.rs setiap ada PPFD baru.
+    pub running_dli: Option<DailyLightIntegral>,
+    // Total harian selesai, dari database atau hasil hitung ulang riwayat.
+    pub daily_light_integrals: Arc<Mutex<Vec<DailyLightIntegral>>>,
+    pub dli_days: u32,
+    pub dli_load_request: bool,
+    pub dli_recompute_request: bool,
+    pub dli_status: Arc<Mutex<String>>,
+
+    pub forecast_settings: ForecastSettings,
+    pub forecast: Arc<Mutex<Option<Forecast>>>,
+    pub dli_projection: Arc<Mutex<Option<DliProjection>>>,
+    pub forecast_request: bool,
+    pub forecast_status: Arc<Mutex<String>>,
 }
 
 impl DataGraphicsScreen {
@@ -22,12 +69,500 @@ impl DataGraphicsScreen {
         Self {
             measurements: measurements,
             newton_raphson_lux_measurements: newton_raphson_lux_measurements,
+            lux_intervals: Vec::new(),
+            max_data_points,
+            ppfd_measurements: {
+                let mut ppfd_measurements = Measurements::new();
+                ppfd_measurements.set_max_data_points(max_data_points);
+                ppfd_measurements
+            },
+            filtered_measurements: {
+                let mut filtered_measurements = Measurements::new();
+                filtered_measurements.set_max_data_points(max_data_points);
+                filtered_measurements
+            },
+            outlier_points: Vec::new(),
+            outlier_lux_points: Vec::new(),
+            flicker_measurements: {
+                let mut flicker_measurements = Measurements::new();
+                flicker_measurements.set_max_data_points(FlickerSettings::MAX_WINDOW);
+                flicker_measurements
+            },
+            flicker_device_timestamps: false,
+            flicker_settings: FlickerSettings::new(),
+
+            history: Arc::new(Mutex::new(Vec::new())),
+            history_kind: SeriesKind::NewtonRaphson,
+            history_days: 7,
+            history_request: false,
+            history_show_ppfd: false,
+            comparison_result_set: None,
+
+            dli_settings: DliSettings::new(),
+            running_dli: None,
+            daily_light_integrals: Arc::new(Mutex::new(Vec::new())),
+            dli_days: 14,
+            dli_load_request: false,
+            dli_recompute_request: false,
+            dli_status: Arc::new(Mutex::new(String::new())),
+
+            forecast_settings: ForecastSettings::new(),
+            forecast: Arc::new(Mutex::new(None)),
+            dli_projection: Arc::new(Mutex::new(None)),
+            forecast_request: false,
+            forecast_status: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    pub fn add_confidence_interval(&mut self, x: f64, interval: Option<ConfidenceInterval>) {
+        self.lux_intervals.push((x, interval));
+        if self.lux_intervals.len() > self.max_data_points {
+            self.lux_intervals.remove(0);
+        }
+    }
+
+    pub fn add_filtered(&mut self, value: Value) {
+        self.filtered_measurements.add_value(value);
+    }
+
+    pub fn add_outlier(&mut self, raw: Value, lux: Option<Value>) {
+        self.outlier_points.push([raw.x, raw.y]);
+        if self.outlier_points.len() > self.max_data_points {
+            self.outlier_points.remove(0);
+        }
+        if let Some(lux) = lux {
+            self.outlier_lux_points.push([lux.x, lux.y]);
+            if self.outlier_lux_points.len() > self.max_data_points {
+                self.outlier_lux_points.remove(0);
+            }
+        }
+    }
+
+    pub fn add_flicker_sample(&mut self, value: Value, device_timestamp: bool) {
+        // Sumbu waktu berganti (firmware berubah format): sampel lama tidak bisa digabung.
+        if device_timestamp != self.flicker_device_timestamps {
+            self.flicker_measurements.clear_values();
+            self.flicker_device_timestamps = device_timestamp;
+        }
+        self.flicker_measurements.add_value(value);
+    }
+
+    pub fn add_ppfd(&mut self, value: Value) {
+        self.ppfd_measurements.add_value(value);
+    }
+
+    // Menimpa entri tanggal yang sama agar hasil hitung ulang menggantikan total lama.
+    pub fn merge_daily_light_integrals(&self, totals: Vec<DailyLightIntegral>) {
+        let mut daily = self.daily_light_integrals.lock().unwrap();
+        for total in totals {
+            daily.retain(|existing| (existing.sensor_id.as_str(), existing.date) != (total.sensor_id.as_str(), total.date));
+            daily.push(total);
+        }
+        daily.sort_by_key(|total| total.date);
+    }
+
+    fn show_flicker(&mut self, ui: &mut Ui) {
+        let settings = &mut self.flicker_settings;
+        Grid::new("flicker_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 8.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Jumlah Sampel FFT:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("flicker_window_size_combo")
+                    .selected_text(settings.window_size.to_string())
+                    .show_ui(ui_grid, |ui_combo| {
+                        let mut size = FlickerSettings::MIN_WINDOW;
+                        while size <= FlickerSettings::MAX_WINDOW {
+                            ui_combo.selectable_value(&mut settings.window_size, size, size.to_string());
+                            size *= 2;
+                        }
+                    });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Fungsi Window:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("flicker_window_function_combo")
+                    .selected_text(settings.window_function.label())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for function in WindowFunction::ALL {
+                            ui_combo.selectable_value(&mut settings.window_function, function, function.label());
+                        }
+                    });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Laju Sampel:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    let mut manual = settings.sample_rate_override.is_some();
+                    if ui_h.checkbox(&mut manual, "Tetapkan manual").changed() {
+                        settings.sample_rate_override = manual.then_some(1000.0);
+                    }
+                    if let Some(rate) = settings.sample_rate_override.as_mut() {
+                        ui_h.add(egui::DragValue::new(rate)
+                            .speed(10.0)
+                            .range(1.0..=1_000_000.0)
+                            .suffix(" Hz"));
+                    } else {
+                        ui_h.label(RichText::new("diperkirakan dari timestamp perangkat").color(Color32::GRAY));
+                    }
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Abaikan di Bawah:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.min_frequency_hz)
+                    .speed(0.5)
+                    .range(0.0..=10_000.0)
+                    .suffix(" Hz"));
+                ui_grid.end_row();
+            });
+
+        ui.add_space(10.0);
+        let analysis = match flicker::analyse(&self.flicker_measurements.values, &self.flicker_settings, self.flicker_device_timestamps) {
+            Ok(analysis) => analysis,
+            Err(message) => {
+                ui.label(RichText::new(message).color(Color32::GRAY).italics());
+                return;
+            },
+        };
+
+        let dominant = analysis.dominant_frequency;
+        Plot::new("flicker_spectrum_plot")
+            .width(ui.available_width())
+            .height(240.0)
+            .include_y(0.0)
+            .auto_bounds([true, true])
+            .show_background(true)
+            .legend(Legend::default())
+            .label_formatter(|name, value| {
+                if !name.is_empty() {
+                    format!("{}: {:.2} Hz, amplitudo {:.3} Lux", name, value.x, value.y)
+                } else {
+                    "".to_owned()
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Spektrum Amplitudo", PlotPoints::from(analysis.spectrum.clone()))
+                    .color(Color32::from_rgb(100, 200, 255))
+                    .width(1.5));
+                if let Some(frequency) = dominant {
+                    plot_ui.points(Points::new("Frekuensi Dominan", PlotPoints::from(vec![[frequency, analysis.dominant_amplitude]]))
+                        .color(Color32::from_rgb(255, 60, 60))
+                        .shape(MarkerShape::Diamond)
+                        .radius(6.0));
+                }
+            });
+
+        ui.label(format!("{} sampel, laju {:.1} Hz (Nyquist {:.1} Hz), rata-rata {:.2} Lux",
+            analysis.sample_count, analysis.sample_rate, analysis.nyquist(), analysis.mean));
+        match dominant {
+            Some(frequency) => {
+                ui.label(format!("Frekuensi dominan: {:.2} Hz (amplitudo {:.3} Lux)", frequency, analysis.dominant_amplitude));
+            },
+            None => {
+                ui.label(RichText::new("Tidak ada komponen periodik di atas batas frekuensi.").color(Color32::GRAY));
+            },
+        }
+        ui.label(format!("Persen flicker: {:.2}% · Indeks flicker: {:.4}", analysis.percent_flicker, analysis.flicker_index));
+        if let Some(risk) = analysis.risk {
+            let color = match risk {
+                FlickerRisk::NoEffect => Color32::LIGHT_GREEN,
+                FlickerRisk::LowRisk => Color32::YELLOW,
+                FlickerRisk::Elevated => Color32::from_rgb(255, 80, 80),
+            };
+            ui.label(RichText::new(format!("IEEE 1789: {}", risk.label())).color(color).strong());
+        }
+        if analysis.timing_jitter > 0.1 && self.flicker_settings.sample_rate_override.is_none() {
+            ui.label(RichText::new(format!("Selang sampel tidak seragam (jitter {:.0}%); tetapkan laju sampel burst Arduino secara manual.", 100.0 * analysis.timing_jitter))
+                .color(Color32::from_rgb(255, 180, 60)));
+        }
+        if dominant.is_some_and(|frequency| frequency > 0.9 * analysis.nyquist()) {
+            ui.label(RichText::new("Frekuensi dominan mendekati Nyquist; flicker sebenarnya mungkin lebih tinggi (aliasing).")
+                .color(Color32::from_rgb(255, 180, 60)));
         }
     }
 
+    fn show_dli(&mut self, ui: &mut Ui) {
+        Grid::new("dli_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 8.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Metode Integrasi:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("dli_method_combo")
+                    .selected_text(self.dli_settings.method.label())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for method in IntegrationMethod::ALL {
+                            ui_combo.selectable_value(&mut self.dli_settings.method, method, method.label());
+                        }
+                    });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Batas Celah Data:").color(Color32::WHITE));
+                let mut gap_minutes = self.dli_settings.max_gap_seconds / 60.0;
+                if ui_grid.add(egui::DragValue::new(&mut gap_minutes)
+                    .speed(0.5)
+                    .range(1.0..=240.0)
+                    .suffix(" menit")).changed() {
+                    self.dli_settings.max_gap_seconds = gap_minutes * 60.0;
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Zona Waktu Batas Hari (UTC+):").color(Color32::WHITE));
+                let mut offset_hours = self.dli_settings.utc_offset.local_minus_utc() / 3600;
+                if ui_grid.add(egui::DragValue::new(&mut offset_hours).range(-12..=14)).changed() {
+                    if let Some(offset) = chrono::FixedOffset::east_opt(offset_hours * 3600) {
+                        self.dli_settings.utc_offset = offset;
+                    }
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Target DLI:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.dli_settings.target)
+                    .speed(0.1)
+                    .range(1.0..=60.0)
+                    .suffix(" mol/m²/hari"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Sampel Outlier:").color(Color32::WHITE));
+                ui_grid.checkbox(&mut self.dli_settings.include_outliers, "Ikut dihitung dalam DLI");
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Rentang:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.dli_days)
+                    .speed(1.0)
+                    .range(1..=365)
+                    .suffix(" hari terakhir"));
+                ui_grid.end_row();
+            });
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui_h| {
+            if ui_h.button(RichText::new("🔄 Muat DLI Tersimpan").color(Color32::WHITE)).clicked() {
+                self.dli_load_request = true;
+            }
+            if ui_h.button(RichText::new("🧮 Hitung DLI dari Riwayat").color(Color32::WHITE)).clicked() {
+                self.dli_recompute_request = true;
+            }
+        });
+
+        let status = self.dli_status.lock().unwrap().clone();
+        if !status.is_empty() {
+            ui.label(RichText::new(status).color(Color32::WHITE));
+        }
+        match &self.running_dli {
+            Some(running) => {
+                ui.label(format!("DLI hari ini ({}): {:.2} mol/m² dari target {:.1} ({:.0}%), cakupan data {:.1} jam.",
+                    running.date, running.dli, self.dli_settings.target,
+                    100.0 * running.dli / self.dli_settings.target, running.covered_hours));
+            },
+            None => {
+                ui.label(RichText::new("Menunggu data PPFD untuk DLI hari ini...").color(Color32::GRAY).italics());
+            },
+        }
+        ui.add_space(10.0);
+
+        let target = self.dli_settings.target;
+        let daily = self.daily_light_integrals.lock().unwrap();
+        let bar_for = |total: &DailyLightIntegral| {
+            let color = if total.dli >= target { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 180, 60) };
+            let x = total.date.and_hms_opt(12, 0, 0).unwrap().and_utc().timestamp() as f64;
+            Bar::new(x, total.dli)
+                .width(0.8 * 86_400.0)
+                .fill(if total.complete { color } else { color.gamma_multiply(0.4) })
+                .name(format!("{} ({:.0}% data)", total.date, 100.0 * total.coverage()))
+        };
+        let mut bars: Vec<Bar> = daily.iter().map(bar_for).collect();
+        if let Some(running) = self.running_dli.as_ref().filter(|running| !daily.iter().any(|total| total.date == running.date)) {
+            bars.push(bar_for(running));
+        }
+
+        Plot::new("dli_bar_plot")
+            .width(ui.available_width())
+            .height(240.0)
+            .include_y(0.0)
+            .include_y(target * 1.2)
+            .auto_bounds([true, true])
+            .show_background(true)
+            .legend(Legend::default())
+            .x_axis_formatter(|mark, _range| format_unix_seconds(mark.value, "%m-%d"))
+            .label_formatter(|name, value| {
+                if !name.is_empty() {
+                    format!("{}: {:.2} mol/m²/hari", name, value.y)
+                } else {
+                    "".to_owned()
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new("DLI Harian", bars));
+                plot_ui.hline(HLine::new("Target DLI", target)
+                    .color(Color32::LIGHT_BLUE)
+                    .width(1.5));
+            });
+
+        let below_target = daily.iter().filter(|total| total.complete && total.dli < target).count();
+        if !daily.is_empty() {
+            ui.label(format!("{} dari {} hari selesai di bawah target. Batang pudar = hari berjalan; hari dengan cakupan data rendah cenderung terlalu kecil.",
+                below_target, daily.iter().filter(|total| total.complete).count()));
+        }
+    }
+
+    fn show_forecast(&mut self, ui: &mut Ui) {
+        let settings = &mut self.forecast_settings;
+        Grid::new("forecast_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 8.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Metode:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("forecast_method_combo")
+                    .selected_text(settings.method.label())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for method in ForecastMethod::ALL {
+                            ui_combo.selectable_value(&mut settings.method, method, method.label());
+                        }
+                    });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Riwayat & Resolusi:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    ui_h.add(egui::DragValue::new(&mut settings.history_days)
+                        .speed(1.0)
+                        .range(2..=60)
+                        .suffix(" hari terakhir"));
+                    egui::ComboBox::from_id_salt("forecast_bucket_combo")
+                        .selected_text(format!("per {} menit", settings.bucket_minutes))
+                        .show_ui(ui_h, |ui_combo| {
+                            for minutes in ForecastSettings::BUCKET_CHOICES {
+                                ui_combo.selectable_value(&mut settings.bucket_minutes, minutes, format!("per {} menit", minutes));
+                            }
+                        });
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Horizon:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.horizon_hours)
+                    .speed(0.5)
+                    .range(1.0..=48.0)
+                    .suffix(" jam (minimal sampai akhir hari)"));
+                ui_grid.end_row();
+
+                match settings.method {
+                    ForecastMethod::HarmonicRegression => {
+                        ui_grid.label(RichText::new("Jumlah Harmonik:").color(Color32::WHITE));
+                        ui_grid.add(egui::DragValue::new(&mut settings.harmonics).range(1..=12));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Koreksi Kondisi Terkini:").color(Color32::WHITE));
+                        ui_grid.horizontal(|ui_h| {
+                            ui_h.add(egui::DragValue::new(&mut settings.recent_hours)
+                                .speed(0.1)
+                                .range(0.25..=6.0)
+                                .prefix("rasio ")
+                                .suffix(" jam terakhir"));
+                            ui_h.add(egui::DragValue::new(&mut settings.recent_decay_hours)
+                                .speed(0.1)
+                                .range(0.25..=24.0)
+                                .prefix("meluruh ")
+                                .suffix(" jam"));
+                        });
+                        ui_grid.end_row();
+                    },
+                    ForecastMethod::HoltWinters => {
+                        ui_grid.label(RichText::new("Pemulusan (α, β, γ):").color(Color32::WHITE));
+                        ui_grid.horizontal(|ui_h| {
+                            for value in [&mut settings.alpha, &mut settings.beta, &mut settings.gamma] {
+                                ui_h.add(egui::DragValue::new(value).speed(0.01).range(0.0..=1.0).fixed_decimals(2));
+                            }
+                        });
+                        ui_grid.end_row();
+                    },
+                }
+
+                ui_grid.label(RichText::new("Lampu Tambahan:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.supplemental_ppfd)
+                    .speed(5.0)
+                    .range(1.0..=2_000.0)
+                    .suffix(" µmol/m²/s"));
+                ui_grid.end_row();
+            });
+
+        ui.add_space(10.0);
+        if ui.button(RichText::new("🔮 Perbarui Prakiraan").color(Color32::WHITE)).clicked() {
+            self.forecast_request = true;
+        }
+        let status = self.forecast_status.lock().unwrap().clone();
+        if !status.is_empty() {
+            ui.label(RichText::new(status).color(Color32::WHITE));
+        }
+
+        let forecast = self.forecast.lock().unwrap();
+        let Some(forecast) = forecast.as_ref() else {
+            ui.label(RichText::new("Belum ada prakiraan. Prakiraan memakai lux Newton-Raphson tersimpan di database.").color(Color32::GRAY).italics());
+            return;
+        };
+
+        if let Some(projection) = self.dli_projection.lock().unwrap().as_ref() {
+            let lower = projection.accumulated + projection.remaining_lower;
+            let upper = projection.accumulated + projection.remaining_upper;
+            ui.label(format!("Proyeksi DLI {}: {:.2} mol/m² (95%: {:.2}–{:.2}) = {:.2} terkumpul + {:.2} prakiraan, target {:.1}.",
+                projection.date, projection.projected(), lower, upper, projection.accumulated, projection.remaining, projection.target));
+            if projection.deficit() > 0.0 {
+                let hours = projection.supplemental_hours(self.forecast_settings.supplemental_ppfd).unwrap_or(0.0);
+                ui.label(RichText::new(format!("⚠ Kekurangan {:.2} mol/m²: perlu sekitar {:.1} jam lampu tambahan pada {:.0} µmol/m²/s.",
+                    projection.deficit(), hours, self.forecast_settings.supplemental_ppfd))
+                    .color(Color32::from_rgb(255, 180, 60))
+                    .strong());
+            } else if lower < projection.target {
+                ui.label(RichText::new("Target diperkirakan tercapai, tetapi batas bawah prakiraan masih di bawah target.").color(Color32::YELLOW));
+            } else {
+                ui.label(RichText::new("✅ Target DLI diperkirakan tercapai tanpa lampu tambahan.").color(Color32::LIGHT_GREEN));
+            }
+        }
+        ui.label(format!("{} · {} bucket riwayat · simpangan residual {:.0} lux · dibuat {} UTC",
+            forecast.method.label(), forecast.observation_count, forecast.residual_std,
+            forecast.generated_at.format("%m-%d %H:%M")));
+
+        let observed: PlotPoints = forecast.recent_observed.iter().map(|(timestamp, lux)| [timestamp.timestamp() as f64, *lux]).collect();
+        let predicted: PlotPoints = forecast.points.iter().map(|point| [point.timestamp.timestamp() as f64, point.lux]).collect();
+        let intervals: Vec<(f64, Option<ConfidenceInterval>)> = forecast.points.iter()
+            .map(|point| (point.timestamp.timestamp() as f64, Some(ConfidenceInterval { lower: point.lower, upper: point.upper })))
+            .collect();
+        let now = forecast.generated_at.timestamp() as f64;
+
+        Plot::new("forecast_plot")
+            .width(ui.available_width())
+            .height(300.0)
+            .include_y(0.0)
+            .auto_bounds([true, true])
+            .show_background(true)
+            .legend(Legend::default())
+            .x_axis_formatter(|mark, _range| format_unix_seconds(mark.value, "%m-%d %H:%M"))
+            .label_formatter(|name, value| {
+                if !name.is_empty() {
+                    format!("{}: {}, {:.0} lux", name, format_unix_seconds(value.x, "%m-%d %H:%M"), value.y)
+                } else {
+                    "".to_owned()
+                }
+            })
+            .show(ui, |plot_ui| {
+                for band in confidence_band("Prakiraan (95%)", &intervals, Color32::from_rgb(255, 200, 80)) {
+                    plot_ui.polygon(band);
+                }
+                plot_ui.line(Line::new("Lux Terukur", observed)
+                    .color(Color32::from_rgb(100, 200, 255))
+                    .width(2.0));
+                plot_ui.line(Line::new("Prakiraan", predicted)
+                    .color(Color32::from_rgb(255, 200, 80))
+                    .style(LineStyle::dashed_loose())
+                    .width(2.0));
+                plot_ui.vline(VLine::new("Sekarang", now).color(Color32::GRAY));
+            });
+        ui.label(RichText::new("Garis putus-putus = prakiraan, pita = interval prediksi 95%. Waktu dalam UTC.")
+            .color(Color32::GRAY)
+            .italics());
+    }
+
     pub fn show(&mut self, ui: &mut Ui) {
-        let measurements_guard = self.measurements.lock().unwrap();
-        let newton_raphson_lux_guard = self.newton_raphson_lux_measurements.lock().unwrap();
+        // Salinan Arc agar guard tidak meminjam `self` selama panel DLI (yang butuh `&mut self`) digambar.
+        let (measurements, newton_raphson_lux) = (Arc::clone(&self.measurements), Arc::clone(&self.newton_raphson_lux_measurements));
+        let measurements_guard = measurements.lock().unwrap();
+        let newton_raphson_lux_guard = newton_raphson_lux.lock().unwrap();
 
         egui::ScrollArea::vertical()
             .auto_shrink([false; 2])
@@ -43,6 +578,10 @@ impl DataGraphicsScreen {
 
                     let photodiode_data: PlotPoints = measurements_guard.values.iter().map(|v| [v.x, v.y]).collect();
                     let line = Line::new("Nilai Terukur Sensor", photodiode_data).color(Color32::from_rgb(100, 200, 255)).width(2.0);
+                    let filtered_data: PlotPoints = self.filtered_measurements.values.iter().map(|v| [v.x, v.y]).collect();
+                    let filtered_line = Line::new("Nilai Terfilter", filtered_data).color(Color32::from_rgb(255, 180, 60)).width(2.0);
+                    let first_x = measurements_guard.values.first().map_or(f64::MIN, |v| v.x);
+                    let outliers = outlier_markers(&self.outlier_points, first_x);
 
                     Plot::new("photodiode_Tegangan_plot")
                         .width(ui.available_width())
@@ -62,12 +601,23 @@ impl DataGraphicsScreen {
                         .legend(Legend::default())
                         .show(ui, |plot_ui| {
                             plot_ui.line(line);
+                            plot_ui.line(filtered_line);
+                            if let Some(outliers) = outliers {
+                                plot_ui.points(outliers);
+                            }
                         });
 
                     if measurements_guard.values.is_empty() {
                         ui.label(RichText::new("Menunggu data photodiode dari sensor...").color(Color32::GRAY).italics());
                     } else {
                         ui.label(format!("Nilai Sensor Terbaru: {:.2}", measurements_guard.values.last().unwrap().y));
+                        if let Some(filtered) = self.filtered_measurements.values.last() {
+                            ui.label(format!("Nilai Terfilter Terbaru: {:.2}", filtered.y));
+                        }
+                        let flagged = self.outlier_points.iter().filter(|p| p[0] >= first_x).count();
+                        if flagged > 0 {
+                            ui.label(RichText::new(format!("{} sampel ditandai outlier pada grafik.", flagged)).color(Color32::from_rgb(255, 80, 80)));
+                        }
                     }
                     ui.add_space(5.0);
                 });
@@ -83,6 +633,7 @@ impl DataGraphicsScreen {
                     let line_nr = Line::new("Lux Hasil NR", newton_raphson_lux_data)
                                     .color(Color32::from_rgb(255, 100, 100))
                                     .width(2.0);
+                    let lux_outliers = outlier_markers(&self.outlier_lux_points, newton_raphson_lux_guard.values.first().map_or(f64::MIN, |v| v.x));
 
                     Plot::new("newton_raphson_lux_plot")
                         .width(ui.available_width())
@@ -100,13 +651,210 @@ impl DataGraphicsScreen {
                             }
                         })
                         .show(ui, |plot_ui| {
+                            for band in confidence_band("Interval 95%", &self.lux_intervals, Color32::from_rgb(255, 100, 100)) {
+                                plot_ui.polygon(band);
+                            }
                             plot_ui.line(line_nr);
+                            if let Some(lux_outliers) = lux_outliers {
+                                plot_ui.points(lux_outliers);
+                            }
                         });
 
                     if newton_raphson_lux_guard.values.is_empty() {
                         ui.label(RichText::new("Menunggu perhitungan Newton-Raphson Lux...").color(Color32::GRAY).italics());
                     } else {
                         ui.label(format!("Lux Newton-Raphson Terbaru: {:.2}", newton_raphson_lux_guard.values.last().unwrap().y));
+                        if let Some((_, Some(interval))) = self.lux_intervals.last() {
+                            ui.label(format!("Interval Kepercayaan 95%: {:.2} – {:.2} Lux", interval.lower, interval.upper));
+                        }
+                    }
+                    ui.add_space(5.0);
+                });
+
+                ui_scroll_content.add_space(20.0);
+
+                ui_scroll_content.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Grafik PPFD (Foton Fotosintesis)").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+
+                    let ppfd_data: PlotPoints = self.ppfd_measurements.values.iter().map(|v| [v.x, v.y]).collect();
+                    let line_ppfd = Line::new("PPFD", ppfd_data)
+                                    .color(Color32::from_rgb(150, 255, 150))
+                                    .width(2.0);
+
+                    Plot::new("ppfd_plot")
+                        .width(ui.available_width())
+                        .height(280.0)
+                        .view_aspect(2.0)
+                        .include_y(0.0)
+                        .auto_bounds([true, true])
+                        .show_background(true)
+                        .legend(Legend::default())
+                        .label_formatter(|name, value| {
+                            if !name.is_empty() {
+                                format!("{}: Waktu {}, {:.1} µmol/m²/s", name, value.x, value.y)
+                            } else {
+                                "".to_owned()
+                            }
+                        })
+                        .show(ui, |plot_ui| {
+                            plot_ui.line(line_ppfd);
+                        });
+
+                    match self.ppfd_measurements.values.last() {
+                        Some(latest) => { ui.label(format!("PPFD Terbaru: {:.1} µmol/m²/s", latest.y)); },
+                        None => { ui.label(RichText::new("Menunggu hasil lux untuk konversi PPFD...").color(Color32::GRAY).italics()); },
+                    }
+                    ui.add_space(5.0);
+                });
+
+                ui_scroll_content.add_space(20.0);
+
+                ui_scroll_content.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Daily Light Integral (DLI)").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_dli(ui);
+                    ui.add_space(5.0);
+                });
+
+                ui_scroll_content.add_space(20.0);
+
+                ui_scroll_content.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Analisis Flicker Lampu (FFT)").color(Color32::LIGHT_BLUE).strong());
+                    ui.add_space(10.0);
+                    self.show_flicker(ui);
+                    ui.add_space(5.0);
+                });
+
+                ui_scroll_content.add_space(20.0);
+
+                ui_scroll_content.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Prakiraan Lux & Kebutuhan Lampu Tambahan").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_forecast(ui);
+                    ui.add_space(5.0);
+                });
+
+                ui_scroll_content.add_space(20.0);
+
+                ui_scroll_content.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Riwayat Data Tersimpan (Live, Impor & Hitung Ulang)").color(Color32::YELLOW).strong());
+                    ui.add_space(10.0);
+
+                    ui.horizontal(|ui_h| {
+                        ui_h.radio_value(&mut self.history_kind, SeriesKind::Photodiode, "Photodiode");
+                        ui_h.radio_value(&mut self.history_kind, SeriesKind::NewtonRaphson, "Lux");
+                        if self.history_kind == SeriesKind::NewtonRaphson {
+                            ui_h.checkbox(&mut self.history_show_ppfd, "Sebagai PPFD");
+                        }
+                        ui_h.add_space(20.0);
+                        ui_h.label(RichText::new("Rentang:").color(Color32::WHITE));
+                        ui_h.add(egui::DragValue::new(&mut self.history_days)
+                            .speed(1.0)
+                            .range(1..=365)
+                            .suffix(" hari terakhir"));
+                        if ui_h.button(RichText::new("🔄 Muat Riwayat").color(Color32::WHITE)).clicked() {
+                            self.history_request = true;
+                        }
+                    });
+                    ui.add_space(10.0);
+
+                    let history_guard = self.history.lock().unwrap();
+                    // Titik dan interval 95% per sumber data.
+                    type SourceSeries = (Vec<[f64; 2]>, Vec<(f64, Option<ConfidenceInterval>)>);
+                    let mut lines_by_source: BTreeMap<&str, SourceSeries> = BTreeMap::new();
+                    let mut flagged_points = Vec::new();
+                    let show_ppfd = self.history_show_ppfd && self.history_kind == SeriesKind::NewtonRaphson;
+                    for record in history_guard.iter() {
+                        let value = if show_ppfd { record.ppfd } else { record.value };
+                        if let (Some(timestamp), Some(value)) = (record.timestamp, value) {
+                            if record.outlier.is_some() {
+                                flagged_points.push([timestamp.timestamp() as f64, value]);
+                            }
+                            let (points, intervals) = lines_by_source.entry(source_label(record)).or_default();
+                            points.push([timestamp.timestamp() as f64, value]);
+                            // Interval 95% hanya tersedia dalam lux.
+                            intervals.push((timestamp.timestamp() as f64, record.confidence_interval.filter(|_| !show_ppfd)));
+                        }
+                    }
+
+                    let result_sets: Vec<String> = lines_by_source.keys()
+                        .filter(|source| recompute::is_result_set(source))
+                        .map(|source| source.to_string())
+                        .collect();
+                    if self.comparison_result_set.as_ref().is_some_and(|set| !result_sets.contains(set)) {
+                        self.comparison_result_set = None;
+                    }
+                    if !result_sets.is_empty() {
+                        ui.horizontal(|ui_h| {
+                            ui_h.label(RichText::new("Bandingkan Lux Lama dengan:").color(Color32::WHITE));
+                            egui::ComboBox::from_id_salt("comparison_result_set_combo")
+                                .selected_text(self.comparison_result_set.clone().unwrap_or_else(|| "Semua sumber".to_string()))
+                                .show_ui(ui_h, |ui_combo| {
+                                    ui_combo.selectable_value(&mut self.comparison_result_set, None, "Semua sumber");
+                                    for set in &result_sets {
+                                        ui_combo.selectable_value(&mut self.comparison_result_set, Some(set.clone()), set);
+                                    }
+                                });
+                        });
+                        ui.add_space(10.0);
+                    }
+                    if let Some(set) = &self.comparison_result_set {
+                        lines_by_source.retain(|source, _| *source == "live" || source == set);
+                        if let (Some((old, _)), Some((new, _))) = (lines_by_source.get("live"), lines_by_source.get(set.as_str())) {
+                            let mean = |points: &Vec<[f64; 2]>| points.iter().map(|p| p[1]).sum::<f64>() / points.len() as f64;
+                            let unit = if show_ppfd { "µmol/m²/s" } else { "lux" };
+                            ui.label(format!("Rata-rata lama {:.2} ({} titik), baru {:.2} ({} titik), selisih {:+.2} {}.",
+                                mean(old), old.len(), mean(new), new.len(), mean(new) - mean(old), unit));
+                        }
+                    }
+
+                    let palette = [
+                        Color32::from_rgb(100, 200, 255),
+                        Color32::from_rgb(255, 180, 60),
+                        Color32::from_rgb(150, 255, 150),
+                        Color32::from_rgb(255, 120, 200),
+                    ];
+
+                    Plot::new("stored_history_plot")
+                        .width(ui.available_width())
+                        .height(280.0)
+                        .include_y(0.0)
+                        .auto_bounds([true, true])
+                        .show_background(true)
+                        .legend(Legend::default())
+                        .x_axis_formatter(|mark, _range| format_unix_seconds(mark.value, "%m-%d %H:%M"))
+                        .label_formatter(|name, value| {
+                            if !name.is_empty() {
+                                format!("{}: {}, {:.2}", name, format_unix_seconds(value.x, "%Y-%m-%d %H:%M:%S"), value.y)
+                            } else {
+                                "".to_owned()
+                            }
+                        })
+                        .show(ui, |plot_ui| {
+                            for (i, (source, (points, intervals))) in lines_by_source.into_iter().enumerate() {
+                                let color = palette[i % palette.len()];
+                                for band in confidence_band(&format!("{} (95%)", source), &intervals, color) {
+                                    plot_ui.polygon(band);
+                                }
+                                plot_ui.line(Line::new(source, PlotPoints::from(points))
+                                    .color(color)
+                                    .width(1.5));
+                            }
+                            if let Some(flagged) = outlier_markers(&flagged_points, f64::MIN) {
+                                plot_ui.points(flagged);
+                            }
+                        });
+
+                    if history_guard.is_empty() {
+                        ui.label(RichText::new("Tekan 'Muat Riwayat' untuk menampilkan data dari database, termasuk data hasil impor.").color(Color32::GRAY).italics());
+                    } else {
+                        ui.label(format!("{} titik data dimuat (waktu dalam UTC).", history_guard.len()));
                     }
                     ui.add_space(5.0);
                 });
@@ -114,8 +862,46 @@ impl DataGraphicsScreen {
         }
 
         pub fn clear_data(&mut self) {
+            self.history.lock().unwrap().clear();
+            self.lux_intervals.clear();
+            self.ppfd_measurements.clear_values();
+            self.filtered_measurements.clear_values();
+            self.outlier_points.clear();
+            self.outlier_lux_points.clear();
+            self.flicker_measurements.clear_values();
+            *self.forecast.lock().unwrap() = None;
+            *self.dli_projection.lock().unwrap() = None;
             self.measurements.lock().unwrap().clear_values();
             self.newton_raphson_lux_measurements.lock().unwrap().clear_values();
             println!("DataGraphicsScreen: Data cleared.");
         }
     }
+
+// Pita interval sebagai trapesium antar titik berurutan; poligon egui_plot hanya terisi benar bila cembung.
+fn confidence_band(name: &str, intervals: &[(f64, Option<ConfidenceInterval>)], color: Color32) -> Vec<Polygon<'static>> {
+    intervals.windows(2)
+        .filter_map(|pair| match (pair[0], pair[1]) {
+            ((x0, Some(a)), (x1, Some(b))) => Some(Polygon::new(name, PlotPoints::from(vec![
+                [x0, a.lower], [x1, b.lower], [x1, b.upper], [x0, a.upper],
+            ]))
+                .fill_color(color.gamma_multiply(0.25))
+                .width(0.0)),
+            _ => None,
+        })
+        .collect()
+}
+
+// Hanya titik yang masih berada dalam jendela grafik live (x >= `first_x`).
+fn outlier_markers(points: &[[f64; 2]], first_x: f64) -> Option<Points<'static>> {
+    let visible: Vec<[f64; 2]> = points.iter().copied().filter(|p| p[0] >= first_x).collect();
+    (!visible.is_empty()).then(|| Points::new("Ditandai Outlier", PlotPoints::from(visible))
+        .color(Color32::from_rgb(255, 60, 60))
+        .shape(MarkerShape::Cross)
+        .radius(5.0))
+}
+
+fn format_unix_seconds(seconds: f64, format: &str) -> String {
+    chrono::DateTime::from_timestamp(seconds as i64, 0)
+        .map(|dt| dt.format(format).to_string())
+        .unwrap_or_default()
+}
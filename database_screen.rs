@@ -1,6 +1,9 @@
 use egui::{RichText, Color32, Grid, ScrollArea};
-use mongodb::bson::Document;
-use mongodb::bson::Bson;
+use crate::storage::{SeriesKind, StorageBackend, StoredRecord};
+use crate::export::{self, DecimalSeparator, ExportColumn, ExportFormat, ExportOptions};
+use crate::csv_import::{self, ColumnMapping, ImportSettings, ParsedCsv, ValidatedRow};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 
 #[derive(PartialEq, Debug, Clone)]
@@ -11,19 +14,382 @@ pub enum DatabaseDataType {
 
 pub struct DatabaseScreen {
     pub current_display_type: DatabaseDataType,
+    pub storage_backend: StorageBackend,
+
+    pub export_format: ExportFormat,
+    pub export_from: String,
+    pub export_to: String,
+    pub export_sensor: String,
+    pub export_photodiode: bool,
+    pub export_lux: bool,
+    pub export_columns: Vec<bool>,
+    pub export_utc_offset_hours: i32,
+    pub export_decimal_comma: bool,
+    pub export_path: String,
+    pub export_request: Option<(PathBuf, ExportOptions)>,
+    pub export_status: Arc<Mutex<String>>,
+
+    pub import_path: String,
+    pub import_settings: ImportSettings,
+    pub import_utc_offset_hours: i32,
+    pub import_sensor: String,
+    pub import_source: String,
+    // Hanya baris awal file (pratinjau); impor penuh membaca ulang file di thread latar belakang.
+    pub import_parsed: Option<ParsedCsv>,
+    pub import_validated: Vec<ValidatedRow>,
+    pub import_request: Option<(PathBuf, ImportSettings, String, String)>,
+    pub import_status: Arc<Mutex<String>>,
 }
 
 impl DatabaseScreen {
-    pub fn new() -> Self {
+    pub fn new(storage_backend: StorageBackend) -> Self {
         Self {
             current_display_type: DatabaseDataType::PhotodiodeData, 
+            storage_backend,
+
+            export_format: ExportFormat::Csv,
+            export_from: String::new(),
+            export_to: String::new(),
+            export_sensor: String::new(),
+            export_photodiode: true,
+            export_lux: true,
+            export_columns: vec![true; ExportColumn::ALL.len()],
+            export_utc_offset_hours: 7,
+            export_decimal_comma: false,
+            export_path: "ekspor_data.csv".to_string(),
+            export_request: None,
+            export_status: Arc::new(Mutex::new(String::new())),
+
+            import_path: String::new(),
+            import_settings: ImportSettings::new(),
+            import_utc_offset_hours: 7,
+            import_sensor: String::new(),
+            import_source: "lux-meter".to_string(),
+            import_parsed: None,
+            import_validated: Vec::new(),
+            import_request: None,
+            import_status: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    fn show_import_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(RichText::new("📥 Impor Data Historis (CSV)").color(Color32::WHITE))
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut reload = false;
+                let mut revalidate = false;
+
+                Grid::new("import_file_grid")
+                    .num_columns(2)
+                    .spacing([20.0, 6.0])
+                    .show(ui, |ui_grid| {
+                        ui_grid.label(RichText::new("File CSV:").color(Color32::WHITE));
+                        ui_grid.text_edit_singleline(&mut self.import_path);
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Pemisah Kolom:").color(Color32::WHITE));
+                        ui_grid.horizontal(|ui_h| {
+                            reload |= ui_h.radio_value(&mut self.import_settings.delimiter, b',', "Koma (,)").changed();
+                            reload |= ui_h.radio_value(&mut self.import_settings.delimiter, b';', "Titik koma (;)").changed();
+                            reload |= ui_h.radio_value(&mut self.import_settings.delimiter, b'\t', "Tab").changed();
+                        });
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Baris Pertama Header:").color(Color32::WHITE));
+                        reload |= ui_grid.checkbox(&mut self.import_settings.has_header, "Ya").changed();
+                        ui_grid.end_row();
+                    });
+
+                if ui.button(RichText::new("🔍 Muat Pratinjau").color(Color32::WHITE)).clicked() {
+                    reload = true;
+                }
+
+                if reload && !self.import_path.trim().is_empty() {
+                    let path = PathBuf::from(self.import_path.trim());
+                    match csv_import::read_csv(&path, self.import_settings.delimiter, self.import_settings.has_header, Some(csv_import::PREVIEW_READ_ROWS)) {
+                        Ok(parsed) => {
+                            *self.import_status.lock().unwrap() = format!("{} baris pertama dibaca dari {} untuk pratinjau", parsed.rows.len(), path.display());
+                            let width = parsed.headers.len();
+                            let mapping = &mut self.import_settings.mapping;
+                            if mapping.timestamp >= width {
+                                *mapping = ColumnMapping { timestamp: 0, raw_value: None, lux: None };
+                            }
+                            self.import_parsed = Some(parsed);
+                            revalidate = true;
+                        },
+                        Err(e) => {
+                            *self.import_status.lock().unwrap() = e;
+                            self.import_parsed = None;
+                            self.import_validated.clear();
+                        }
+                    }
+                }
+
+                if let Some(parsed) = &self.import_parsed {
+                    ui.add_space(5.0);
+                    let column_name = |index: Option<usize>| match index {
+                        Some(i) => parsed.headers.get(i).cloned().unwrap_or_else(|| format!("Kolom {}", i + 1)),
+                        None => "(tidak ada)".to_string(),
+                    };
+
+                    Grid::new("import_mapping_grid")
+                        .num_columns(2)
+                        .spacing([20.0, 6.0])
+                        .show(ui, |ui_grid| {
+                            ui_grid.label(RichText::new("Kolom Waktu:").color(Color32::WHITE));
+                            egui::ComboBox::from_id_salt("import_timestamp_column")
+                                .selected_text(column_name(Some(self.import_settings.mapping.timestamp)))
+                                .show_ui(ui_grid, |ui_combo| {
+                                    for (i, header) in parsed.headers.iter().enumerate() {
+                                        revalidate |= ui_combo.selectable_value(&mut self.import_settings.mapping.timestamp, i, header).changed();
+                                    }
+                                });
+                            ui_grid.end_row();
+
+                            for (label, salt, target) in [
+                                ("Kolom Nilai Photodiode:", "import_raw_column", &mut self.import_settings.mapping.raw_value),
+                                ("Kolom Lux:", "import_lux_column", &mut self.import_settings.mapping.lux),
+                            ] {
+                                ui_grid.label(RichText::new(label).color(Color32::WHITE));
+                                egui::ComboBox::from_id_salt(salt)
+                                    .selected_text(column_name(*target))
+                                    .show_ui(ui_grid, |ui_combo| {
+                                        revalidate |= ui_combo.selectable_value(target, None, "(tidak ada)").changed();
+                                        for (i, header) in parsed.headers.iter().enumerate() {
+                                            revalidate |= ui_combo.selectable_value(target, Some(i), header).changed();
+                                        }
+                                    });
+                                ui_grid.end_row();
+                            }
+
+                            ui_grid.label(RichText::new("Format Waktu (kosong = otomatis):").color(Color32::WHITE));
+                            revalidate |= ui_grid.text_edit_singleline(&mut self.import_settings.timestamp_format).changed();
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Zona Waktu Data (UTC+):").color(Color32::WHITE));
+                            revalidate |= ui_grid.add(egui::DragValue::new(&mut self.import_utc_offset_hours)
+                                .speed(1.0)
+                                .range(-12..=14)
+                                .prefix("UTC+")).changed();
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Desimal Koma:").color(Color32::WHITE));
+                            revalidate |= ui_grid.checkbox(&mut self.import_settings.decimal_comma, "Ya (mis. 12,5)").changed();
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("ID Sensor:").color(Color32::WHITE));
+                            ui_grid.text_edit_singleline(&mut self.import_sensor);
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Label Sumber:").color(Color32::WHITE));
+                            ui_grid.text_edit_singleline(&mut self.import_source);
+                            ui_grid.end_row();
+                        });
+
+                    if revalidate {
+                        if let Some(offset) = chrono::FixedOffset::east_opt(self.import_utc_offset_hours * 3600) {
+                            self.import_settings.utc_offset = offset;
+                        }
+                        self.import_validated = csv_import::validate(parsed, &self.import_settings);
+                    }
+
+                    let valid_count = self.import_validated.iter().filter(|row| row.result.is_ok()).count();
+                    let invalid_count = self.import_validated.len() - valid_count;
+                    ui.add_space(5.0);
+                    ui.label(RichText::new(format!("Pratinjau {} baris pertama: {} valid, {} tidak valid", self.import_validated.len(), valid_count, invalid_count))
+                        .color(if invalid_count > 0 { Color32::YELLOW } else { Color32::LIGHT_GREEN }));
+
+                    ScrollArea::vertical()
+                        .id_salt("import_preview_scroll")
+                        .max_height(200.0)
+                        .show(ui, |ui_scroll| {
+                            Grid::new("import_preview_grid")
+                                .num_columns(4)
+                                .spacing([20.0, 4.0])
+                                .striped(true)
+                                .show(ui_scroll, |ui_grid| {
+                                    ui_grid.strong(RichText::new("Baris").color(Color32::LIGHT_BLUE));
+                                    ui_grid.strong(RichText::new("Waktu (UTC)").color(Color32::LIGHT_BLUE));
+                                    ui_grid.strong(RichText::new("Photodiode / Lux").color(Color32::LIGHT_BLUE));
+                                    ui_grid.strong(RichText::new("Status").color(Color32::LIGHT_BLUE));
+                                    ui_grid.end_row();
+
+                                    let invalid_rows = self.import_validated.iter().filter(|row| row.result.is_err()).take(csv_import::PREVIEW_ROWS);
+                                    let preview_rows = self.import_validated.iter().take(csv_import::PREVIEW_ROWS).filter(|row| row.result.is_ok());
+                                    for row in preview_rows.chain(invalid_rows) {
+                                        ui_grid.label(format!("{}", row.line));
+                                        match &row.result {
+                                            Ok(import_row) => {
+                                                let format_value = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string());
+                                                ui_grid.label(import_row.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                                                ui_grid.label(format!("{} / {}", format_value(import_row.raw_value), format_value(import_row.lux)));
+                                                ui_grid.label(RichText::new("OK").color(Color32::LIGHT_GREEN));
+                                            },
+                                            Err(e) => {
+                                                ui_grid.label("-");
+                                                ui_grid.label("-");
+                                                ui_grid.label(RichText::new(e).color(Color32::RED));
+                                            }
+                                        }
+                                        ui_grid.end_row();
+                                    }
+                                });
+                        });
+
+                    let ready = valid_count > 0 && !self.import_sensor.trim().is_empty() && !self.import_source.trim().is_empty();
+                    if ui.add_enabled(ready, egui::Button::new(RichText::new("📥 Impor Semua Baris Valid").color(Color32::WHITE))).clicked() {
+                        *self.import_status.lock().unwrap() = "Membaca dan memvalidasi seluruh file...".to_string();
+                        self.import_request = Some((
+                            PathBuf::from(self.import_path.trim()),
+                            self.import_settings.clone(),
+                            self.import_sensor.trim().to_string(),
+                            self.import_source.trim().to_string(),
+                        ));
+                    }
+                    if !ready {
+                        ui.label(RichText::new("Isi ID sensor dan label sumber, dan pastikan ada baris yang valid.").color(Color32::GRAY).italics());
+                    }
+                    if parsed.rows.len() >= csv_import::PREVIEW_READ_ROWS {
+                        ui.label(RichText::new("Baris setelah pratinjau divalidasi saat impor; baris tidak valid dilewati dan dilaporkan.").color(Color32::GRAY).italics());
+                    }
+                }
+
+                let status = self.import_status.lock().unwrap();
+                if !status.is_empty() {
+                    let color = if status.contains("GAGAL") { Color32::RED } else { Color32::LIGHT_GREEN };
+                    ui.label(RichText::new(status.as_str()).color(color).italics());
+                }
+            });
+    }
+
+    fn build_export_options(&self) -> Result<ExportOptions, String> {
+        let mut options = ExportOptions::new();
+        options.format = self.export_format;
+        options.utc_offset = chrono::FixedOffset::east_opt(self.export_utc_offset_hours * 3600)
+            .ok_or("Zona waktu tidak valid")?;
+        options.from = export::parse_date(&self.export_from, &options.utc_offset, false)?;
+        options.to = export::parse_date(&self.export_to, &options.utc_offset, true)?;
+
+        let sensor = self.export_sensor.trim();
+        options.sensor_id = if sensor.is_empty() { None } else { Some(sensor.to_string()) };
+
+        options.series.clear();
+        if self.export_photodiode {
+            options.series.push(SeriesKind::Photodiode);
+        }
+        if self.export_lux {
+            options.series.push(SeriesKind::NewtonRaphson);
+        }
+        if options.series.is_empty() {
+            return Err("Pilih minimal satu jenis data untuk diekspor.".to_string());
+        }
+
+        options.columns = ExportColumn::ALL.iter().zip(&self.export_columns)
+            .filter(|(_, selected)| **selected)
+            .map(|(column, _)| *column)
+            .collect();
+        if options.columns.is_empty() {
+            return Err("Pilih minimal satu kolom untuk diekspor.".to_string());
         }
+
+        options.decimal_separator = if self.export_decimal_comma { DecimalSeparator::Comma } else { DecimalSeparator::Dot };
+        Ok(options)
+    }
+
+    fn show_export_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(RichText::new("📤 Ekspor Data (CSV / JSON Lines / Parquet)").color(Color32::WHITE))
+            .default_open(false)
+            .show(ui, |ui| {
+                Grid::new("export_options_grid")
+                    .num_columns(2)
+                    .spacing([20.0, 6.0])
+                    .show(ui, |ui_grid| {
+                        ui_grid.label(RichText::new("Format:").color(Color32::WHITE));
+                        ui_grid.horizontal(|ui_h| {
+                            ui_h.radio_value(&mut self.export_format, ExportFormat::Csv, "CSV");
+                            ui_h.radio_value(&mut self.export_format, ExportFormat::JsonLines, "JSON Lines");
+                            ui_h.radio_value(&mut self.export_format, ExportFormat::Parquet, "Parquet (folder per hari)");
+                        });
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Dari Tanggal (YYYY-MM-DD):").color(Color32::WHITE));
+                        ui_grid.text_edit_singleline(&mut self.export_from);
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Sampai Tanggal (YYYY-MM-DD):").color(Color32::WHITE));
+                        ui_grid.text_edit_singleline(&mut self.export_to);
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("ID Sensor (kosong = semua):").color(Color32::WHITE));
+                        ui_grid.text_edit_singleline(&mut self.export_sensor);
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Jenis Data:").color(Color32::WHITE));
+                        ui_grid.horizontal(|ui_h| {
+                            ui_h.checkbox(&mut self.export_photodiode, "Photodiode");
+                            ui_h.checkbox(&mut self.export_lux, "Lux Newton-Raphson");
+                        });
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Kolom:").color(Color32::WHITE));
+                        ui_grid.horizontal_wrapped(|ui_h| {
+                            for (column, selected) in ExportColumn::ALL.iter().zip(self.export_columns.iter_mut()) {
+                                ui_h.checkbox(selected, column.label());
+                            }
+                        });
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Zona Waktu (UTC+):").color(Color32::WHITE));
+                        ui_grid.add(egui::DragValue::new(&mut self.export_utc_offset_hours)
+                            .speed(1.0)
+                            .range(-12..=14)
+                            .prefix("UTC+"));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Desimal Koma (CSV):").color(Color32::WHITE));
+                        ui_grid.checkbox(&mut self.export_decimal_comma, "Gunakan koma, kolom dipisah titik koma");
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new(if self.export_format == ExportFormat::Parquet { "Folder Tujuan:" } else { "File Tujuan:" }).color(Color32::WHITE));
+                        ui_grid.text_edit_singleline(&mut self.export_path);
+                        ui_grid.end_row();
+                    });
+
+                if self.export_format == ExportFormat::Parquet {
+                    ui.label(RichText::new("Parquet memakai skema tetap (timestamp, sensor_id, raw_adc, voltage, lux, nr_iterations); pilihan kolom dan desimal diabaikan.").color(Color32::GRAY).italics());
+                }
+
+                ui.add_space(5.0);
+                if ui.button(RichText::new("💾 Ekspor").color(Color32::WHITE)).clicked() {
+                    match self.build_export_options() {
+                        Ok(options) => {
+                            let mut path = PathBuf::from(self.export_path.trim());
+                            if let (None, Some(extension)) = (path.extension(), options.format.extension()) {
+                                path.set_extension(extension);
+                            }
+                            *self.export_status.lock().unwrap() = format!("Mengekspor ke {}...", path.display());
+                            self.export_request = Some((path, options));
+                        },
+                        Err(e) => *self.export_status.lock().unwrap() = format!("GAGAL: {}", e),
+                    }
+                }
+
+                let status = self.export_status.lock().unwrap();
+                if !status.is_empty() {
+                    let color = if status.contains("GAGAL") {
+                        Color32::RED
+                    } else {
+                        Color32::LIGHT_GREEN
+                    };
+                    ui.label(RichText::new(status.as_str()).color(color).italics());
+                }
+            });
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, data: &Vec<Document>) {
+    pub fn show(&mut self, ui: &mut egui::Ui, data: &[StoredRecord]) {
         ui.vertical_centered(|ui| {
             ui.add_space(10.0);
-            ui.heading(RichText::new("Data Tersimpan (MongoDB)").color(Color32::WHITE).strong());
+            ui.heading(RichText::new(format!("Data Tersimpan ({})", self.storage_backend.label())).color(Color32::WHITE).strong());
             ui.add_space(15.0);
         });
 
@@ -34,6 +400,11 @@ impl DatabaseScreen {
         });
         ui.add_space(10.0);
 
+        self.show_export_panel(ui);
+        ui.add_space(5.0);
+        self.show_import_panel(ui);
+        ui.add_space(10.0);
+
         ScrollArea::vertical()
             .auto_shrink([false; 2])
             .max_height(ui.available_height() - 20.0)
@@ -41,7 +412,7 @@ impl DatabaseScreen {
 
             ui.add_space(5.0);
             Grid::new("database_header_grid")
-                .num_columns(3)
+                .num_columns(4)
                 .spacing([20.0, 8.0])
                 .striped(true)
                 .show(ui, |ui_grid| {
@@ -51,6 +422,7 @@ impl DatabaseScreen {
                         DatabaseDataType::NewtonRaphsonResults => ui_grid.strong(RichText::new("Akar (Newton-Raphson)").color(Color32::LIGHT_BLUE)),
                     };
                     ui_grid.strong(RichText::new("Waktu Pengukuran").color(Color32::LIGHT_BLUE));
+                    ui_grid.strong(RichText::new("Sumber").color(Color32::LIGHT_BLUE));
                     ui_grid.end_row();
                 });
             ui.add_space(5.0);
@@ -60,44 +432,31 @@ impl DatabaseScreen {
                 ui.vertical_centered(|ui_centered| {
                     ui_centered.add_space(20.0);
                     ui_centered.label(RichText::new("Belum ada data di database.").color(Color32::GRAY).italics());
-                    ui_centered.label(RichText::new("Pastikan sensor terhubung dan pengiriman data ke MongoDB aktif.").color(Color32::GRAY).italics());
+                    ui_centered.label(RichText::new(format!("Pastikan sensor terhubung dan pengiriman data ke {} aktif.", self.storage_backend.label())).color(Color32::GRAY).italics());
                 });
             } else {
                 Grid::new("database_data_grid")
-                    .num_columns(3)
+                    .num_columns(4)
                     .spacing([20.0, 8.0])
                     .striped(true)
                     .show(ui, |ui_grid| {
-                        for (i, doc) in data.iter().enumerate() {
+                        for (i, record) in data.iter().enumerate() {
                             let doc_index = i + 1;
 
-                            let value_str = match self.current_display_type {
-                                DatabaseDataType::PhotodiodeData => {
-                                    if let Some(bson_value) = doc.get("photodiode_value") {
-                                        match bson_value {
-                                            Bson::Double(v) => format!("{:.2}", v),
-                                            Bson::Int32(v) => format!("{:.0}", v), 
-                                            _ => "N/A (Tipe Data Tak Dikenal)".to_string(),
-                                        }
-                                    } else {
-                                        "N/A (Field Tidak Ditemukan)".to_string()
-                                    }
-                                },
-                                DatabaseDataType::NewtonRaphsonResults => doc.get_f64("akar_terakhir")
-                                    .map(|v| format!("{:.8}", v))
-                                    .unwrap_or_else(|_| "N/A".to_string()),
+                            let value_str = match (&self.current_display_type, record.value) {
+                                (DatabaseDataType::PhotodiodeData, Some(v)) => format!("{:.2}", v),
+                                (DatabaseDataType::NewtonRaphsonResults, Some(v)) => format!("{:.8}", v),
+                                (_, None) => "N/A".to_string(),
                             };
 
-                            let timestamp_str = doc.get_datetime("timestamp")
-                                .map(|dt| {
-                                    let utc_dt = dt.to_chrono();
-                                    utc_dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()
-                                })
-                                .unwrap_or_else(|_| "N/A".to_string());
+                            let timestamp_str = record.timestamp
+                                .map(|utc_dt| utc_dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                                .unwrap_or_else(|| "N/A".to_string());
 
                             ui_grid.label(RichText::new(format!("{}", doc_index)).color(Color32::WHITE));
                             ui_grid.label(RichText::new(&value_str).color(Color32::YELLOW).strong());
                             ui_grid.label(RichText::new(&timestamp_str).color(Color32::LIGHT_GREEN));
+                            ui_grid.label(RichText::new(export::source_label(record)).color(Color32::GRAY));
                             ui_grid.end_row();
                         }
                     });
@@ -1,58 +1,561 @@
 // src/db.rs
 #![allow(unused_imports)] // Mengizinkan import yang tidak digunakan untuk kompatibilitas
 
-use mongodb::{Client, options::ClientOptions, Database, bson::doc};
+use mongodb::{Client, options::{ClientOptions, IndexOptions}, Database, IndexModel, bson::doc};
 use std::error::Error;
-use mongodb::bson::Document;
+use mongodb::bson::{Bson, Document};
 use futures::stream::TryStreamExt;
 use mongodb::bson::DateTime; // Diperlukan untuk DateTime::now()
-use chrono::Utc; // Diperlukan untuk konversi to_chrono() di database_screen.rs
+use chrono::Utc;
+use crate::calibration_profile::CalibrationProfile;
+use crate::crop::Bed;
+use crate::csv_import::ImportRow;
+use crate::dli::DailyLightIntegral;
+use crate::outliers::OutlierFlag;
+use crate::recompute::{self, RecomputedResult};
+use crate::storage::{self, AggregateRecord, ResultMetadata, RetentionPolicy, SeriesKind, Storage, StorageResult, StoredRecord};
+use crate::uncertainty::ConfidenceInterval;
 // HAPUS BARIS INI: use mongodb::bson::datetime::DateTimeExt; 
 
-pub async fn connect_db() -> Result<Database, Box<dyn Error>> {
+pub async fn connect_db() -> mongodb::error::Result<Database> {
     let client_uri = "mongodb://localhost:27017";
     let options = ClientOptions::parse(client_uri).await?;
     let client = Client::with_options(options)?;
     Ok(client.database("amitdb"))
 }
 
-pub async fn insert_photodiode_data(db: &Database, photodiode_value: f64) -> mongodb::error::Result<()> {
+pub async fn insert_photodiode_data(db: &Database, sensor_id: &str, photodiode_value: f64, outlier: Option<OutlierFlag>) -> mongodb::error::Result<()> {
     let collection = db.collection("photodiode_data");
     let doc = doc! { 
+        "sensor_id": sensor_id,
         "photodiode_value": photodiode_value, 
+        "outlier": outlier.map(|flag| flag.key()),
         "timestamp": mongodb::bson::DateTime::now()
     };
     collection.insert_one(doc).await?;
     Ok(())
 }
 
-pub async fn get_all_photodiode_data(db: &Database) -> mongodb::error::Result<Vec<Document>> {
-    let collection = db.collection::<Document>("photodiode_data");
-    let mut cursor = collection.find(doc! {}).await?;
-    let mut results = Vec::new();
-    while let Some(doc) = cursor.try_next().await? {
-        results.push(doc);
-    }
-    Ok(results)
-}
-
-pub async fn insert_newton_raphson_result(db: &Database, akar: f64, iterations_history: Vec<f64>) -> mongodb::error::Result<()> {
+pub async fn insert_newton_raphson_result(db: &Database, sensor_id: &str, akar: f64, iterations_history: Vec<f64>, metadata: &ResultMetadata) -> mongodb::error::Result<()> {
     let collection = db.collection("newton_raphson_results");
     let doc = doc! {
+        "sensor_id": sensor_id,
         "akar_terakhir": akar,
         "riwayat_iterasi": iterations_history,
+        "calibration_profile": metadata.calibration_profile.as_deref(),
+        "lux_lower": metadata.confidence_interval.map(|ci| ci.lower),
+        "lux_upper": metadata.confidence_interval.map(|ci| ci.upper),
+        "ppfd": metadata.ppfd,
+        "outlier": metadata.outlier.map(|flag| flag.key()),
         "timestamp": mongodb::bson::DateTime::now()
     };
     collection.insert_one(doc).await?;
     Ok(())
 }
 
-pub async fn get_all_newton_raphson_results(db: &Database) -> mongodb::error::Result<Vec<Document>> {
-    let collection = db.collection::<Document>("newton_raphson_results");
-    let mut cursor = collection.find(doc! {}).await?;
+pub async fn insert_imported_rows(db: &Database, sensor_id: &str, source: &str, rows: &[ImportRow]) -> mongodb::error::Result<usize> {
+    let photodiode_docs: Vec<Document> = rows.iter()
+        .filter_map(|row| row.raw_value.map(|value| doc! {
+            "sensor_id": sensor_id,
+            "source": source,
+            "photodiode_value": value,
+            "timestamp": DateTime::from_chrono(row.timestamp),
+        }))
+        .collect();
+    let newton_raphson_docs: Vec<Document> = rows.iter()
+        .filter_map(|row| row.lux.map(|lux| doc! {
+            "sensor_id": sensor_id,
+            "source": source,
+            "akar_terakhir": lux,
+            "riwayat_iterasi": Vec::<f64>::new(),
+            "timestamp": DateTime::from_chrono(row.timestamp),
+        }))
+        .collect();
+
+    let mut inserted = 0;
+    if !photodiode_docs.is_empty() {
+        inserted += db.collection::<Document>("photodiode_data").insert_many(photodiode_docs).await?.inserted_ids.len();
+    }
+    if !newton_raphson_docs.is_empty() {
+        inserted += db.collection::<Document>("newton_raphson_results").insert_many(newton_raphson_docs).await?.inserted_ids.len();
+    }
+    Ok(inserted)
+}
+
+pub async fn insert_recomputed_results(db: &Database, sensor_id: &str, result_set: &str, calibration_profile: &str, results: &[RecomputedResult]) -> mongodb::error::Result<usize> {
+    if results.is_empty() {
+        return Ok(0);
+    }
+    let docs: Vec<Document> = results.iter()
+        .map(|result| doc! {
+            "sensor_id": sensor_id,
+            "source": result_set,
+            "akar_terakhir": result.lux,
+            "riwayat_iterasi": result.iterations_history.clone(),
+            "calibration_profile": calibration_profile,
+            "lux_lower": result.confidence_interval.map(|ci| ci.lower),
+            "lux_upper": result.confidence_interval.map(|ci| ci.upper),
+            "ppfd": result.ppfd,
+            "outlier": result.outlier.map(|flag| flag.key()),
+            "timestamp": DateTime::from_chrono(result.timestamp),
+        })
+        .collect();
+    Ok(db.collection::<Document>("newton_raphson_results").insert_many(docs).await?.inserted_ids.len())
+}
+
+pub async fn find_range(db: &Database, collection_name: &str, sensor_id: Option<&str>, from: Option<DateTime>, to: Option<DateTime>) -> mongodb::error::Result<Vec<Document>> {
+    let collection = db.collection::<Document>(collection_name);
+    let mut cursor = collection.find(range_filter(sensor_id, from, to))
+        .sort(doc! { "timestamp": 1 })
+        .await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(doc);
+    }
+    Ok(results)
+}
+
+// Ringkasan per jam di `hourly_collection` digabung dengan data mentah, dibobot jumlah sampelnya.
+pub async fn aggregate_range(db: &Database, (collection_name, hourly_collection): (&str, &str), value_field: &str, sensor_id: Option<&str>, from: Option<DateTime>, to: Option<DateTime>, bucket_ms: i64) -> mongodb::error::Result<Vec<Document>> {
+    let collection = db.collection::<Document>(collection_name);
+    let value_path = format!("${}", value_field);
+    let mut filter = range_filter(sensor_id, from, to);
+    // Set hasil hitung ulang memakai timestamp data asli; tanpa filter ini kalibrasi lama dan baru dirata-rata bersama.
+    filter.insert("source", doc! { "$not": mongodb::bson::Regex {
+        pattern: format!("^{}", recompute::RESULT_SET_PREFIX),
+        options: String::new(),
+    } });
+    let pipeline = vec![
+        doc! { "$match": filter.clone() },
+        doc! { "$project": {
+            "timestamp": 1,
+            "sum": &value_path,
+            "count": { "$literal": 1 },
+            "min": &value_path,
+            "max": &value_path,
+        } },
+        doc! { "$unionWith": {
+            "coll": hourly_collection,
+            "pipeline": [
+                { "$match": filter },
+                { "$project": { "timestamp": 1, "sum": 1, "count": 1, "min": 1, "max": 1 } },
+            ],
+        } },
+        doc! { "$group": {
+            "_id": { "$toDate": { "$subtract": [
+                { "$toLong": "$timestamp" },
+                { "$mod": [{ "$toLong": "$timestamp" }, bucket_ms] },
+            ] } },
+            "count": { "$sum": "$count" },
+            "sum": { "$sum": "$sum" },
+            "min": { "$min": "$min" },
+            "max": { "$max": "$max" },
+        } },
+        doc! { "$set": { "avg": { "$divide": ["$sum", "$count"] } } },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+    let mut cursor = collection.aggregate(pipeline).await?;
     let mut results = Vec::new();
     while let Some(doc) = cursor.try_next().await? {
         results.push(doc);
     }
     Ok(results)
-}
\ No newline at end of file
+}
+
+fn range_filter(sensor_id: Option<&str>, from: Option<DateTime>, to: Option<DateTime>) -> Document {
+    let mut filter = Document::new();
+    if let Some(sensor_id) = sensor_id {
+        filter.insert("sensor_id", sensor_id);
+    }
+    let mut range = Document::new();
+    if let Some(from) = from {
+        range.insert("$gte", from);
+    }
+    if let Some(to) = to {
+        range.insert("$lt", to);
+    }
+    if !range.is_empty() {
+        filter.insert("timestamp", range);
+    }
+    filter
+}
+
+pub async fn insert_calibration_profile(db: &Database, profile: &CalibrationProfile) -> StorageResult<()> {
+    let doc = mongodb::bson::to_document(profile)?;
+    db.collection::<Document>("calibration_profiles").insert_one(doc).await?;
+    Ok(())
+}
+
+pub async fn find_calibration_profiles(db: &Database, sensor_id: Option<&str>) -> StorageResult<Vec<CalibrationProfile>> {
+    let mut filter = Document::new();
+    if let Some(sensor_id) = sensor_id {
+        filter.insert("sensor_id", sensor_id);
+    }
+    let mut cursor = db.collection::<Document>("calibration_profiles").find(filter).await?;
+    let mut profiles = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        profiles.push(mongodb::bson::from_document::<CalibrationProfile>(doc)?);
+    }
+    storage::sort_profiles(&mut profiles);
+    Ok(profiles)
+}
+
+pub async fn insert_profile_activation(db: &Database, sensor_id: &str, profile_id: &str) -> mongodb::error::Result<()> {
+    let doc = doc! {
+        "sensor_id": sensor_id,
+        "profile_id": profile_id,
+        "timestamp": DateTime::now(),
+    };
+    db.collection::<Document>("calibration_activations").insert_one(doc).await?;
+    Ok(())
+}
+
+pub async fn find_active_profiles(db: &Database) -> mongodb::error::Result<Vec<(String, String)>> {
+    let pipeline = vec![
+        doc! { "$sort": { "timestamp": -1 } },
+        doc! { "$group": { "_id": "$sensor_id", "profile_id": { "$first": "$profile_id" } } },
+    ];
+    let mut cursor = db.collection::<Document>("calibration_activations").aggregate(pipeline).await?;
+    let mut active = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        if let (Ok(sensor_id), Ok(profile_id)) = (doc.get_str("_id"), doc.get_str("profile_id")) {
+            active.push((sensor_id.to_string(), profile_id.to_string()));
+        }
+    }
+    Ok(active)
+}
+
+pub async fn upsert_daily_light_integral(db: &Database, dli: &DailyLightIntegral) -> StorageResult<()> {
+    let doc = mongodb::bson::to_document(dli)?;
+    db.collection::<Document>("daily_light_integrals")
+        .replace_one(doc! { "sensor_id": &dli.sensor_id, "date": dli.date.to_string() }, doc)
+        .upsert(true)
+        .await?;
+    Ok(())
+}
+
+pub async fn find_daily_light_integrals(db: &Database, sensor_id: Option<&str>, from: Option<chrono::NaiveDate>) -> StorageResult<Vec<DailyLightIntegral>> {
+    let mut filter = Document::new();
+    if let Some(sensor_id) = sensor_id {
+        filter.insert("sensor_id", sensor_id);
+    }
+    if let Some(from) = from {
+        filter.insert("date", doc! { "$gte": from.to_string() });
+    }
+    let mut cursor = db.collection::<Document>("daily_light_integrals")
+        .find(filter)
+        .sort(doc! { "date": 1, "sensor_id": 1 })
+        .await?;
+    let mut totals = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        totals.push(mongodb::bson::from_document::<DailyLightIntegral>(doc)?);
+    }
+    Ok(totals)
+}
+
+pub async fn upsert_bed(db: &Database, bed: &Bed) -> StorageResult<()> {
+    let doc = mongodb::bson::to_document(bed)?;
+    db.collection::<Document>("beds")
+        .replace_one(doc! { "id": &bed.id }, doc)
+        .upsert(true)
+        .await?;
+    Ok(())
+}
+
+pub async fn find_beds(db: &Database) -> StorageResult<Vec<Bed>> {
+    let mut cursor = db.collection::<Document>("beds")
+        .find(Document::new())
+        .sort(doc! { "id": 1 })
+        .await?;
+    let mut beds = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        beds.push(mongodb::bson::from_document::<Bed>(doc)?);
+    }
+    Ok(beds)
+}
+
+pub const PHOTODIODE_HOURLY_COLLECTION: &str = "photodiode_data_hourly";
+pub const NEWTON_RAPHSON_HOURLY_COLLECTION: &str = "newton_raphson_results_hourly";
+
+const TIMESTAMP_INDEX_NAME: &str = "timestamp_1";
+const TIMESTAMP_TTL_INDEX_NAME: &str = "timestamp_ttl";
+
+// Memastikan index `timestamp` ada di semua koleksi. TTL (opsional) hanya untuk data mentah photodiode.
+pub async fn ensure_indexes(db: &Database, raw_ttl: Option<std::time::Duration>) -> mongodb::error::Result<()> {
+    ensure_timestamp_index(&db.collection::<Document>("photodiode_data"), raw_ttl).await?;
+    ensure_timestamp_index(&db.collection::<Document>("newton_raphson_results"), None).await?;
+    ensure_timestamp_index(&db.collection::<Document>(PHOTODIODE_HOURLY_COLLECTION), None).await?;
+    ensure_timestamp_index(&db.collection::<Document>(NEWTON_RAPHSON_HOURLY_COLLECTION), None).await?;
+    Ok(())
+}
+
+async fn ensure_timestamp_index(collection: &mongodb::Collection<Document>, ttl: Option<std::time::Duration>) -> mongodb::error::Result<()> {
+    let (wanted_name, other_name) = match ttl {
+        Some(_) => (TIMESTAMP_TTL_INDEX_NAME, TIMESTAMP_INDEX_NAME),
+        None => (TIMESTAMP_INDEX_NAME, TIMESTAMP_TTL_INDEX_NAME),
+    };
+
+    let mut up_to_date = false;
+    let mut cursor = collection.list_indexes().await?;
+    while let Some(index) = cursor.try_next().await? {
+        let name = index.options.as_ref().and_then(|o| o.name.clone()).unwrap_or_default();
+        let expire_after = index.options.as_ref().and_then(|o| o.expire_after);
+        if name == other_name || (name == wanted_name && expire_after != ttl) {
+            // Index dengan key yang sama tapi opsi berbeda tidak bisa berdampingan, jadi dibuang dulu.
+            collection.drop_index(&name).await?;
+        } else if name == wanted_name {
+            up_to_date = true;
+        }
+    }
+
+    if !up_to_date {
+        let options = IndexOptions::builder()
+            .name(wanted_name.to_string())
+            .expire_after(ttl)
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "timestamp": 1 })
+            .options(options)
+            .build();
+        collection.create_index(model).await?;
+    }
+    Ok(())
+}
+
+// Data mentah yang lebih tua dari `raw_retention_days` diringkas per jam lalu dihapus.
+pub async fn apply_retention_policy(db: &Database, policy: &RetentionPolicy) -> mongodb::error::Result<()> {
+    if !policy.downsampling_enabled {
+        return Ok(());
+    }
+
+    const HOUR_MS: i64 = 3_600_000;
+    let retention_ms = policy.raw_retention_days as i64 * 24 * HOUR_MS;
+    let cutoff_ms = DateTime::now().timestamp_millis() - retention_ms;
+    let cutoff = DateTime::from_millis(cutoff_ms - cutoff_ms.rem_euclid(HOUR_MS));
+
+    downsample_collection(db, "photodiode_data", "photodiode_value", PHOTODIODE_HOURLY_COLLECTION, cutoff).await?;
+    downsample_collection(db, "newton_raphson_results", "akar_terakhir", NEWTON_RAPHSON_HOURLY_COLLECTION, cutoff).await?;
+    Ok(())
+}
+
+// Diproses per batch `_id`: hanya dokumen yang benar-benar ikut diringkas yang dihapus, sehingga data
+// impor yang masuk di antara agregasi dan penghapusan tidak hilang tanpa ringkasan.
+async fn downsample_collection(db: &Database, collection_name: &str, value_field: &str, target: &str, cutoff: DateTime) -> mongodb::error::Result<()> {
+    const BATCH_SIZE: i64 = 10_000;
+    let collection = db.collection::<Document>(collection_name);
+    let value_path = format!("${}", value_field);
+    let mut total_deleted = 0;
+    loop {
+        let mut cursor = collection.find(doc! { "timestamp": { "$lt": cutoff } })
+            .projection(doc! { "_id": 1 })
+            .limit(BATCH_SIZE)
+            .await?;
+        let mut ids = Vec::new();
+        while let Some(doc) = cursor.try_next().await? {
+            if let Some(id) = doc.get("_id") {
+                ids.push(id.clone());
+            }
+        }
+        if ids.is_empty() {
+            break;
+        }
+
+        let pipeline = vec![
+            doc! { "$match": { "_id": { "$in": ids.clone() } } },
+            // `source` ikut menjadi kunci agar data live, impor berlabel dan set hitung ulang tetap terpisah.
+            doc! { "$group": {
+                "_id": {
+                    "timestamp": { "$dateTrunc": { "date": "$timestamp", "unit": "hour" } },
+                    "sensor_id": "$sensor_id",
+                    "source": "$source",
+                },
+                "sum": { "$sum": &value_path },
+                "count": { "$sum": 1 },
+                "min": { "$min": &value_path },
+                "max": { "$max": &value_path },
+            } },
+            doc! { "$set": {
+                "timestamp": "$_id.timestamp",
+                "sensor_id": "$_id.sensor_id",
+                "source": "$_id.source",
+                "avg": { "$divide": ["$sum", "$count"] },
+            } },
+            // Jam yang sudah pernah diringkas (batch sebelumnya atau data impor terlambat) digabungkan, bukan ditimpa.
+            doc! { "$merge": {
+                "into": target,
+                "on": "_id",
+                "whenMatched": [
+                    { "$set": {
+                        "sum": { "$add": ["$sum", "$$new.sum"] },
+                        "count": { "$add": ["$count", "$$new.count"] },
+                        "min": { "$min": ["$min", "$$new.min"] },
+                        "max": { "$max": ["$max", "$$new.max"] },
+                    } },
+                    { "$set": { "avg": { "$divide": ["$sum", "$count"] } } },
+                ],
+                "whenNotMatched": "insert",
+            } },
+        ];
+        collection.aggregate(pipeline).await?;
+
+        total_deleted += collection.delete_many(doc! { "_id": { "$in": ids } }).await?.deleted_count;
+    }
+    if total_deleted > 0 {
+        eprintln!("[Retensi] {} dokumen lama dari '{}' diringkas ke '{}'.", total_deleted, collection_name, target);
+    }
+    Ok(())
+}
+
+
+pub struct MongoStorage {
+    runtime: tokio::runtime::Runtime,
+    db: Database,
+}
+
+impl MongoStorage {
+    pub fn connect() -> StorageResult<Self> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        let db = runtime.block_on(connect_db())?;
+        Ok(Self { runtime, db })
+    }
+
+    // (koleksi data mentah, field nilai, koleksi ringkasan per jam).
+    fn collection_for(kind: SeriesKind) -> (&'static str, &'static str, &'static str) {
+        match kind {
+            SeriesKind::Photodiode => ("photodiode_data", "photodiode_value", PHOTODIODE_HOURLY_COLLECTION),
+            SeriesKind::NewtonRaphson => ("newton_raphson_results", "akar_terakhir", NEWTON_RAPHSON_HOURLY_COLLECTION),
+        }
+    }
+}
+
+fn bson_to_f64(value: &Bson) -> Option<f64> {
+    match value {
+        Bson::Double(v) => Some(*v),
+        Bson::Int32(v) => Some(*v as f64),
+        Bson::Int64(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+impl Storage for MongoStorage {
+    fn insert_reading(&self, sensor_id: &str, photodiode_value: f64, outlier: Option<OutlierFlag>) -> StorageResult<()> {
+        self.runtime.block_on(insert_photodiode_data(&self.db, sensor_id, photodiode_value, outlier))?;
+        Ok(())
+    }
+
+    fn insert_result(&self, sensor_id: &str, akar: f64, iterations_history: Vec<f64>, metadata: &ResultMetadata) -> StorageResult<()> {
+        self.runtime.block_on(insert_newton_raphson_result(&self.db, sensor_id, akar, iterations_history, metadata))?;
+        Ok(())
+    }
+
+    fn insert_imported(&self, sensor_id: &str, source: &str, rows: &[ImportRow]) -> StorageResult<usize> {
+        Ok(self.runtime.block_on(insert_imported_rows(&self.db, sensor_id, source, rows))?)
+    }
+
+    fn insert_recomputed(&self, sensor_id: &str, result_set: &str, calibration_profile: &str, results: &[RecomputedResult]) -> StorageResult<usize> {
+        Ok(self.runtime.block_on(insert_recomputed_results(&self.db, sensor_id, result_set, calibration_profile, results))?)
+    }
+
+    fn query_range(&self, kind: SeriesKind, sensor_id: Option<&str>, from: Option<chrono::DateTime<Utc>>, to: Option<chrono::DateTime<Utc>>) -> StorageResult<Vec<StoredRecord>> {
+        let (collection_name, value_field, hourly_collection) = Self::collection_for(kind);
+        let (from, to) = (from.map(DateTime::from_chrono), to.map(DateTime::from_chrono));
+        let docs = self.runtime.block_on(find_range(&self.db, collection_name, sensor_id, from, to))?;
+        let summaries = self.runtime.block_on(find_range(&self.db, hourly_collection, sensor_id, from, to))?;
+
+        let summarized = summaries.iter().map(|doc| StoredRecord {
+            timestamp: doc.get_datetime("timestamp").ok().map(|dt| dt.to_chrono()),
+            sensor_id: doc.get_str("sensor_id").ok().map(str::to_string),
+            source: doc.get_str("source").ok().map(str::to_string),
+            value: doc.get("avg").and_then(bson_to_f64),
+            iterations_history: Vec::new(),
+            calibration_profile: None,
+            confidence_interval: None,
+            ppfd: None,
+            outlier: None,
+            summarized_count: doc.get("count").and_then(bson_to_f64).map(|count| count as u64),
+        });
+        let mut records: Vec<StoredRecord> = summarized.chain(docs.iter().map(|doc| StoredRecord {
+            timestamp: doc.get_datetime("timestamp").ok().map(|dt| dt.to_chrono()),
+            sensor_id: doc.get_str("sensor_id").ok().map(str::to_string),
+            source: doc.get_str("source").ok().map(str::to_string),
+            value: doc.get(value_field).and_then(bson_to_f64),
+            iterations_history: doc.get_array("riwayat_iterasi")
+                .map(|history| history.iter().filter_map(bson_to_f64).collect())
+                .unwrap_or_default(),
+            calibration_profile: doc.get_str("calibration_profile").ok().map(str::to_string),
+            confidence_interval: match (doc.get_f64("lux_lower"), doc.get_f64("lux_upper")) {
+                (Ok(lower), Ok(upper)) => Some(ConfidenceInterval { lower, upper }),
+                _ => None,
+            },
+            ppfd: doc.get_f64("ppfd").ok(),
+            outlier: doc.get_str("outlier").ok().and_then(OutlierFlag::from_key),
+            summarized_count: None,
+        })).collect();
+        records.sort_by_key(|record| record.timestamp);
+        Ok(records)
+    }
+
+    fn aggregate(&self, kind: SeriesKind, sensor_id: Option<&str>, from: Option<chrono::DateTime<Utc>>, to: Option<chrono::DateTime<Utc>>, bucket: chrono::Duration) -> StorageResult<Vec<AggregateRecord>> {
+        let (collection_name, value_field, hourly_collection) = Self::collection_for(kind);
+        let docs = self.runtime.block_on(aggregate_range(
+            &self.db,
+            (collection_name, hourly_collection),
+            value_field,
+            sensor_id,
+            from.map(DateTime::from_chrono),
+            to.map(DateTime::from_chrono),
+            bucket.num_milliseconds().max(1),
+        ))?;
+
+        Ok(docs.iter().filter_map(|doc| Some(AggregateRecord {
+            bucket_start: doc.get_datetime("_id").ok()?.to_chrono(),
+            count: doc.get("count").and_then(bson_to_f64)? as u64,
+            min: doc.get("min").and_then(bson_to_f64)?,
+            max: doc.get("max").and_then(bson_to_f64)?,
+            avg: doc.get("avg").and_then(bson_to_f64)?,
+        })).collect())
+    }
+
+    fn ensure_schema(&self, policy: &RetentionPolicy) -> StorageResult<()> {
+        self.runtime.block_on(ensure_indexes(&self.db, policy.raw_ttl()))?;
+        Ok(())
+    }
+
+    fn apply_retention_policy(&self, policy: &RetentionPolicy) -> StorageResult<()> {
+        self.runtime.block_on(apply_retention_policy(&self.db, policy))?;
+        Ok(())
+    }
+
+    fn save_calibration_profile(&self, profile: &CalibrationProfile) -> StorageResult<()> {
+        self.runtime.block_on(insert_calibration_profile(&self.db, profile))
+    }
+
+    fn list_calibration_profiles(&self, sensor_id: Option<&str>) -> StorageResult<Vec<CalibrationProfile>> {
+        self.runtime.block_on(find_calibration_profiles(&self.db, sensor_id))
+    }
+
+    fn record_profile_activation(&self, sensor_id: &str, profile_id: &str) -> StorageResult<()> {
+        self.runtime.block_on(insert_profile_activation(&self.db, sensor_id, profile_id))?;
+        Ok(())
+    }
+
+    fn active_calibration_profiles(&self) -> StorageResult<Vec<(String, String)>> {
+        Ok(self.runtime.block_on(find_active_profiles(&self.db))?)
+    }
+
+    fn save_daily_light_integral(&self, dli: &DailyLightIntegral) -> StorageResult<()> {
+        self.runtime.block_on(upsert_daily_light_integral(&self.db, dli))
+    }
+
+    fn list_daily_light_integrals(&self, sensor_id: Option<&str>, from: Option<chrono::NaiveDate>) -> StorageResult<Vec<DailyLightIntegral>> {
+        self.runtime.block_on(find_daily_light_integrals(&self.db, sensor_id, from))
+    }
+
+    fn save_bed(&self, bed: &Bed) -> StorageResult<()> {
+        self.runtime.block_on(upsert_bed(&self.db, bed))
+    }
+
+    fn list_beds(&self) -> StorageResult<Vec<Bed>> {
+        self.runtime.block_on(find_beds(&self.db))
+    }
+}
@@ -0,0 +1,316 @@
+// src/diagnostics.rs
+// Diagnostik kesehatan sensor dari agregat photodiode per menit: nilai macet (flatline), saturasi ADC,
+// gelap terus-menerus, celah data, dan drift relatif terhadap sensor acuan.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+
+use crate::adc::AdcSettings;
+use crate::storage::AggregateRecord;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HealthIssue {
+    Flatline,
+    Saturated,
+    ProlongedDark,
+    MissingData,
+    Drift,
+}
+
+impl HealthIssue {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthIssue::Flatline => "Nilai Macet",
+            HealthIssue::Saturated => "Saturasi ADC",
+            HealthIssue::ProlongedDark => "Gelap Terus-menerus",
+            HealthIssue::MissingData => "Data Hilang",
+            HealthIssue::Drift => "Drift terhadap Acuan",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagnosticsSettings {
+    // Rentang data yang dianalisis setiap pemeriksaan.
+    pub window_hours: f64,
+    // Nilai yang tidak berubah lebih dari toleransi (hitungan ADC) selama ini dianggap macet.
+    pub flatline_minutes: f64,
+    pub flatline_tolerance: f64,
+    // Jarak dari 0 atau skala penuh (hitungan ADC) yang dianggap saturasi.
+    pub saturation_margin: f64,
+    pub saturation_minutes: f64,
+    // Porsi skala penuh yang dianggap gelap; malam hari wajar sampai `max_dark_hours`.
+    pub dark_fraction: f64,
+    pub max_dark_hours: f64,
+    pub max_gap_minutes: f64,
+    // Sensor pembanding untuk drift; `None` menonaktifkan pemeriksaan drift.
+    pub reference_sensor: Option<String>,
+    pub drift_threshold_percent: f64,
+    // 0 = hanya manual.
+    pub auto_interval_minutes: u32,
+}
+
+impl DiagnosticsSettings {
+    pub fn new() -> Self {
+        Self {
+            window_hours: 72.0,
+            flatline_minutes: 60.0,
+            flatline_tolerance: 1.0,
+            saturation_margin: 2.0,
+            saturation_minutes: 10.0,
+            dark_fraction: 0.02,
+            max_dark_hours: 16.0,
+            max_gap_minutes: 10.0,
+            reference_sensor: None,
+            drift_threshold_percent: 15.0,
+            auto_interval_minutes: 15,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthWarning {
+    pub issue: HealthIssue,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    // Masih berlangsung sampai data terakhir (atau sampai sekarang untuk data hilang).
+    pub ongoing: bool,
+    pub detail: String,
+}
+
+impl HealthWarning {
+    pub fn duration_hours(&self) -> f64 {
+        (self.end - self.start).num_seconds() as f64 / 3600.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DriftEstimate {
+    pub reference_sensor: String,
+    // Rasio porsi cahaya sensor / acuan per jam, hanya jam terang.
+    pub hourly_ratio: Vec<(DateTime<Utc>, f64)>,
+    // Perubahan rasio dari awal ke akhir jendela menurut regresi linier.
+    pub change_percent: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensorHealth {
+    pub sensor_id: String,
+    pub checked_at: DateTime<Utc>,
+    pub sample_count: u64,
+    pub last_sample: Option<DateTime<Utc>>,
+    pub warnings: Vec<HealthWarning>,
+    pub drift: Option<DriftEstimate>,
+}
+
+impl SensorHealth {
+    pub fn active_warnings(&self) -> impl Iterator<Item = &HealthWarning> {
+        self.warnings.iter().filter(|warning| warning.ongoing)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.active_warnings().next().is_none()
+    }
+
+    // Menyimpan estimasi drift dan menambah peringatan bila melewati ambang.
+    pub fn apply_drift(&mut self, drift: DriftEstimate, settings: &DiagnosticsSettings) {
+        if drift.change_percent.abs() >= settings.drift_threshold_percent {
+            if let (Some((start, _)), Some((end, _))) = (drift.hourly_ratio.first(), drift.hourly_ratio.last()) {
+                self.warnings.push(HealthWarning {
+                    issue: HealthIssue::Drift,
+                    start: *start,
+                    end: *end + Duration::hours(1),
+                    ongoing: true,
+                    detail: format!("Rasio terhadap {} berubah {:+.1}%", drift.reference_sensor, drift.change_percent),
+                });
+            }
+        }
+        self.drift = Some(drift);
+    }
+}
+
+fn bucket_end(record: &AggregateRecord, bucket: Duration) -> DateTime<Utc> {
+    record.bucket_start + bucket
+}
+
+fn format_minutes(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 120 {
+        format!("{:.1} jam", minutes as f64 / 60.0)
+    } else {
+        format!("{} menit", minutes)
+    }
+}
+
+// Rangkaian bucket berurutan tanpa celah yang memenuhi `predicate`, sebagai rentang indeks inklusif.
+fn runs(buckets: &[AggregateRecord], bucket: Duration, max_gap: Duration, predicate: impl Fn(&AggregateRecord) -> bool) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, record) in buckets.iter().enumerate() {
+        let contiguous = i > 0 && record.bucket_start - bucket_end(&buckets[i - 1], bucket) <= max_gap;
+        if start.is_some() && (!predicate(record) || !contiguous) {
+            result.push((start.take().unwrap(), i - 1));
+        }
+        if start.is_none() && predicate(record) {
+            start = Some(i);
+        }
+    }
+    if let Some(start) = start {
+        result.push((start, buckets.len() - 1));
+    }
+    result
+}
+
+// `buckets` terurut menurut waktu, satu sensor, ukuran `bucket` (biasanya 1 menit).
+pub fn analyse(sensor_id: &str, buckets: &[AggregateRecord], bucket: Duration, adc: &AdcSettings, settings: &DiagnosticsSettings, now: DateTime<Utc>) -> SensorHealth {
+    let max_gap = Duration::seconds((settings.max_gap_minutes * 60.0) as i64);
+    let full_scale = adc.max_value();
+    let last_index = buckets.len().checked_sub(1);
+    let mut warnings = Vec::new();
+    let mut push_run = |issue: HealthIssue, (first, last): (usize, usize), min_duration_minutes: f64, detail: String| {
+        let start = buckets[first].bucket_start;
+        let end = bucket_end(&buckets[last], bucket);
+        if (end - start).num_seconds() as f64 >= min_duration_minutes * 60.0 {
+            warnings.push(HealthWarning { issue, start, end, ongoing: Some(last) == last_index, detail });
+        }
+    };
+
+    let is_dark = |record: &AggregateRecord| adc.light_fraction(record.max).max(adc.light_fraction(record.min)) <= settings.dark_fraction;
+    let is_saturated = |record: &AggregateRecord| {
+        let at_rail = record.min >= full_scale - settings.saturation_margin || record.max <= settings.saturation_margin;
+        at_rail && !is_dark(record)
+    };
+
+    for run in runs(buckets, bucket, max_gap, is_saturated) {
+        let rail = if buckets[run.0].avg > full_scale / 2.0 { format!("skala penuh ({})", full_scale) } else { "0".to_string() };
+        push_run(HealthIssue::Saturated, run, settings.saturation_minutes, format!("Nilai menempel di {}", rail));
+    }
+    for run in runs(buckets, bucket, max_gap, is_dark) {
+        push_run(HealthIssue::ProlongedDark, run, settings.max_dark_hours * 60.0,
+            format!("Gelap lebih dari {} jam; sensor tertutup atau kabel putus?", settings.max_dark_hours));
+    }
+
+    // Flatline: rentang min–maks gabungan tetap dalam toleransi, di luar kondisi gelap atau saturasi.
+    let mut start: Option<(usize, f64, f64)> = None;
+    for i in 0..=buckets.len() {
+        let record = buckets.get(i);
+        let extends = match (start, record) {
+            (Some((_, low, high)), Some(record)) => {
+                let contiguous = record.bucket_start - bucket_end(&buckets[i - 1], bucket) <= max_gap;
+                contiguous && !is_dark(record) && !is_saturated(record)
+                    && high.max(record.max) - low.min(record.min) <= settings.flatline_tolerance
+            },
+            _ => false,
+        };
+        if extends {
+            let (first, low, high) = start.unwrap();
+            let record = record.unwrap();
+            start = Some((first, low.min(record.min), high.max(record.max)));
+            continue;
+        }
+        if let Some((first, low, high)) = start.take() {
+            push_run(HealthIssue::Flatline, (first, i - 1), settings.flatline_minutes,
+                format!("Nilai tetap {:.1}–{:.1} selama {}", low, high, format_minutes(bucket_end(&buckets[i - 1], bucket) - buckets[first].bucket_start)));
+        }
+        if let Some(record) = record {
+            if !is_dark(record) && !is_saturated(record) && record.max - record.min <= settings.flatline_tolerance {
+                start = Some((i, record.min, record.max));
+            }
+        }
+    }
+
+    for pair in buckets.windows(2) {
+        let gap = pair[1].bucket_start - bucket_end(&pair[0], bucket);
+        if gap > max_gap {
+            warnings.push(HealthWarning {
+                issue: HealthIssue::MissingData,
+                start: bucket_end(&pair[0], bucket),
+                end: pair[1].bucket_start,
+                ongoing: false,
+                detail: format!("Tidak ada data selama {}", format_minutes(gap)),
+            });
+        }
+    }
+    let last_sample = buckets.last().map(|record| bucket_end(record, bucket));
+    match last_sample {
+        Some(last) if now - last > max_gap => warnings.push(HealthWarning {
+            issue: HealthIssue::MissingData,
+            start: last,
+            end: now,
+            ongoing: true,
+            detail: format!("Tidak ada data sejak {}", format_minutes(now - last)),
+        }),
+        None => warnings.push(HealthWarning {
+            issue: HealthIssue::MissingData,
+            start: now - Duration::seconds((settings.window_hours * 3600.0) as i64),
+            end: now,
+            ongoing: true,
+            detail: format!("Tidak ada data dalam {} jam terakhir", settings.window_hours),
+        }),
+        _ => {},
+    }
+
+    warnings.sort_by_key(|warning| warning.start);
+    SensorHealth {
+        sensor_id: sensor_id.to_string(),
+        checked_at: now,
+        sample_count: buckets.iter().map(|record| record.count).sum(),
+        last_sample,
+        warnings,
+        drift: None,
+    }
+}
+
+fn hourly_light_fraction(buckets: &[AggregateRecord], adc: &AdcSettings) -> BTreeMap<i64, f64> {
+    let mut sums: BTreeMap<i64, (f64, u64)> = BTreeMap::new();
+    for record in buckets {
+        let entry = sums.entry(record.bucket_start.timestamp().div_euclid(3600)).or_default();
+        entry.0 += adc.light_fraction(record.avg) * record.count as f64;
+        entry.1 += record.count;
+    }
+    sums.into_iter()
+        .filter(|(_, (_, count))| *count > 0)
+        .map(|(hour, (sum, count))| (hour, sum / count as f64))
+        .collect()
+}
+
+// Rasio porsi cahaya per jam terhadap sensor acuan. Sensor yang kotor atau menua menurunkan rasio perlahan,
+// sedangkan perubahan cahaya sungguhan terlihat pada kedua sensor. Hanya jam dengan acuan terang dipakai.
+pub fn estimate_drift(buckets: &[AggregateRecord], adc: &AdcSettings, reference_sensor: &str, reference: &[AggregateRecord], reference_adc: &AdcSettings, settings: &DiagnosticsSettings) -> Option<DriftEstimate> {
+    let sensor_hours = hourly_light_fraction(buckets, adc);
+    let reference_hours = hourly_light_fraction(reference, reference_adc);
+    let hourly_ratio: Vec<(i64, f64)> = sensor_hours.iter()
+        .filter_map(|(hour, fraction)| {
+            let reference_fraction = *reference_hours.get(hour)?;
+            (reference_fraction > settings.dark_fraction).then(|| (*hour, fraction / reference_fraction))
+        })
+        .collect();
+    if hourly_ratio.len() < 3 {
+        return None;
+    }
+
+    let n = hourly_ratio.len() as f64;
+    let mean_x = hourly_ratio.iter().map(|&(hour, _)| hour as f64).sum::<f64>() / n;
+    let mean_y = hourly_ratio.iter().map(|&(_, ratio)| ratio).sum::<f64>() / n;
+    let sxx: f64 = hourly_ratio.iter().map(|&(hour, _)| (hour as f64 - mean_x).powi(2)).sum();
+    let sxy: f64 = hourly_ratio.iter().map(|&(hour, ratio)| (hour as f64 - mean_x) * (ratio - mean_y)).sum();
+    if sxx <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let first_hour = hourly_ratio.first()?.0 as f64;
+    let last_hour = hourly_ratio.last()?.0 as f64;
+    let start_ratio = mean_y + slope * (first_hour - mean_x);
+    let end_ratio = mean_y + slope * (last_hour - mean_x);
+    if start_ratio.abs() < 1e-9 {
+        return None;
+    }
+
+    Some(DriftEstimate {
+        reference_sensor: reference_sensor.to_string(),
+        hourly_ratio: hourly_ratio.into_iter()
+            .filter_map(|(hour, ratio)| Some((DateTime::from_timestamp(hour * 3600, 0)?, ratio)))
+            .collect(),
+        change_percent: 100.0 * (end_ratio - start_ratio) / start_ratio,
+    })
+}
@@ -0,0 +1,237 @@
+use egui::{Color32, Grid, RichText, ScrollArea, Ui};
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+use crate::diagnostics::{DiagnosticsSettings, SensorHealth};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+pub struct DiagnosticsScreen {
+    pub settings: DiagnosticsSettings,
+    // ID sensor yang diperiksa, diisi main.rs dari konfigurasi sensor dan bedengan.
+    pub known_sensors: Vec<String>,
+    pub reports: Arc<Mutex<Vec<SensorHealth>>>,
+    pub run_request: bool,
+    pub last_run: Option<Instant>,
+    pub status: Arc<Mutex<String>>,
+}
+
+impl DiagnosticsScreen {
+    pub fn new() -> Self {
+        Self {
+            settings: DiagnosticsSettings::new(),
+            known_sensors: Vec::new(),
+            reports: Arc::new(Mutex::new(Vec::new())),
+            run_request: false,
+            last_run: None,
+            status: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    // Pemeriksaan otomatis pertama dijalankan segera setelah aplikasi dibuka.
+    pub fn auto_run_due(&self) -> bool {
+        let interval = self.settings.auto_interval_minutes as u64 * 60;
+        interval > 0 && self.last_run.is_none_or(|last| last.elapsed().as_secs() >= interval)
+    }
+
+    fn local_time(timestamp: chrono::DateTime<chrono::Utc>) -> String {
+        timestamp.with_timezone(&chrono::Local).format("%m-%d %H:%M").to_string()
+    }
+
+    fn show_settings(&mut self, ui: &mut Ui) {
+        let settings = &mut self.settings;
+        Grid::new("diagnostics_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 8.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Rentang Analisis:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.window_hours)
+                    .speed(1.0)
+                    .range(1.0..=720.0)
+                    .suffix(" jam terakhir"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Nilai Macet:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    ui_h.add(egui::DragValue::new(&mut settings.flatline_tolerance)
+                        .speed(0.1)
+                        .range(0.0..=100.0)
+                        .prefix("± ")
+                        .suffix(" hitungan"));
+                    ui_h.add(egui::DragValue::new(&mut settings.flatline_minutes)
+                        .speed(1.0)
+                        .range(5.0..=10_000.0)
+                        .prefix("selama ")
+                        .suffix(" menit"));
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Saturasi ADC:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    ui_h.add(egui::DragValue::new(&mut settings.saturation_margin)
+                        .speed(0.5)
+                        .range(0.0..=1_000.0)
+                        .prefix("± ")
+                        .suffix(" hitungan dari 0/skala penuh"));
+                    ui_h.add(egui::DragValue::new(&mut settings.saturation_minutes)
+                        .speed(1.0)
+                        .range(1.0..=10_000.0)
+                        .prefix("selama ")
+                        .suffix(" menit"));
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Gelap Terlalu Lama:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    let mut dark_percent = settings.dark_fraction * 100.0;
+                    if ui_h.add(egui::DragValue::new(&mut dark_percent)
+                        .speed(0.1)
+                        .range(0.0..=50.0)
+                        .prefix("< ")
+                        .suffix(" % skala")).changed() {
+                        settings.dark_fraction = dark_percent / 100.0;
+                    }
+                    ui_h.add(egui::DragValue::new(&mut settings.max_dark_hours)
+                        .speed(0.5)
+                        .range(1.0..=240.0)
+                        .prefix("lebih dari ")
+                        .suffix(" jam"));
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Batas Celah Data:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.max_gap_minutes)
+                    .speed(0.5)
+                    .range(1.0..=1_440.0)
+                    .suffix(" menit"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Sensor Acuan Drift:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    egui::ComboBox::from_id_salt("diagnostics_reference_combo")
+                        .selected_text(settings.reference_sensor.clone().unwrap_or_else(|| "Tidak ada".to_string()))
+                        .show_ui(ui_h, |ui_combo| {
+                            ui_combo.selectable_value(&mut settings.reference_sensor, None, "Tidak ada");
+                            for sensor_id in &self.known_sensors {
+                                ui_combo.selectable_value(&mut settings.reference_sensor, Some(sensor_id.clone()), sensor_id);
+                            }
+                        });
+                    ui_h.add(egui::DragValue::new(&mut settings.drift_threshold_percent)
+                        .speed(0.5)
+                        .range(1.0..=100.0)
+                        .prefix("ambang ")
+                        .suffix(" %"));
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Pemeriksaan Otomatis:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.auto_interval_minutes)
+                    .speed(1.0)
+                    .range(0..=1_440)
+                    .prefix("setiap ")
+                    .suffix(" menit (0 = manual)"));
+                ui_grid.end_row();
+            });
+    }
+
+    fn show_report(ui: &mut Ui, report: &SensorHealth) {
+        let (icon, color, text) = if report.is_healthy() {
+            ("✅", Color32::LIGHT_GREEN, "Sehat")
+        } else {
+            ("⚠", Color32::from_rgb(255, 120, 80), "Perlu Diperiksa")
+        };
+        ui.label(RichText::new(format!("{} Sensor {} · {}", icon, report.sensor_id, text)).color(color).size(18.0).strong());
+        ui.label(format!("{} sampel dalam rentang · data terakhir {} · diperiksa {}",
+            report.sample_count,
+            report.last_sample.map(Self::local_time).unwrap_or_else(|| "-".to_string()),
+            Self::local_time(report.checked_at)));
+        ui.add_space(5.0);
+
+        if !report.warnings.is_empty() {
+            Grid::new(("diagnostics_warnings_grid", &report.sensor_id))
+                .num_columns(5)
+                .striped(true)
+                .spacing([20.0, 6.0])
+                .show(ui, |ui_grid| {
+                    for header in ["Masalah", "Mulai", "Sampai", "Durasi", "Keterangan"] {
+                        ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                    }
+                    ui_grid.end_row();
+
+                    for warning in &report.warnings {
+                        let issue = RichText::new(warning.issue.label());
+                        ui_grid.label(if warning.ongoing { issue.color(Color32::from_rgb(255, 120, 80)).strong() } else { issue });
+                        ui_grid.label(Self::local_time(warning.start));
+                        ui_grid.label(if warning.ongoing { "berlangsung".to_string() } else { Self::local_time(warning.end) });
+                        ui_grid.label(format!("{:.1} jam", warning.duration_hours()));
+                        ui_grid.label(&warning.detail);
+                        ui_grid.end_row();
+                    }
+                });
+        }
+
+        if let Some(drift) = &report.drift {
+            ui.add_space(5.0);
+            ui.label(format!("Rasio terhadap {}: perubahan {:+.1}% dalam rentang analisis.", drift.reference_sensor, drift.change_percent));
+            let points: PlotPoints = drift.hourly_ratio.iter().map(|(timestamp, ratio)| [timestamp.timestamp() as f64, *ratio]).collect();
+            Plot::new(("drift_ratio_plot", &report.sensor_id))
+                .width(ui.available_width())
+                .height(180.0)
+                .show_background(true)
+                .legend(Legend::default())
+                .x_axis_formatter(|mark, _range| {
+                    chrono::DateTime::from_timestamp(mark.value as i64, 0)
+                        .map(Self::local_time)
+                        .unwrap_or_default()
+                })
+                .show(ui, |plot_ui| {
+                    plot_ui.hline(HLine::new("Rasio 1", 1.0).color(Color32::GRAY));
+                    plot_ui.line(Line::new("Rasio per Jam", points).color(Color32::from_rgb(100, 200, 255)).width(2.0));
+                });
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(20.0);
+                    ui.heading(RichText::new("Diagnostik Kesehatan Sensor").color(Color32::WHITE).strong());
+                    ui.add_space(30.0);
+                });
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Pengaturan Pemeriksaan").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_settings(ui);
+                    ui.add_space(10.0);
+                    if ui.button(RichText::new("🩺 Periksa Sekarang").color(Color32::WHITE)).clicked() {
+                        self.run_request = true;
+                    }
+                    let status = self.status.lock().unwrap().clone();
+                    if !status.is_empty() {
+                        ui.label(RichText::new(status).color(Color32::WHITE));
+                    }
+                    ui.label(RichText::new("Analisis memakai agregat photodiode per menit dari database. Malam hari tidak dianggap masalah selama gelap tidak melebihi batas jam.")
+                        .color(Color32::GRAY)
+                        .italics());
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Hasil per Sensor").color(Color32::YELLOW).strong());
+                    ui.add_space(10.0);
+                    let reports = self.reports.lock().unwrap();
+                    if reports.is_empty() {
+                        ui.label(RichText::new("Belum ada hasil pemeriksaan.").color(Color32::GRAY).italics());
+                    }
+                    for report in reports.iter() {
+                        Self::show_report(ui, report);
+                        ui.add_space(15.0);
+                    }
+                });
+            });
+    }
+}
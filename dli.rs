@@ -0,0 +1,432 @@
+// src/dli.rs
+// Daily Light Integral (mol/m²/hari): integral PPFD (µmol/m²/s) terhadap waktu selama satu hari lokal.
+// Timestamp tidak beraturan; selang lebih panjang dari batas celah tidak diintegrasikan dan dilaporkan
+// lewat cakupan jam, sehingga data yang hilang tidak diisi diam-diam.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::recompute;
+use crate::storage::{self, StoredRecord};
+
+const MICROMOL_PER_MOL: f64 = 1e6;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IntegrationMethod {
+    Trapezoidal,
+    Simpson,
+}
+
+impl IntegrationMethod {
+    pub const ALL: [IntegrationMethod; 2] = [IntegrationMethod::Trapezoidal, IntegrationMethod::Simpson];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntegrationMethod::Trapezoidal => "Trapesium",
+            IntegrationMethod::Simpson => "Simpson (Selang Tak Seragam)",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DliSettings {
+    pub method: IntegrationMethod,
+    // Selang antar sampel yang lebih panjang dianggap celah data.
+    pub max_gap_seconds: f64,
+    // Zona waktu yang menentukan batas hari (default WIB).
+    pub utc_offset: FixedOffset,
+    pub target: f64,
+    // Sampel yang ditandai detektor outlier ikut diintegrasikan; jika tidak, selangnya dijembatani sampel tetangga.
+    pub include_outliers: bool,
+}
+
+impl DliSettings {
+    pub fn new() -> Self {
+        Self {
+            method: IntegrationMethod::Simpson,
+            max_gap_seconds: 900.0,
+            utc_offset: FixedOffset::east_opt(7 * 3600).unwrap(),
+            target: 17.0,
+            include_outliers: false,
+        }
+    }
+
+    pub fn local_date(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        timestamp.with_timezone(&self.utc_offset).date_naive()
+    }
+
+    // Awal hari lokal `date` dalam UTC.
+    fn day_start(&self, date: NaiveDate) -> DateTime<Utc> {
+        let local_midnight = date.and_hms_opt(0, 0, 0).unwrap();
+        DateTime::<Utc>::from_naive_utc_and_offset(local_midnight, Utc) - Duration::seconds(self.utc_offset.local_minus_utc() as i64)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Integral {
+    // µmol/m².
+    pub micromoles: f64,
+    pub covered_seconds: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DailyLightIntegral {
+    pub sensor_id: String,
+    // Tanggal lokal sesuai `DliSettings::utc_offset`.
+    pub date: NaiveDate,
+    pub dli: f64,
+    pub covered_hours: f64,
+    pub sample_count: usize,
+    pub method: IntegrationMethod,
+    // `false` selama hari tersebut masih berjalan.
+    pub complete: bool,
+}
+
+impl DailyLightIntegral {
+    pub fn coverage(&self) -> f64 {
+        (self.covered_hours / 24.0).clamp(0.0, 1.0)
+    }
+}
+
+// Sampel `(detik, PPFD)` harus terurut naik. Sampel dengan waktu sama dengan sebelumnya diabaikan.
+pub fn integrate(samples: &[(f64, f64)], method: IntegrationMethod, max_gap_seconds: f64) -> Integral {
+    let mut running = RunningIntegral::new(method, max_gap_seconds);
+    for &(t, value) in samples {
+        running.push(t, value);
+    }
+    running.integral()
+}
+
+fn trapezoid(points: &[(f64, f64)]) -> f64 {
+    points.windows(2)
+        .map(|pair| 0.5 * (pair[1].0 - pair[0].0) * (pair[0].1 + pair[1].1))
+        .sum()
+}
+
+// Di luar rasio selang [0.5, 2] bobot Simpson tak seragam untuk f0 atau f2 menjadi negatif (mis. satu
+// sampel hilang tepat saat lampu padam, atau timestamp hampir kembar setelah serial tersambung ulang),
+// jadi selang pertama pasangan itu memakai trapesium.
+const SIMPSON_MAX_STEP_RATIO: f64 = 2.0;
+
+// Simpson untuk satu pasangan selang (h0, h1) tak seragam; `None` bila rasio selang di luar batas.
+fn simpson_pair(points: &[(f64, f64)]) -> Option<f64> {
+    let [(t0, f0), (t1, f1), (t2, f2)] = points else { return None };
+    let (h0, h1) = (t1 - t0, t2 - t1);
+    let ratio = h1 / h0;
+    if !(1.0 / SIMPSON_MAX_STEP_RATIO..=SIMPSON_MAX_STEP_RATIO).contains(&ratio) {
+        return None;
+    }
+    Some((h0 + h1) / 6.0 * ((2.0 - ratio) * f0 + (h0 + h1).powi(2) / (h0 * h1) * f1 + (2.0 - h0 / h1) * f2))
+}
+
+// Integral yang diperbarui per sampel dalam O(1). Simpson komposit dijalankan per pasangan selang;
+// selang yang belum berpasangan (jumlah selang ganjil) dihitung dengan trapesium sampai pasangannya tiba.
+#[derive(Clone, Debug)]
+pub struct RunningIntegral {
+    method: IntegrationMethod,
+    max_gap_seconds: f64,
+    // Integral selang yang sudah final.
+    committed: Integral,
+    // Sampel sejak selang final terakhir dalam segmen berjalan (paling banyak 2 sesudah `push`).
+    pending: Vec<(f64, f64)>,
+}
+
+impl RunningIntegral {
+    pub fn new(method: IntegrationMethod, max_gap_seconds: f64) -> Self {
+        Self {
+            method,
+            max_gap_seconds,
+            committed: Integral { micromoles: 0.0, covered_seconds: 0.0 },
+            pending: Vec::with_capacity(3),
+        }
+    }
+
+    pub fn last(&self) -> Option<(f64, f64)> {
+        self.pending.last().copied()
+    }
+
+    pub fn push(&mut self, t: f64, value: f64) {
+        if !t.is_finite() || !value.is_finite() {
+            return;
+        }
+        match self.last() {
+            Some((last_t, _)) if t <= last_t => return,
+            Some((last_t, _)) if t - last_t > self.max_gap_seconds => {
+                self.committed.micromoles += trapezoid(&self.pending);
+                self.pending.clear();
+            },
+            Some((last_t, _)) => self.committed.covered_seconds += t - last_t,
+            None => {},
+        }
+        self.pending.push((t, value));
+
+        let pair = match self.method {
+            IntegrationMethod::Trapezoidal if self.pending.len() == 2 => Some(trapezoid(&self.pending)),
+            IntegrationMethod::Simpson if self.pending.len() == 3 => simpson_pair(&self.pending),
+            _ => return,
+        };
+        match pair {
+            Some(micromoles) => {
+                self.committed.micromoles += micromoles;
+                self.pending.drain(..self.pending.len() - 1);
+            },
+            None => {
+                self.committed.micromoles += trapezoid(&self.pending[..2]);
+                self.pending.remove(0);
+            },
+        }
+    }
+
+    pub fn integral(&self) -> Integral {
+        Integral {
+            micromoles: self.committed.micromoles + trapezoid(&self.pending),
+            covered_seconds: self.committed.covered_seconds,
+        }
+    }
+}
+
+fn unix_seconds(timestamp: DateTime<Utc>) -> f64 {
+    timestamp.timestamp_millis() as f64 / 1000.0
+}
+
+fn daily_light_integral(sensor_id: &str, date: NaiveDate, samples: &[(f64, f64)], settings: &DliSettings, complete: bool) -> DailyLightIntegral {
+    let integral = integrate(samples, settings.method, settings.max_gap_seconds);
+    from_integral(sensor_id, date, integral, samples.len(), settings.method, complete)
+}
+
+fn from_integral(sensor_id: &str, date: NaiveDate, integral: Integral, sample_count: usize, method: IntegrationMethod, complete: bool) -> DailyLightIntegral {
+    DailyLightIntegral {
+        sensor_id: sensor_id.to_string(),
+        date,
+        dli: integral.micromoles / MICROMOL_PER_MOL,
+        covered_hours: integral.covered_seconds / SECONDS_PER_HOUR,
+        sample_count,
+        method,
+        complete,
+    }
+}
+
+// Titik interpolasi linier tepat di tengah malam agar selang yang melintasi pergantian hari terbagi ke dua hari.
+fn midnight_point(settings: &DliSettings, previous: (f64, f64), next_date: NaiveDate, next: (f64, f64)) -> Option<(f64, f64)> {
+    if next.0 - previous.0 > settings.max_gap_seconds {
+        return None;
+    }
+    let boundary = unix_seconds(settings.day_start(next_date));
+    let fraction = (boundary - previous.0) / (next.0 - previous.0);
+    Some((boundary, previous.1 + fraction * (next.1 - previous.1)))
+}
+
+// DLI per hari lokal dari sampel PPFD tersimpan. Hari sebelum hari ini (menurut `now`) ditandai selesai.
+pub fn daily_totals(sensor_id: &str, samples: &[(DateTime<Utc>, f64)], settings: &DliSettings, now: DateTime<Utc>) -> Vec<DailyLightIntegral> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut days: BTreeMap<NaiveDate, Vec<(f64, f64)>> = BTreeMap::new();
+    let mut previous: Option<(NaiveDate, (f64, f64))> = None;
+    for (timestamp, ppfd) in sorted {
+        let date = settings.local_date(timestamp);
+        let point = (unix_seconds(timestamp), ppfd);
+        if let Some((previous_date, previous_point)) = previous {
+            if previous_date != date {
+                if let Some(boundary) = midnight_point(settings, previous_point, date, point) {
+                    days.entry(previous_date).or_default().push(boundary);
+                    days.entry(date).or_default().push(boundary);
+                }
+            }
+        }
+        days.entry(date).or_default().push(point);
+        previous = Some((date, point));
+    }
+
+    let today = settings.local_date(now);
+    days.iter()
+        .map(|(date, points)| daily_light_integral(sensor_id, *date, points, settings, *date < today))
+        .collect()
+}
+
+// Sampel PPFD dari hasil lux tersimpan (live dan impor; set hitung ulang dilewati agar tidak dobel).
+// Data lama tanpa kolom PPFD dikonversi dengan `fallback_factor`. Ringkasan per jam dari retensi menjadi
+// deret konstan bernilai rata-ratanya selama jam itu, dengan selang di bawah batas celah terkecil (1 menit).
+pub fn ppfd_samples(records: &[StoredRecord], fallback_factor: f64, include_outliers: bool) -> Vec<(DateTime<Utc>, f64)> {
+    const SUMMARY_STEP_MS: i64 = 60_000;
+    let edge = Duration::milliseconds(1);
+    records.iter()
+        .filter(|record| !record.source.as_deref().is_some_and(recompute::is_result_set))
+        .filter(|record| include_outliers || record.outlier.is_none())
+        .flat_map(|record| {
+            let ppfd = record.ppfd.or_else(|| record.value.map(|lux| lux * fallback_factor));
+            match (record.timestamp, ppfd, record.summarized_count) {
+                (Some(start), Some(ppfd), Some(_)) => (0..=storage::SUMMARY_BUCKET_MS / SUMMARY_STEP_MS)
+                    .map(|step| Duration::milliseconds(step * SUMMARY_STEP_MS))
+                    .map(|offset| (start + offset.clamp(edge, Duration::milliseconds(storage::SUMMARY_BUCKET_MS) - edge), ppfd))
+                    .collect(),
+                (Some(timestamp), Some(ppfd), None) => vec![(timestamp, ppfd)],
+                _ => Vec::new(),
+            }
+        })
+        .collect()
+}
+
+struct DayAccumulator {
+    date: NaiveDate,
+    integral: RunningIntegral,
+    sample_count: usize,
+}
+
+impl DayAccumulator {
+    fn new(date: NaiveDate, settings: &DliSettings) -> Self {
+        Self { date, integral: RunningIntegral::new(settings.method, settings.max_gap_seconds), sample_count: 0 }
+    }
+
+    fn push(&mut self, point: (f64, f64)) {
+        self.integral.push(point.0, point.1);
+        self.sample_count += 1;
+    }
+
+    fn total(&self, sensor_id: &str, complete: bool) -> DailyLightIntegral {
+        from_integral(sensor_id, self.date, self.integral.integral(), self.sample_count, self.integral.method, complete)
+    }
+}
+
+// Akumulasi DLI berjalan dari data live per sensor; hanya integral berjalan yang disimpan, bukan sampelnya.
+pub struct DliTracker {
+    days: HashMap<String, DayAccumulator>,
+}
+
+impl DliTracker {
+    pub fn new() -> Self {
+        Self { days: HashMap::new() }
+    }
+
+    // Mengembalikan DLI hari sebelumnya saat sampel pertama hari baru masuk.
+    pub fn add(&mut self, sensor_id: &str, timestamp: DateTime<Utc>, ppfd: f64, settings: &DliSettings) -> Option<DailyLightIntegral> {
+        let date = settings.local_date(timestamp);
+        let point = (unix_seconds(timestamp), ppfd);
+        let day = self.days.entry(sensor_id.to_string()).or_insert_with(|| DayAccumulator::new(date, settings));
+
+        let mut completed = None;
+        if day.date != date {
+            let boundary = day.integral.last().and_then(|last| midnight_point(settings, last, date, point));
+            if let Some(boundary) = boundary {
+                day.push(boundary);
+            }
+            if day.date < date {
+                completed = Some(day.total(sensor_id, true));
+            }
+            *day = DayAccumulator::new(date, settings);
+            if let Some(boundary) = boundary {
+                day.push(boundary);
+            }
+        }
+        day.push(point);
+        completed
+    }
+
+    pub fn running(&self, sensor_id: &str) -> Option<DailyLightIntegral> {
+        Some(self.days.get(sensor_id)?.total(sensor_id, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METHODS: [IntegrationMethod; 2] = [IntegrationMethod::Trapezoidal, IntegrationMethod::Simpson];
+    // Selang tak beraturan termasuk timestamp hampir kembar dan selang panjang.
+    const IRREGULAR_TIMES: [f64; 10] = [0.0, 1.0, 1.001, 4.0, 10.0, 10.5, 70.0, 71.0, 300.0, 301.0];
+
+    #[test]
+    fn constant_ppfd_is_exact_on_irregular_grid() {
+        let samples: Vec<(f64, f64)> = IRREGULAR_TIMES.iter().map(|&t| (t, 250.0)).collect();
+        for method in METHODS {
+            let integral = integrate(&samples, method, 900.0);
+            assert!((integral.micromoles - 250.0 * 301.0).abs() < 1e-6, "{:?}: {}", method, integral.micromoles);
+            assert_eq!(integral.covered_seconds, 301.0);
+        }
+    }
+
+    #[test]
+    fn linear_ppfd_is_exact_on_irregular_grid() {
+        let ppfd = |t: f64| 100.0 + 2.0 * t;
+        let samples: Vec<(f64, f64)> = IRREGULAR_TIMES.iter().map(|&t| (t, ppfd(t))).collect();
+        let exact = 100.0 * 301.0 + 301.0 * 301.0;
+        for method in METHODS {
+            let integral = integrate(&samples, method, 900.0);
+            assert!((integral.micromoles - exact).abs() < 1e-6, "{:?}: {} vs {}", method, integral.micromoles, exact);
+        }
+    }
+
+    #[test]
+    fn simpson_stays_bounded_with_near_duplicate_timestamps() {
+        // Lompatan PPFD di antara dua timestamp hampir kembar; integral harus tetap di antara 0 dan maks · durasi.
+        let samples = [(0.0, 0.0), (0.001, 500.0), (600.0, 500.0), (600.002, 0.0), (1200.0, 0.0)];
+        let integral = integrate(&samples, IntegrationMethod::Simpson, 900.0);
+        assert!(integral.micromoles >= 0.0 && integral.micromoles <= 500.0 * 1200.0, "{}", integral.micromoles);
+        assert!((integral.micromoles - 500.0 * 600.0).abs() < 1.0, "{}", integral.micromoles);
+    }
+
+    #[test]
+    fn simpson_stays_positive_when_lights_go_off_with_dropped_sample() {
+        // Rasio selang 4: bobot Simpson untuk f0 akan negatif (-100000), trapesium memberi +30000.
+        let samples = [(0.0, 1000.0), (60.0, 0.0), (300.0, 0.0)];
+        let integral = integrate(&samples, IntegrationMethod::Simpson, 900.0);
+        assert!((integral.micromoles - 30_000.0).abs() < 1e-6, "{}", integral.micromoles);
+    }
+
+    #[test]
+    fn tracker_matches_batch_integration() {
+        let settings = DliSettings::new();
+        let start = Utc::now() - Duration::days(1);
+        let start = settings.day_start(settings.local_date(start));
+        let samples: Vec<(DateTime<Utc>, f64)> = IRREGULAR_TIMES.iter().chain(&[1500.0, 1560.0, 1600.0, 1900.0])
+            .map(|&t| (start + Duration::milliseconds((t * 1000.0) as i64), 300.0 + 100.0 * (t / 200.0).sin()))
+            .collect();
+        for method in METHODS {
+            let settings = DliSettings { method, ..settings.clone() };
+            let mut tracker = DliTracker::new();
+            for &(timestamp, ppfd) in &samples {
+                tracker.add("A0", timestamp, ppfd, &settings);
+            }
+            let running = tracker.running("A0").unwrap();
+            let batch = daily_totals("A0", &samples, &settings, start)[0].clone();
+            assert!((running.dli - batch.dli).abs() < 1e-12, "{:?}: {} vs {}", method, running.dli, batch.dli);
+            assert_eq!(running.covered_hours, batch.covered_hours);
+            assert_eq!(running.sample_count, batch.sample_count);
+        }
+    }
+
+    #[test]
+    fn hourly_summaries_cover_their_whole_hour() {
+        let settings = DliSettings::new();
+        let start = settings.day_start(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()) + Duration::hours(8);
+        let summary = |hour: i64| StoredRecord {
+            timestamp: Some(start + Duration::hours(hour)),
+            sensor_id: Some("A0".to_string()),
+            source: None,
+            value: Some(10_000.0),
+            iterations_history: Vec::new(),
+            calibration_profile: None,
+            confidence_interval: None,
+            ppfd: None,
+            outlier: None,
+            summarized_count: Some(3600),
+        };
+        let samples = ppfd_samples(&[summary(0), summary(1), summary(2)], 0.02, false);
+        let totals = daily_totals("A0", &samples, &settings, start + Duration::days(2));
+        // 200 µmol/m²/s selama 3 jam; transisi 2 ms antar jam diabaikan.
+        assert!((totals[0].dli - 200.0 * 3.0 * 3600.0 / 1e6).abs() < 1e-3, "{}", totals[0].dli);
+        assert!((totals[0].covered_hours - 3.0).abs() < 1e-6, "{}", totals[0].covered_hours);
+    }
+
+    #[test]
+    fn gaps_longer_than_limit_are_not_integrated() {
+        let samples = [(0.0, 100.0), (60.0, 100.0), (2000.0, 100.0), (2060.0, 100.0)];
+        for method in METHODS {
+            let integral = integrate(&samples, method, 900.0);
+            assert!((integral.micromoles - 100.0 * 120.0).abs() < 1e-9, "{:?}", method);
+            assert_eq!(integral.covered_seconds, 120.0);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+// src/export.rs
+// Ekspor data tersimpan ke CSV atau JSON Lines untuk dianalisis di spreadsheet,
+// atau ke Parquet per hari (lihat parquet_export.rs).
+
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::adc::AdcSettings;
+use crate::parquet_export;
+use crate::storage::{SeriesKind, Storage, StorageResult, StoredRecord};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "jsonl" | "json" | "jsonlines" => Some(ExportFormat::JsonLines),
+            "parquet" => Some(ExportFormat::Parquet),
+            _ => None,
+        }
+    }
+
+    // Parquet ditulis ke sebuah folder, bukan satu file.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            ExportFormat::Csv => Some("csv"),
+            ExportFormat::JsonLines => Some("jsonl"),
+            ExportFormat::Parquet => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportColumn {
+    Timestamp,
+    SensorId,
+    Source,
+    Series,
+    Value,
+    Iterations,
+    CalibrationProfile,
+    LuxLower,
+    LuxUpper,
+    Ppfd,
+    Outlier,
+}
+
+impl ExportColumn {
+    pub const ALL: [ExportColumn; 11] = [
+        ExportColumn::Timestamp,
+        ExportColumn::SensorId,
+        ExportColumn::Source,
+        ExportColumn::Series,
+        ExportColumn::Value,
+        ExportColumn::Iterations,
+        ExportColumn::CalibrationProfile,
+        ExportColumn::LuxLower,
+        ExportColumn::LuxUpper,
+        ExportColumn::Ppfd,
+        ExportColumn::Outlier,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            ExportColumn::Timestamp => "timestamp",
+            ExportColumn::SensorId => "sensor_id",
+            ExportColumn::Source => "source",
+            ExportColumn::Series => "series",
+            ExportColumn::Value => "value",
+            ExportColumn::Iterations => "iterations",
+            ExportColumn::CalibrationProfile => "calibration_profile",
+            ExportColumn::LuxLower => "lux_lower_95",
+            ExportColumn::LuxUpper => "lux_upper_95",
+            ExportColumn::Ppfd => "ppfd",
+            ExportColumn::Outlier => "outlier",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportColumn::Timestamp => "Waktu",
+            ExportColumn::SensorId => "ID Sensor",
+            ExportColumn::Source => "Sumber",
+            ExportColumn::Series => "Jenis Data",
+            ExportColumn::Value => "Nilai",
+            ExportColumn::Iterations => "Jumlah Iterasi NR",
+            ExportColumn::CalibrationProfile => "Profil Kalibrasi",
+            ExportColumn::LuxLower => "Batas Bawah Lux 95%",
+            ExportColumn::LuxUpper => "Batas Atas Lux 95%",
+            ExportColumn::Ppfd => "PPFD (µmol/m²/s)",
+            ExportColumn::Outlier => "Tanda Outlier",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|column| column.key() == key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecimalSeparator {
+    Dot,
+    Comma,
+}
+
+pub fn series_key(kind: SeriesKind) -> &'static str {
+    match kind {
+        SeriesKind::Photodiode => "photodiode",
+        SeriesKind::NewtonRaphson => "lux",
+    }
+}
+
+pub fn series_from_key(key: &str) -> Option<SeriesKind> {
+    match key {
+        "photodiode" | "readings" => Some(SeriesKind::Photodiode),
+        "lux" | "newton_raphson" | "results" => Some(SeriesKind::NewtonRaphson),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub series: Vec<SeriesKind>,
+    pub sensor_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub columns: Vec<ExportColumn>,
+    pub utc_offset: FixedOffset,
+    // Hanya berlaku untuk CSV; angka di JSON Lines selalu memakai titik.
+    pub decimal_separator: DecimalSeparator,
+    // Untuk kolom tegangan Parquet; sensor tanpa entri memakai `AdcSettings::new()`.
+    pub adc_by_sensor: BTreeMap<String, AdcSettings>,
+}
+
+impl ExportOptions {
+    pub fn new() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            series: vec![SeriesKind::Photodiode, SeriesKind::NewtonRaphson],
+            sensor_id: None,
+            from: None,
+            to: None,
+            columns: ExportColumn::ALL.to_vec(),
+            utc_offset: FixedOffset::east_opt(7 * 3600).unwrap(), // WIB
+            decimal_separator: DecimalSeparator::Dot,
+            adc_by_sensor: BTreeMap::new(),
+        }
+    }
+}
+
+// "+07:00", "+7", "-03:30" atau "UTC".
+pub fn parse_utc_offset(text: &str) -> Result<FixedOffset, String> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("utc") || text.eq_ignore_ascii_case("z") {
+        return Ok(FixedOffset::east_opt(0).unwrap());
+    }
+    let (sign, rest) = match text.chars().next() {
+        Some('+') => (1, &text[1..]),
+        Some('-') => (-1, &text[1..]),
+        _ => (1, text),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().map_err(|_| format!("Zona waktu tidak valid: '{}'", text))?;
+    let minutes: i32 = minutes.parse().map_err(|_| format!("Zona waktu tidak valid: '{}'", text))?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .ok_or_else(|| format!("Zona waktu di luar jangkauan: '{}'", text))
+}
+
+// Tanggal "YYYY-MM-DD" dibaca pada zona waktu ekspor. `end_of_day` memajukan batas ke awal hari berikutnya
+// agar tanggal akhir ikut tercakup.
+pub fn parse_date(text: &str, offset: &FixedOffset, end_of_day: bool) -> Result<Option<DateTime<Utc>>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let mut date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map_err(|_| format!("Format tanggal harus YYYY-MM-DD: '{}'", text))?;
+    if end_of_day {
+        date = date.succ_opt().ok_or_else(|| format!("Tanggal di luar jangkauan: '{}'", text))?;
+    }
+    let local = offset.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .single()
+        .ok_or_else(|| format!("Tanggal tidak valid: '{}'", text))?;
+    Ok(Some(local.with_timezone(&Utc)))
+}
+
+fn format_number(value: f64, separator: DecimalSeparator) -> String {
+    let text = value.to_string();
+    match separator {
+        DecimalSeparator::Dot => text,
+        DecimalSeparator::Comma => text.replace('.', ","),
+    }
+}
+
+fn iteration_count(record: &StoredRecord) -> Option<usize> {
+    // Riwayat iterasi diawali tebakan awal x0.
+    if record.iterations_history.is_empty() {
+        None
+    } else {
+        Some(record.iterations_history.len() - 1)
+    }
+}
+
+pub fn source_label(record: &StoredRecord) -> &str {
+    record.source.as_deref().unwrap_or("live")
+}
+
+fn format_timestamp(timestamp: &DateTime<Utc>, offset: &FixedOffset) -> String {
+    timestamp.with_timezone(offset).format("%Y-%m-%d %H:%M:%S%:z").to_string()
+}
+
+pub fn collect_rows(storage: &dyn Storage, options: &ExportOptions) -> StorageResult<Vec<(SeriesKind, StoredRecord)>> {
+    let mut rows = Vec::new();
+    for kind in &options.series {
+        let records = storage.query_range(*kind, options.sensor_id.as_deref(), options.from, options.to)?;
+        rows.extend(records.into_iter().map(|record| (*kind, record)));
+    }
+    rows.sort_by_key(|(_, record)| record.timestamp);
+    Ok(rows)
+}
+
+pub fn write_rows<W: Write>(writer: W, rows: &[(SeriesKind, StoredRecord)], options: &ExportOptions) -> StorageResult<usize> {
+    match options.format {
+        ExportFormat::Csv => write_csv(writer, rows, options),
+        ExportFormat::JsonLines => write_json_lines(writer, rows, options),
+        ExportFormat::Parquet => Err("Parquet ditulis per hari ke folder, gunakan export_to_path".into()),
+    }
+}
+
+fn write_csv<W: Write>(writer: W, rows: &[(SeriesKind, StoredRecord)], options: &ExportOptions) -> StorageResult<usize> {
+    // Dengan desimal koma, pemisah kolom ikut diganti titik koma seperti pengaturan spreadsheet lokal.
+    let delimiter = match options.decimal_separator {
+        DecimalSeparator::Dot => b',',
+        DecimalSeparator::Comma => b';',
+    };
+    let mut csv_writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(writer);
+    csv_writer.write_record(options.columns.iter().map(|column| column.key()))?;
+
+    for (kind, record) in rows {
+        let fields: Vec<String> = options.columns.iter().map(|column| match column {
+            ExportColumn::Timestamp => record.timestamp
+                .map(|ts| format_timestamp(&ts, &options.utc_offset))
+                .unwrap_or_default(),
+            ExportColumn::SensorId => record.sensor_id.clone().unwrap_or_default(),
+            ExportColumn::Source => source_label(record).to_string(),
+            ExportColumn::Series => series_key(*kind).to_string(),
+            ExportColumn::Value => record.value
+                .map(|v| format_number(v, options.decimal_separator))
+                .unwrap_or_default(),
+            ExportColumn::Iterations => iteration_count(record)
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            ExportColumn::CalibrationProfile => record.calibration_profile.clone().unwrap_or_default(),
+            ExportColumn::LuxLower => record.confidence_interval
+                .map(|ci| format_number(ci.lower, options.decimal_separator))
+                .unwrap_or_default(),
+            ExportColumn::LuxUpper => record.confidence_interval
+                .map(|ci| format_number(ci.upper, options.decimal_separator))
+                .unwrap_or_default(),
+            ExportColumn::Ppfd => record.ppfd
+                .map(|v| format_number(v, options.decimal_separator))
+                .unwrap_or_default(),
+            ExportColumn::Outlier => record.outlier.map(|flag| flag.key().to_string()).unwrap_or_default(),
+        }).collect();
+        csv_writer.write_record(&fields)?;
+    }
+    csv_writer.flush()?;
+    Ok(rows.len())
+}
+
+fn write_json_lines<W: Write>(mut writer: W, rows: &[(SeriesKind, StoredRecord)], options: &ExportOptions) -> StorageResult<usize> {
+    use serde_json::{Map, Value as JsonValue};
+
+    for (kind, record) in rows {
+        let mut object = Map::new();
+        for column in &options.columns {
+            let value = match column {
+                ExportColumn::Timestamp => record.timestamp
+                    .map(|ts| JsonValue::from(format_timestamp(&ts, &options.utc_offset)))
+                    .unwrap_or(JsonValue::Null),
+                ExportColumn::SensorId => record.sensor_id.clone().map(JsonValue::from).unwrap_or(JsonValue::Null),
+                ExportColumn::Source => JsonValue::from(source_label(record)),
+                ExportColumn::Series => JsonValue::from(series_key(*kind)),
+                ExportColumn::Value => record.value.map(JsonValue::from).unwrap_or(JsonValue::Null),
+                ExportColumn::Iterations => iteration_count(record).map(JsonValue::from).unwrap_or(JsonValue::Null),
+                ExportColumn::CalibrationProfile => record.calibration_profile.clone().map(JsonValue::from).unwrap_or(JsonValue::Null),
+                ExportColumn::LuxLower => record.confidence_interval.map(|ci| JsonValue::from(ci.lower)).unwrap_or(JsonValue::Null),
+                ExportColumn::LuxUpper => record.confidence_interval.map(|ci| JsonValue::from(ci.upper)).unwrap_or(JsonValue::Null),
+                ExportColumn::Ppfd => record.ppfd.map(JsonValue::from).unwrap_or(JsonValue::Null),
+                ExportColumn::Outlier => record.outlier.map(|flag| JsonValue::from(flag.key())).unwrap_or(JsonValue::Null),
+            };
+            object.insert(column.key().to_string(), value);
+        }
+        serde_json::to_writer(&mut writer, &object)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(rows.len())
+}
+
+pub fn export_to_path(storage: &dyn Storage, path: &Path, options: &ExportOptions) -> StorageResult<usize> {
+    if options.format == ExportFormat::Parquet {
+        return parquet_export::export_partitioned(storage, path, options);
+    }
+    let rows = collect_rows(storage, options)?;
+    let file = BufWriter::new(File::create(path)?);
+    write_rows(file, &rows, options)
+}
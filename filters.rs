@@ -0,0 +1,154 @@
+// src/filters.rs
+// Rantai filter untuk nilai mentah photodiode sebelum konversi ke lux. Setiap tahap menyimpan
+// keadaannya sendiri, jadi satu rantai dipakai untuk satu sensor saja.
+
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterKind {
+    MovingAverage,
+    Exponential,
+    Median,
+    Kalman,
+}
+
+impl FilterKind {
+    pub const ALL: [FilterKind; 4] = [
+        FilterKind::MovingAverage,
+        FilterKind::Exponential,
+        FilterKind::Median,
+        FilterKind::Kalman,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FilterKind::MovingAverage => "Rata-rata Bergerak",
+            FilterKind::Exponential => "Pemulusan Eksponensial",
+            FilterKind::Median => "Median",
+            FilterKind::Kalman => "Kalman 1D",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterStage {
+    pub kind: FilterKind,
+    // Jumlah sampel untuk rata-rata bergerak dan median.
+    pub window: usize,
+    // Bobot sampel baru pada pemulusan eksponensial (0-1].
+    pub alpha: f64,
+    // Varians proses (Q) dan pengukuran (R) Kalman, dalam hitungan ADC².
+    pub process_noise: f64,
+    pub measurement_noise: f64,
+
+    history: VecDeque<f64>,
+    estimate: Option<f64>,
+    error_variance: f64,
+}
+
+impl FilterStage {
+    pub const MAX_WINDOW: usize = 101;
+
+    pub fn new(kind: FilterKind) -> Self {
+        Self {
+            kind,
+            window: 5,
+            alpha: 0.3,
+            process_noise: 1.0,
+            measurement_noise: 25.0,
+            history: VecDeque::new(),
+            estimate: None,
+            error_variance: 0.0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.estimate = None;
+        self.error_variance = 0.0;
+    }
+
+    pub fn apply(&mut self, value: f64) -> f64 {
+        match self.kind {
+            FilterKind::MovingAverage | FilterKind::Median => {
+                let window = self.window.clamp(1, Self::MAX_WINDOW);
+                self.history.push_back(value);
+                while self.history.len() > window {
+                    self.history.pop_front();
+                }
+                if self.kind == FilterKind::MovingAverage {
+                    self.history.iter().sum::<f64>() / self.history.len() as f64
+                } else {
+                    let mut sorted: Vec<f64> = self.history.iter().copied().collect();
+                    sorted.sort_by(|a, b| a.total_cmp(b));
+                    let middle = sorted.len() / 2;
+                    if 2 * middle == sorted.len() { 0.5 * (sorted[middle - 1] + sorted[middle]) } else { sorted[middle] }
+                }
+            },
+            FilterKind::Exponential => {
+                let alpha = self.alpha.clamp(0.001, 1.0);
+                let estimate = self.estimate.map_or(value, |previous| previous + alpha * (value - previous));
+                self.estimate = Some(estimate);
+                estimate
+            },
+            // Model acak-jalan: prediksi = estimasi sebelumnya, varians bertambah Q setiap sampel.
+            FilterKind::Kalman => {
+                let Some(previous) = self.estimate else {
+                    self.estimate = Some(value);
+                    self.error_variance = self.measurement_noise;
+                    return value;
+                };
+                let predicted_variance = self.error_variance + self.process_noise.max(0.0);
+                let gain = predicted_variance / (predicted_variance + self.measurement_noise.max(1e-12));
+                let estimate = previous + gain * (value - previous);
+                self.estimate = Some(estimate);
+                self.error_variance = (1.0 - gain) * predicted_variance;
+                estimate
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            FilterKind::MovingAverage | FilterKind::Median => format!("{} ({} sampel)", self.kind.label(), self.window),
+            FilterKind::Exponential => format!("{} (α = {})", self.kind.label(), self.alpha),
+            FilterKind::Kalman => format!("{} (Q = {}, R = {})", self.kind.label(), self.process_noise, self.measurement_noise),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterChain {
+    pub enabled: bool,
+    // Diterapkan berurutan; keluaran satu tahap menjadi masukan tahap berikutnya.
+    pub stages: Vec<FilterStage>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            stages: vec![FilterStage::new(FilterKind::Median)],
+        }
+    }
+
+    pub fn apply(&mut self, raw_value: f64) -> f64 {
+        if !self.enabled || !raw_value.is_finite() {
+            return raw_value;
+        }
+        self.stages.iter_mut().fold(raw_value, |value, stage| stage.apply(value))
+    }
+
+    pub fn reset(&mut self) {
+        for stage in &mut self.stages {
+            stage.reset();
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        if !self.enabled || self.stages.is_empty() {
+            return "Tanpa filter".to_string();
+        }
+        self.stages.iter().map(|stage| stage.describe()).collect::<Vec<_>>().join(" → ")
+    }
+}
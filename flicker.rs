@@ -0,0 +1,324 @@
+// src/flicker.rs
+// Analisis flicker lampu dari deret cahaya berkecepatan tinggi: spektrum FFT (radix-2), frekuensi dominan,
+// persen flicker dan indeks flicker (IES), serta klasifikasi risiko IEEE 1789.
+
+use std::f64::consts::PI;
+
+use crate::measurements::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowFunction {
+    Rectangular,
+    Hann,
+    BlackmanHarris,
+}
+
+impl WindowFunction {
+    pub const ALL: [WindowFunction; 3] = [WindowFunction::Rectangular, WindowFunction::Hann, WindowFunction::BlackmanHarris];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowFunction::Rectangular => "Persegi (Tanpa Window)",
+            WindowFunction::Hann => "Hann",
+            WindowFunction::BlackmanHarris => "Blackman-Harris",
+        }
+    }
+
+    fn coefficient(&self, i: usize, n: usize) -> f64 {
+        let phase = 2.0 * PI * i as f64 / (n - 1).max(1) as f64;
+        match self {
+            WindowFunction::Rectangular => 1.0,
+            WindowFunction::Hann => 0.5 - 0.5 * phase.cos(),
+            WindowFunction::BlackmanHarris => 0.35875 - 0.48829 * phase.cos() + 0.14128 * (2.0 * phase).cos() - 0.01168 * (3.0 * phase).cos(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FlickerRisk {
+    NoEffect,
+    LowRisk,
+    Elevated,
+}
+
+impl FlickerRisk {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FlickerRisk::NoEffect => "Tanpa Efek Teramati",
+            FlickerRisk::LowRisk => "Risiko Rendah",
+            FlickerRisk::Elevated => "Melewati Batas Risiko Rendah",
+        }
+    }
+
+    // Batas IEEE 1789-2015 untuk modulasi (%) pada frekuensi `frequency` Hz. Batas risiko rendah berlaku
+    // sampai 1,25 kHz, batas tanpa efek sampai 3 kHz.
+    pub fn classify(frequency: f64, percent_flicker: f64) -> Self {
+        if frequency > 3000.0 {
+            return FlickerRisk::NoEffect;
+        }
+        let (no_effect, low_risk) = if frequency < 90.0 {
+            (0.01 * frequency, 0.025 * frequency)
+        } else if frequency <= 1250.0 {
+            (0.0333 * frequency, 0.08 * frequency)
+        } else {
+            (0.0333 * frequency, f64::INFINITY)
+        };
+        if percent_flicker <= no_effect {
+            FlickerRisk::NoEffect
+        } else if percent_flicker <= low_risk {
+            FlickerRisk::LowRisk
+        } else {
+            FlickerRisk::Elevated
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlickerSettings {
+    // Jumlah sampel FFT, pangkat dua.
+    pub window_size: usize,
+    pub window_function: WindowFunction,
+    // Laju sampel burst Arduino (Hz); `None` = diperkirakan dari timestamp perangkat. Tanpa timestamp
+    // perangkat laju harus ditetapkan, karena waktu terima di host mengukur jadwal proses, bukan laju burst.
+    pub sample_rate_override: Option<f64>,
+    // Komponen di bawah frekuensi ini (perubahan cahaya alami) diabaikan saat mencari frekuensi dominan.
+    pub min_frequency_hz: f64,
+}
+
+impl FlickerSettings {
+    pub const MIN_WINDOW: usize = 16;
+    pub const MAX_WINDOW: usize = 4096;
+
+    pub fn new() -> Self {
+        Self {
+            window_size: 1024,
+            window_function: WindowFunction::Hann,
+            sample_rate_override: None,
+            min_frequency_hz: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlickerAnalysis {
+    pub sample_count: usize,
+    pub sample_rate: f64,
+    // Simpangan baku relatif selang sampel; FFT mengasumsikan sampel berjarak sama.
+    pub timing_jitter: f64,
+    pub mean: f64,
+    // (Hz, amplitudo satu sisi dalam satuan sinyal), tanpa komponen DC.
+    pub spectrum: Vec<[f64; 2]>,
+    pub dominant_frequency: Option<f64>,
+    pub dominant_amplitude: f64,
+    // 100 · (maks − min) / (maks + min).
+    pub percent_flicker: f64,
+    // Luas di atas rata-rata dibagi luas total, 0–1.
+    pub flicker_index: f64,
+    pub risk: Option<FlickerRisk>,
+}
+
+impl FlickerAnalysis {
+    pub fn nyquist(&self) -> f64 {
+        0.5 * self.sample_rate
+    }
+}
+
+// FFT radix-2 in-place (Cooley-Tukey iteratif). Panjang harus pangkat dua.
+pub fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut length = 2;
+    while length <= n {
+        let angle = -2.0 * PI / length as f64;
+        let (w_re, w_im) = (angle.cos(), angle.sin());
+        for start in (0..n).step_by(length) {
+            let (mut t_re, mut t_im) = (1.0, 0.0);
+            for k in 0..length / 2 {
+                let (a, b) = (start + k, start + k + length / 2);
+                let u = (re[a], im[a]);
+                let v = (re[b] * t_re - im[b] * t_im, re[b] * t_im + im[b] * t_re);
+                re[a] = u.0 + v.0;
+                im[a] = u.1 + v.1;
+                re[b] = u.0 - v.0;
+                im[b] = u.1 - v.1;
+                (t_re, t_im) = (t_re * w_re - t_im * w_im, t_re * w_im + t_im * w_re);
+            }
+        }
+        length <<= 1;
+    }
+}
+
+// Jam perangkat dari timestamp `micros()` Arduino (u32, berputar tiap ~71 menit) dalam detik sejak sampel
+// pertama. Mundurnya timestamp (Arduino reset) tidak memajukan jam.
+pub struct DeviceClock {
+    last_micros: Option<u32>,
+    elapsed_micros: u64,
+}
+
+impl DeviceClock {
+    pub fn new() -> Self {
+        Self { last_micros: None, elapsed_micros: 0 }
+    }
+
+    pub fn seconds(&mut self, micros: u32) -> f64 {
+        if let Some(last) = self.last_micros {
+            let step = micros.wrapping_sub(last);
+            if step <= i32::MAX as u32 {
+                self.elapsed_micros += step as u64;
+            }
+        }
+        self.last_micros = Some(micros);
+        self.elapsed_micros as f64 / 1e6
+    }
+}
+
+// Baris serial `nilai` atau `micros,nilai` (pemisah koma atau spasi/tab); mengembalikan timestamp perangkat bila ada.
+pub fn parse_serial_line(line: &str) -> Option<(Option<u32>, f64)> {
+    let mut fields = line.split(|c: char| c == ',' || c.is_whitespace()).filter(|field| !field.is_empty());
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some(value), None, None) => Some((None, value.parse().ok()?)),
+        (Some(micros), Some(value), None) => Some((Some(micros.parse().ok()?), value.parse().ok()?)),
+        _ => None,
+    }
+}
+
+// Memakai sampel terakhir sebanyak pangkat dua terbesar yang tersedia (maksimal `window_size`).
+// `device_timestamps`: x sampel berasal dari jam perangkat sehingga laju sampel bisa diperkirakan.
+pub fn analyse(values: &[Value], settings: &FlickerSettings, device_timestamps: bool) -> Result<FlickerAnalysis, String> {
+    let available = values.len().min(settings.window_size.clamp(FlickerSettings::MIN_WINDOW, FlickerSettings::MAX_WINDOW));
+    if available < FlickerSettings::MIN_WINDOW {
+        return Err(format!("Menunggu minimal {} sampel untuk FFT...", FlickerSettings::MIN_WINDOW));
+    }
+    let override_rate = settings.sample_rate_override.filter(|rate| *rate > 0.0);
+    if override_rate.is_none() && !device_timestamps {
+        return Err("Sampel tanpa timestamp perangkat: tetapkan laju sampel burst Arduino secara manual, \
+            atau kirim baris `micros,nilai` dari firmware.".to_string());
+    }
+    let n = 1 << available.ilog2();
+    let window = &values[values.len() - n..];
+
+    let intervals: Vec<f64> = window.windows(2).map(|pair| pair[1].x - pair[0].x).filter(|dt| *dt > 0.0).collect();
+    if intervals.is_empty() {
+        return Err("Timestamp sampel tidak bertambah.".to_string());
+    }
+    let mean_interval = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    let interval_variance = intervals.iter().map(|dt| (dt - mean_interval).powi(2)).sum::<f64>() / intervals.len() as f64;
+    // Median selang agar jeda antar-burst tidak menurunkan perkiraan laju.
+    let sample_rate = override_rate.unwrap_or_else(|| {
+        let mut sorted = intervals.clone();
+        sorted.sort_by(f64::total_cmp);
+        1.0 / sorted[sorted.len() / 2]
+    });
+
+    let mean = window.iter().map(|v| v.y).sum::<f64>() / n as f64;
+    let max = window.iter().map(|v| v.y).fold(f64::MIN, f64::max);
+    let min = window.iter().map(|v| v.y).fold(f64::MAX, f64::min);
+    let percent_flicker = if max + min > 0.0 { 100.0 * (max - min) / (max + min) } else { 0.0 };
+    let total: f64 = window.iter().map(|v| v.y).sum();
+    let above_mean: f64 = window.iter().map(|v| (v.y - mean).max(0.0)).sum();
+    let flicker_index = if total > 0.0 { above_mean / total } else { 0.0 };
+
+    let coefficients: Vec<f64> = (0..n).map(|i| settings.window_function.coefficient(i, n)).collect();
+    let coherent_gain: f64 = coefficients.iter().sum();
+    let mut re: Vec<f64> = window.iter().zip(&coefficients).map(|(v, w)| (v.y - mean) * w).collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+
+    let spectrum: Vec<[f64; 2]> = (1..=n / 2)
+        .map(|k| {
+            let scale = if k == n / 2 { 1.0 } else { 2.0 };
+            [k as f64 * sample_rate / n as f64, scale * re[k].hypot(im[k]) / coherent_gain]
+        })
+        .collect();
+    let dominant = spectrum.iter()
+        .filter(|point| point[0] >= settings.min_frequency_hz)
+        .max_by(|a, b| a[1].total_cmp(&b[1]))
+        .copied()
+        .filter(|point| point[1] > 0.0);
+
+    Ok(FlickerAnalysis {
+        sample_count: n,
+        sample_rate,
+        timing_jitter: interval_variance.sqrt() / mean_interval,
+        mean,
+        spectrum,
+        dominant_frequency: dominant.map(|point| point[0]),
+        dominant_amplitude: dominant.map_or(0.0, |point| point[1]),
+        percent_flicker,
+        flicker_index,
+        risk: dominant.map(|point| FlickerRisk::classify(point[0], percent_flicker)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 1000.0;
+    const N: usize = 1024;
+
+    // DC 500 dengan modulasi sinus 100 pada frekuensi tepat di bin FFT ke-120 (117,1875 Hz).
+    fn sine_with_offset(frequency: f64) -> Vec<Value> {
+        (0..N)
+            .map(|i| {
+                let t = i as f64 / SAMPLE_RATE;
+                Value { x: t, y: 500.0 + 100.0 * (2.0 * PI * frequency * t).sin() }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_finds_dominant_frequency_and_percent_flicker() {
+        let frequency = 120.0 * SAMPLE_RATE / N as f64;
+        let settings = FlickerSettings { window_size: N, ..FlickerSettings::new() };
+        let analysis = analyse(&sine_with_offset(frequency), &settings, true).unwrap();
+        assert_eq!(analysis.sample_count, N);
+        assert!((analysis.sample_rate - SAMPLE_RATE).abs() < 1e-6, "{}", analysis.sample_rate);
+        assert!((analysis.mean - 500.0).abs() < 1e-9, "{}", analysis.mean);
+        assert!((analysis.dominant_frequency.unwrap() - frequency).abs() < 1e-9, "{:?}", analysis.dominant_frequency);
+        assert!((analysis.dominant_amplitude - 100.0).abs() < 0.5, "{}", analysis.dominant_amplitude);
+        // 100 · (600 − 400) / (600 + 400).
+        assert!((analysis.percent_flicker - 20.0).abs() < 0.05, "{}", analysis.percent_flicker);
+    }
+
+    #[test]
+    fn host_timestamps_require_manual_sample_rate() {
+        let values = sine_with_offset(50.0);
+        let mut settings = FlickerSettings { window_size: N, ..FlickerSettings::new() };
+        assert!(analyse(&values, &settings, false).is_err());
+
+        // Waktu host yang menggumpal tidak dipakai bila laju ditetapkan manual.
+        let host_timed: Vec<Value> = values.iter().enumerate().map(|(i, v)| Value { x: (i / 8) as f64 * 0.01, y: v.y }).collect();
+        settings.sample_rate_override = Some(SAMPLE_RATE);
+        let analysis = analyse(&host_timed, &settings, false).unwrap();
+        assert!((analysis.dominant_frequency.unwrap() - 50.0).abs() <= SAMPLE_RATE / N as f64);
+    }
+
+    #[test]
+    fn device_clock_unwraps_micros_overflow() {
+        let mut clock = DeviceClock::new();
+        assert_eq!(clock.seconds(u32::MAX - 499), 0.0);
+        assert!((clock.seconds(500) - 0.001).abs() < 1e-12);
+        // Arduino reset: timestamp mundur, jam tidak maju.
+        assert!((clock.seconds(10) - 0.001).abs() < 1e-12);
+        assert!((clock.seconds(1010) - 0.002).abs() < 1e-12);
+        assert_eq!(parse_serial_line("123456,512"), Some((Some(123456), 512.0)));
+        assert_eq!(parse_serial_line(" 512.5 "), Some((None, 512.5)));
+        assert_eq!(parse_serial_line("abc"), None);
+    }
+}
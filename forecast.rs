@@ -0,0 +1,409 @@
+// src/forecast.rs
+// Prakiraan lux beberapa jam ke depan dari agregat tersimpan: regresi harmonik pola harian (dikoreksi
+// kondisi terkini) atau Holt-Winters aditif dengan musim satu hari, serta proyeksi DLI hari berjalan.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::dli::{self, DliSettings};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+// Kuantil normal untuk interval prediksi 95%.
+const Z_95: f64 = 1.96;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForecastMethod {
+    HarmonicRegression,
+    HoltWinters,
+}
+
+impl ForecastMethod {
+    pub const ALL: [ForecastMethod; 2] = [ForecastMethod::HarmonicRegression, ForecastMethod::HoltWinters];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ForecastMethod::HarmonicRegression => "Regresi Harmonik Harian",
+            ForecastMethod::HoltWinters => "Holt-Winters Aditif",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForecastSettings {
+    pub method: ForecastMethod,
+    pub history_days: u32,
+    // Harus membagi 1440 agar musim harian tepat sejumlah bucket.
+    pub bucket_minutes: u32,
+    // Prakiraan selalu diperpanjang sampai akhir hari lokal untuk proyeksi DLI.
+    pub horizon_hours: f64,
+    pub harmonics: usize,
+    // Rasio lux terukur / model selama jendela ini mengoreksi regresi harmonik (mis. hari berawan),
+    // dengan pengaruh meluruh eksponensial sesuai `recent_decay_hours`.
+    pub recent_hours: f64,
+    pub recent_decay_hours: f64,
+    // Konstanta pemulusan Holt-Winters: level, tren, musiman.
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    // PPFD lampu tambahan (µmol/m²/s) untuk menghitung lama penyinaran yang dibutuhkan.
+    pub supplemental_ppfd: f64,
+}
+
+impl ForecastSettings {
+    pub const BUCKET_CHOICES: [u32; 5] = [5, 10, 15, 30, 60];
+
+    pub fn new() -> Self {
+        Self {
+            method: ForecastMethod::HarmonicRegression,
+            history_days: 7,
+            bucket_minutes: 10,
+            horizon_hours: 6.0,
+            harmonics: 4,
+            recent_hours: 1.0,
+            recent_decay_hours: 2.0,
+            alpha: 0.2,
+            beta: 0.01,
+            gamma: 0.3,
+            supplemental_ppfd: 150.0,
+        }
+    }
+
+    fn bucket_seconds(&self) -> i64 {
+        self.bucket_minutes.max(1) as i64 * 60
+    }
+
+    // Batas celah integrasi DLI untuk deret per bucket: jarak antarbucket yang wajar tidak boleh
+    // dianggap celah data walau melebihi batas celah sampel live (mis. bucket 30/60 menit).
+    fn max_gap_seconds(&self, dli_settings: &DliSettings) -> f64 {
+        dli_settings.max_gap_seconds.max(1.5 * self.bucket_seconds() as f64)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForecastPoint {
+    pub timestamp: DateTime<Utc>,
+    pub lux: f64,
+    // Interval prediksi 95%, tidak negatif.
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forecast {
+    pub method: ForecastMethod,
+    pub generated_at: DateTime<Utc>,
+    // Agregat terukur 24 jam terakhir untuk digambar sebelum prakiraan.
+    pub recent_observed: Vec<(DateTime<Utc>, f64)>,
+    pub points: Vec<ForecastPoint>,
+    pub observation_count: usize,
+    pub residual_std: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DliProjection {
+    pub date: NaiveDate,
+    // mol/m² yang sudah terkumpul hari ini.
+    pub accumulated: f64,
+    // Tambahan menurut prakiraan sampai akhir hari, dengan batas 95%.
+    pub remaining: f64,
+    pub remaining_lower: f64,
+    pub remaining_upper: f64,
+    pub target: f64,
+}
+
+impl DliProjection {
+    pub fn projected(&self) -> f64 {
+        self.accumulated + self.remaining
+    }
+
+    pub fn deficit(&self) -> f64 {
+        (self.target - self.projected()).max(0.0)
+    }
+
+    // Jam lampu tambahan pada `ppfd` µmol/m²/s untuk menutup kekurangan.
+    pub fn supplemental_hours(&self, ppfd: f64) -> Option<f64> {
+        (ppfd > 0.0).then(|| self.deficit() * 1e6 / (ppfd * 3600.0))
+    }
+}
+
+fn unix_seconds(timestamp: DateTime<Utc>) -> f64 {
+    timestamp.timestamp_millis() as f64 / 1000.0
+}
+
+// Eliminasi Gauss dengan pivot parsial; `None` bila matriks singular.
+fn solve_linear(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+        let pivot_row = matrix[col].clone();
+        for row in col + 1..n {
+            let factor = matrix[row][col] / pivot_row[col];
+            for (value, pivot_value) in matrix[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+// Konstanta, tren linier (per hari relatif terhadap `origin`), lalu pasangan cos/sin harmonik harian.
+fn harmonic_features(t: f64, origin: f64, harmonics: usize) -> Vec<f64> {
+    let phase = 2.0 * std::f64::consts::PI * t.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_DAY;
+    let mut features = vec![1.0, (t - origin) / SECONDS_PER_DAY];
+    for k in 1..=harmonics {
+        features.push((k as f64 * phase).cos());
+        features.push((k as f64 * phase).sin());
+    }
+    features
+}
+
+// Simpangan baku residual per jam (UTC); jam dengan residual terlalu sedikit memakai nilai global.
+fn hourly_sigma(residuals: &[(f64, f64)]) -> [f64; 24] {
+    let global = (residuals.iter().map(|(_, r)| r * r).sum::<f64>() / residuals.len().max(1) as f64).sqrt();
+    let mut sums = [(0.0, 0usize); 24];
+    for &(t, r) in residuals {
+        let hour = (t.rem_euclid(SECONDS_PER_DAY) / 3600.0) as usize % 24;
+        sums[hour].0 += r * r;
+        sums[hour].1 += 1;
+    }
+    sums.map(|(sum, count)| if count >= 3 { (sum / count as f64).sqrt() } else { global })
+}
+
+fn harmonic_forecast(observations: &[(f64, f64)], settings: &ForecastSettings, now: f64, targets: &[f64]) -> Result<(Vec<ForecastPoint>, f64), String> {
+    let harmonics = settings.harmonics.clamp(1, 12);
+    let parameter_count = 2 + 2 * harmonics;
+    if observations.len() < 2 * parameter_count {
+        return Err(format!("Data terlalu sedikit untuk regresi harmonik ({} bucket, minimal {}).", observations.len(), 2 * parameter_count));
+    }
+
+    let mut normal = vec![vec![0.0; parameter_count]; parameter_count];
+    let mut rhs = vec![0.0; parameter_count];
+    for &(t, lux) in observations {
+        let features = harmonic_features(t, now, harmonics);
+        for i in 0..parameter_count {
+            rhs[i] += features[i] * lux;
+            for j in 0..parameter_count {
+                normal[i][j] += features[i] * features[j];
+            }
+        }
+    }
+    let coefficients = solve_linear(normal, rhs).ok_or("Regresi harmonik gagal: data tidak mencakup cukup variasi waktu.")?;
+    let model = |t: f64| harmonic_features(t, now, harmonics).iter().zip(&coefficients).map(|(f, c)| f * c).sum::<f64>();
+
+    let residuals: Vec<(f64, f64)> = observations.iter().map(|&(t, lux)| (t, lux - model(t))).collect();
+    let sigma = hourly_sigma(&residuals);
+    let residual_std = (residuals.iter().map(|(_, r)| r * r).sum::<f64>() / residuals.len() as f64).sqrt();
+
+    let recent_start = now - settings.recent_hours * 3600.0;
+    let (observed_sum, model_sum) = observations.iter()
+        .filter(|(t, _)| *t >= recent_start)
+        .fold((0.0, 0.0), |(o, m), &(t, lux)| (o + lux, m + model(t).max(0.0)));
+    // Malam hari (model ≈ 0) tidak memberi informasi kondisi langit.
+    let ratio = if model_sum > 1.0 { (observed_sum / model_sum).clamp(0.1, 3.0) } else { 1.0 };
+
+    let points = targets.iter()
+        .map(|&t| {
+            let hours_ahead = ((t - now) / 3600.0).max(0.0);
+            let correction = 1.0 + (ratio - 1.0) * (-hours_ahead / settings.recent_decay_hours.max(0.1)).exp();
+            let lux = (model(t) * correction).max(0.0);
+            let spread = Z_95 * sigma[(t.rem_euclid(SECONDS_PER_DAY) / 3600.0) as usize % 24];
+            ForecastPoint {
+                timestamp: DateTime::from_timestamp_millis((t * 1000.0) as i64).unwrap_or_default(),
+                lux,
+                lower: (lux - spread).max(0.0),
+                upper: lux + spread,
+            }
+        })
+        .collect();
+    Ok((points, residual_std))
+}
+
+fn holt_winters_forecast(observations: &[(f64, f64)], settings: &ForecastSettings, targets: &[f64]) -> Result<(Vec<ForecastPoint>, f64), String> {
+    let bucket = settings.bucket_seconds();
+    let season = (SECONDS_PER_DAY as i64 / bucket) as usize;
+    let index_of = |t: f64| (t as i64).div_euclid(bucket);
+    let (Some(first), Some(last)) = (observations.first(), observations.last()) else {
+        return Err("Tidak ada data untuk Holt-Winters.".to_string());
+    };
+    let (first_index, last_index) = (index_of(first.0), index_of(last.0));
+    let length = (last_index - first_index + 1) as usize;
+    if length < 2 * season {
+        return Err(format!("Holt-Winters membutuhkan riwayat minimal 2 hari ({:.1} hari tersedia).", length as f64 / season as f64));
+    }
+
+    let mut grid: Vec<Option<f64>> = vec![None; length];
+    for &(t, lux) in observations {
+        grid[(index_of(t) - first_index) as usize] = Some(lux);
+    }
+    let season_mean = |offset: usize| {
+        let values: Vec<f64> = grid[offset..offset + season].iter().flatten().copied().collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    let first_mean = season_mean(0).ok_or("Hari pertama riwayat tidak berisi data.")?;
+    let mut level = first_mean;
+    let mut trend = season_mean(season).map_or(0.0, |second_mean| (second_mean - first_mean) / season as f64);
+    // Indeks musim mengikuti bucket absolut sejak epoch agar selaras dengan jam dalam sehari.
+    let season_slot = |index: i64| index.rem_euclid(season as i64) as usize;
+    let mut seasonal = vec![0.0; season];
+    for (offset, value) in grid[..season].iter().enumerate() {
+        let initial = value.or(grid[offset + season].map(|next| next - season as f64 * trend));
+        seasonal[season_slot(first_index + offset as i64)] = initial.map_or(0.0, |value| value - level);
+    }
+
+    let (alpha, beta, gamma) = (settings.alpha.clamp(0.0, 1.0), settings.beta.clamp(0.0, 1.0), settings.gamma.clamp(0.0, 1.0));
+    let mut squared_errors = (0.0, 0usize);
+    for (offset, value) in grid.iter().enumerate() {
+        let slot = season_slot(first_index + offset as i64);
+        let predicted = level + trend + seasonal[slot];
+        let Some(observed) = *value else {
+            // Bucket kosong: keadaan maju tanpa koreksi.
+            level += trend;
+            continue;
+        };
+        if offset >= season {
+            squared_errors.0 += (observed - predicted).powi(2);
+            squared_errors.1 += 1;
+        }
+        let new_level = alpha * (observed - seasonal[slot]) + (1.0 - alpha) * (level + trend);
+        trend = beta * (new_level - level) + (1.0 - beta) * trend;
+        seasonal[slot] = gamma * (observed - new_level) + (1.0 - gamma) * seasonal[slot];
+        level = new_level;
+    }
+    let residual_std = (squared_errors.0 / squared_errors.1.max(1) as f64).sqrt();
+
+    let points = targets.iter()
+        .map(|&t| {
+            let steps = (index_of(t) - last_index).max(1);
+            let lux = (level + steps as f64 * trend + seasonal[season_slot(index_of(t))]).max(0.0);
+            // Varians h langkah Holt-Winters aditif (Hyndman dkk.).
+            let variance_factor: f64 = 1.0 + (1..steps)
+                .map(|j| {
+                    let seasonal_term = if (j as usize).is_multiple_of(season) { gamma } else { 0.0 };
+                    (alpha * (1.0 + j as f64 * beta) + seasonal_term).powi(2)
+                })
+                .sum::<f64>();
+            let spread = Z_95 * residual_std * variance_factor.sqrt();
+            ForecastPoint {
+                timestamp: DateTime::from_timestamp_millis((t * 1000.0) as i64).unwrap_or_default(),
+                lux,
+                lower: (lux - spread).max(0.0),
+                upper: lux + spread,
+            }
+        })
+        .collect();
+    Ok((points, residual_std))
+}
+
+// `history` berisi rata-rata lux per bucket (titik tengah bucket), terurut naik. Prakiraan dimulai
+// pada `now` dan berlanjut per bucket sampai horizon atau akhir hari lokal, mana yang lebih lambat.
+pub fn forecast(history: &[(DateTime<Utc>, f64)], settings: &ForecastSettings, dli_settings: &DliSettings, now: DateTime<Utc>) -> Result<Forecast, String> {
+    let observations: Vec<(f64, f64)> = history.iter()
+        .filter(|(timestamp, lux)| *timestamp <= now && lux.is_finite())
+        .map(|&(timestamp, lux)| (unix_seconds(timestamp), lux.max(0.0)))
+        .collect();
+
+    let tomorrow = dli_settings.local_date(now).succ_opt().ok_or("Tanggal di luar jangkauan.")?;
+    let end_of_day = tomorrow.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(dli_settings.utc_offset).unwrap().with_timezone(&Utc);
+    let until = end_of_day.max(now + Duration::seconds((settings.horizon_hours * 3600.0) as i64));
+    let bucket = settings.bucket_seconds();
+    let steps = (until - now).num_seconds() / bucket;
+    let now_seconds = unix_seconds(now);
+    let targets: Vec<f64> = (0..=steps).map(|i| now_seconds + (i * bucket) as f64).collect();
+
+    let (points, residual_std) = match settings.method {
+        ForecastMethod::HarmonicRegression => harmonic_forecast(&observations, settings, now_seconds, &targets)?,
+        ForecastMethod::HoltWinters => holt_winters_forecast(&observations, settings, &targets)?,
+    };
+    Ok(Forecast {
+        method: settings.method,
+        generated_at: now,
+        recent_observed: history.iter().filter(|(timestamp, _)| *timestamp > now - Duration::hours(24)).copied().collect(),
+        points,
+        observation_count: observations.len(),
+        residual_std,
+    })
+}
+
+// `accumulated` = DLI hari ini sampai `now` (mol/m²); sisa hari diintegrasikan dari prakiraan.
+pub fn project_dli(forecast: &Forecast, accumulated: f64, ppfd_factor: f64, settings: &ForecastSettings, dli_settings: &DliSettings, now: DateTime<Utc>) -> DliProjection {
+    let max_gap_seconds = settings.max_gap_seconds(dli_settings);
+    let today = dli_settings.local_date(now);
+    let remaining_points: Vec<&ForecastPoint> = forecast.points.iter()
+        .filter(|point| point.timestamp >= now && dli_settings.local_date(point.timestamp) == today)
+        .collect();
+    let integral = |value: fn(&ForecastPoint) -> f64| {
+        let samples: Vec<(f64, f64)> = remaining_points.iter().map(|point| (unix_seconds(point.timestamp), value(point) * ppfd_factor)).collect();
+        dli::integrate(&samples, dli_settings.method, max_gap_seconds).micromoles / 1e6
+    };
+    DliProjection {
+        date: today,
+        accumulated,
+        remaining: integral(|point| point.lux),
+        remaining_lower: integral(|point| point.lower),
+        remaining_upper: integral(|point| point.upper),
+        target: dli_settings.target,
+    }
+}
+
+// DLI terkumpul hari ini dari agregat lux bila data live belum mencakup hari ini.
+pub fn accumulated_from_history(history: &[(DateTime<Utc>, f64)], ppfd_factor: f64, settings: &ForecastSettings, dli_settings: &DliSettings, now: DateTime<Utc>) -> f64 {
+    let today = dli_settings.local_date(now);
+    let samples: Vec<(f64, f64)> = history.iter()
+        .filter(|(timestamp, _)| *timestamp <= now && dli_settings.local_date(*timestamp) == today)
+        .map(|&(timestamp, lux)| (unix_seconds(timestamp), lux * ppfd_factor))
+        .collect();
+    dli::integrate(&samples, dli_settings.method, settings.max_gap_seconds(dli_settings)).micromoles / 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+
+    const LUX: f64 = 10_000.0;
+
+    // Riwayat lux konstan per bucket (titik tengah bucket) selama `days` hari sampai `now`.
+    fn constant_history(settings: &ForecastSettings, days: i64, now: DateTime<Utc>) -> Vec<(DateTime<Utc>, f64)> {
+        let bucket = Duration::seconds(settings.bucket_seconds());
+        let mut history = Vec::new();
+        let mut timestamp = now - Duration::days(days) + bucket / 2;
+        while timestamp <= now {
+            history.push((timestamp, LUX));
+            timestamp += bucket;
+        }
+        history
+    }
+
+    #[test]
+    fn hourly_buckets_still_integrate_dli() {
+        let dli_settings = DliSettings { utc_offset: FixedOffset::east_opt(0).unwrap(), ..DliSettings::new() };
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        for bucket_minutes in [30, 60] {
+            let settings = ForecastSettings { bucket_minutes, ..ForecastSettings::new() };
+            let history = constant_history(&settings, 7, now);
+            let ppfd_factor = 0.0185;
+            let expected_mol_per_hour = LUX * ppfd_factor * 3600.0 / 1e6;
+
+            let accumulated = accumulated_from_history(&history, ppfd_factor, &settings, &dli_settings, now);
+            // Titik tengah bucket pertama dan terakhir hari ini masing-masing setengah bucket dari tengah malam dan `now`.
+            let covered_hours = 12.0 - bucket_minutes as f64 / 60.0;
+            assert!((accumulated - expected_mol_per_hour * covered_hours).abs() < 1e-6, "bucket {}: {}", bucket_minutes, accumulated);
+
+            let result = forecast(&history, &settings, &dli_settings, now).unwrap();
+            let projection = project_dli(&result, accumulated, ppfd_factor, &settings, &dli_settings, now);
+            assert!(projection.remaining > 0.0, "bucket {}: {:?}", bucket_minutes, projection);
+            assert!(projection.remaining_upper >= projection.remaining);
+        }
+    }
+}
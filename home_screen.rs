@@ -1,15 +1,126 @@
-use egui::{Ui, RichText, Color32};
+use egui::{Ui, RichText, Color32, Grid};
+use crate::adc::{AdcPolarity, AdcSettings};
+use crate::crop::{Bed, StageTargets, TargetRange, TargetStatus};
+use crate::diagnostics::SensorHealth;
+use crate::ppfd::LightSource;
+
+// Kondisi cahaya terkini untuk sensor yang ditampilkan.
+pub struct LightConditions {
+    pub lux: Option<f64>,
+    pub ppfd: Option<f64>,
+    pub light_source: LightSource,
+    // DLI hari berjalan dan hari terakhir yang sudah selesai (mol/m²/hari).
+    pub dli_today: Option<f64>,
+    pub dli_last_complete: Option<f64>,
+    // Fotoperiode hari terakhir yang sudah lewat (jam), dari Ringkasan Harian.
+    pub photoperiod_hours: Option<f64>,
+}
 
 pub struct HomeScreen {
+    // Bedengan yang dievaluasi; `None` = bedengan pertama untuk sensor ini.
+    pub selected_bed: Option<String>,
+}
+
+fn status_color(status: TargetStatus) -> Color32 {
+    match status {
+        TargetStatus::Below => Color32::from_rgb(100, 100, 200),
+        TargetStatus::Within => Color32::LIGHT_GREEN,
+        TargetStatus::Above => Color32::YELLOW,
+    }
+}
+
+fn status_icon(status: TargetStatus) -> &'static str {
+    match status {
+        TargetStatus::Below => "🌙",
+        TargetStatus::Within => "✅",
+        TargetStatus::Above => "☀️",
+    }
 }
 
 impl HomeScreen {
     pub fn new() -> Self {
         Self {
+            selected_bed: None,
+        }
+    }
+
+    fn show_stage_targets(ui: &mut Ui, bed: &Bed, days: i64, targets: &StageTargets, conditions: &LightConditions) {
+        ui.label(RichText::new(format!("Bedengan {} · {} · HST {} · Tahap {}", bed.id, bed.crop, days, targets.stage.label()))
+            .color(Color32::WHITE)
+            .size(18.0));
+        ui.add_space(10.0);
+
+        let row = |ui_grid: &mut Ui, name: &str, value: Option<f64>, range: &TargetRange, unit: &str, decimals: usize| {
+            ui_grid.label(RichText::new(name).color(Color32::WHITE));
+            ui_grid.label(value.map(|v| format!("{:.*} {}", decimals, v, unit)).unwrap_or_else(|| "-".to_string()));
+            ui_grid.label(range.describe(unit));
+            match value.map(|v| range.evaluate(v)) {
+                Some(status) => ui_grid.label(RichText::new(format!("{} {}", status_icon(status), status.label())).color(status_color(status))),
+                None => ui_grid.label(RichText::new("Belum ada data").color(Color32::GRAY)),
+            };
+            ui_grid.end_row();
+        };
+
+        Grid::new("stage_targets_grid")
+            .num_columns(4)
+            .striped(true)
+            .spacing([30.0, 8.0])
+            .show(ui, |ui_grid| {
+                for header in ["Parameter", "Saat Ini", "Target Tahap", "Status"] {
+                    ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                }
+                ui_grid.end_row();
+
+                row(ui_grid, "Intensitas Cahaya", conditions.lux, &targets.lux, "Lux", 0);
+                row(ui_grid, "PPFD", conditions.ppfd, &targets.ppfd, "µmol/m²/s", 1);
+                row(ui_grid, "DLI Hari Terakhir", conditions.dli_last_complete, &targets.dli, "mol/m²/hari", 2);
+
+                // DLI hari berjalan masih bertambah, jadi hanya ditampilkan sebagai kemajuan terhadap batas bawah.
+                ui_grid.label(RichText::new("DLI Hari Ini").color(Color32::WHITE));
+                ui_grid.label(conditions.dli_today.map(|dli| format!("{:.2} mol/m²", dli)).unwrap_or_else(|| "-".to_string()));
+                ui_grid.label(targets.dli.describe("mol/m²/hari"));
+                ui_grid.label(conditions.dli_today
+                    .map(|dli| format!("{:.0}% dari batas bawah", 100.0 * dli / targets.dli.min))
+                    .unwrap_or_default());
+                ui_grid.end_row();
+
+                row(ui_grid, "Fotoperiode Hari Terakhir", conditions.photoperiod_hours, &targets.photoperiod_hours, "jam", 1);
+            });
+    }
+
+    fn show_health(ui: &mut Ui, health: &[SensorHealth]) {
+        let unhealthy: Vec<&SensorHealth> = health.iter().filter(|report| !report.is_healthy()).collect();
+        if unhealthy.is_empty() {
+            ui.label(RichText::new(format!("✅ {} sensor sehat pada pemeriksaan terakhir.", health.len())).color(Color32::LIGHT_GREEN));
+            return;
         }
+        ui.group(|ui| {
+            ui.add_space(5.0);
+            ui.heading(RichText::new("⚠ Peringatan Kesehatan Sensor").color(Color32::from_rgb(255, 120, 80)).strong());
+            ui.add_space(5.0);
+            for report in unhealthy {
+                for warning in report.active_warnings() {
+                    ui.label(RichText::new(format!("Sensor {}: {} — {}", report.sensor_id, warning.issue.label(), warning.detail)).color(Color32::WHITE));
+                }
+            }
+            ui.label(RichText::new("Lihat Diagnostik Sensor untuk rinciannya.").color(Color32::GRAY).italics());
+            ui.add_space(5.0);
+        });
     }
 
-    pub fn show(&mut self, ui: &mut Ui, current_photodiode_value: f64) {
+    pub fn show(&mut self, ui: &mut Ui, current_photodiode_value: f64, adc: &AdcSettings, conditions: &LightConditions, beds: &[Bed], health: &[SensorHealth]) {
+        let today = chrono::Local::now().date_naive();
+        let sensor_beds: Vec<&Bed> = beds.iter().filter(|bed| bed.sensor_id == crate::SENSOR_PORT).collect();
+        let bed = sensor_beds.iter()
+            .find(|bed| Some(&bed.id) == self.selected_bed.as_ref())
+            .or(sensor_beds.first())
+            .copied();
+        let stage = bed.and_then(|bed| {
+            let crop = bed.crop_profile()?;
+            let days = bed.days_after_planting(today);
+            Some((bed, days, crop.stage_for(days).clone()))
+        });
+
         ui.vertical_centered(|ui| {
             ui.add_space(20.0);
             ui.heading(RichText::new("Selamat Datang di Sistem Pemantauan Tanaman Selada Greenhouse")
@@ -19,31 +130,45 @@ impl HomeScreen {
 
             ui.add_space(30.0);
 
+            if !health.is_empty() {
+                Self::show_health(ui, health);
+                ui.add_space(20.0);
+            }
+
             ui.group(|ui| {
                 ui.add_space(10.0);
                 ui.vertical_centered(|ui| {
                     ui.heading(RichText::new("Status Intensitas Cahaya").color(Color32::WHITE).strong());
                     ui.add_space(15.0);
 
-                    let photodiode_text_color = if current_photodiode_value < 300.0 {
+                    // Tanpa bedengan, klasifikasi memakai porsi cahaya dari pengaturan ADC sensor, bukan nilai mentah.
+                    let light_fraction = adc.light_fraction(current_photodiode_value);
+                    // Dengan bedengan, status mengikuti target PPFD tahap aktif (lux bila PPFD belum ada).
+                    let stage_status = stage.as_ref().and_then(|(_, _, targets)| match (conditions.ppfd, conditions.lux) {
+                        (Some(ppfd), _) => Some(("PPFD", targets.ppfd.evaluate(ppfd))),
+                        (None, Some(lux)) => Some(("Lux", targets.lux.evaluate(lux))),
+                        (None, None) => None,
+                    });
+
+                    let photodiode_text_color = if light_fraction > 0.7 {
                         Color32::YELLOW 
-                    } else if current_photodiode_value < 600.0 {
+                    } else if light_fraction > 0.4 {
                         Color32::LIGHT_GREEN 
                     } else {
                         Color32::from_rgb(100, 100, 200) 
                     };
 
-                    let light_status_icon = if current_photodiode_value < 300.0 {
+                    let light_status_icon = if light_fraction > 0.7 {
                         "☀️" 
-                    } else if current_photodiode_value < 600.0 {
+                    } else if light_fraction > 0.4 {
                         "☁️" 
                     } else {
                         "🌙" 
                     };
 
-                    let light_status_text = if current_photodiode_value < 300.0 {
+                    let light_status_text = if light_fraction > 0.7 {
                         "Sangat Terang"
-                    } else if current_photodiode_value < 600.0 {
+                    } else if light_fraction > 0.4 {
                         "Normal"
                     } else {
                         "Gelap"
@@ -54,10 +179,52 @@ impl HomeScreen {
                         .size(40.0)
                         .strong());
                     ui.add_space(10.0);
-                    ui.label(RichText::new(format!("{} {}", light_status_icon, light_status_text))
-                        .color(photodiode_text_color)
-                        .size(22.0)
-                        .italics());
+                    match stage_status {
+                        Some((metric, status)) => ui.label(RichText::new(format!("{} {} {}", status_icon(status), metric, status.label()))
+                            .color(status_color(status))
+                            .size(22.0)
+                            .italics()),
+                        None => ui.label(RichText::new(format!("{} {}", light_status_icon, light_status_text))
+                            .color(photodiode_text_color)
+                            .size(22.0)
+                            .italics()),
+                    };
+                    ui.add_space(10.0);
+                    match conditions.ppfd {
+                        Some(ppfd) => ui.label(RichText::new(format!("PPFD: {:.1} µmol/m²/s ({})", ppfd, conditions.light_source.label()))
+                            .color(Color32::LIGHT_GREEN)
+                            .size(22.0)),
+                        None => ui.label(RichText::new("PPFD: menunggu hasil lux").color(Color32::GRAY).size(18.0)),
+                    };
+                });
+                ui.add_space(10.0);
+            });
+
+            ui.add_space(20.0);
+
+            ui.group(|ui| {
+                ui.add_space(10.0);
+                ui.vertical_centered(|ui| {
+                    ui.heading(RichText::new("Target Tahap Pertumbuhan").color(Color32::WHITE).strong());
+                    ui.add_space(10.0);
+                    if sensor_beds.len() > 1 {
+                        egui::ComboBox::from_id_salt("home_bed_combo")
+                            .selected_text(bed.map(|bed| bed.id.clone()).unwrap_or_default())
+                            .show_ui(ui, |ui_combo| {
+                                for bed in &sensor_beds {
+                                    ui_combo.selectable_value(&mut self.selected_bed, Some(bed.id.clone()), &bed.id);
+                                }
+                            });
+                        ui.add_space(10.0);
+                    }
+                    match &stage {
+                        Some((bed, days, targets)) => Self::show_stage_targets(ui, bed, *days, targets, conditions),
+                        None => {
+                            ui.label(RichText::new("Belum ada bedengan untuk sensor ini. Tambahkan bedengan dan tanggal tanam di Konfigurasi Sensor.")
+                                .color(Color32::GRAY)
+                                .italics());
+                        },
+                    }
                 });
                 ui.add_space(10.0);
             });
@@ -76,11 +243,15 @@ impl HomeScreen {
             });
             ui.horizontal(|ui| {
                 ui.label(RichText::new("ℹ️ Info:").strong().color(Color32::WHITE));
-                ui.label(RichText::new("Rentang nilai photodiode 0-1023 (0=Terang, 1023=Gelap)").color(Color32::WHITE));
+                let range_text = match adc.polarity {
+                    AdcPolarity::DarkLow => format!("Rentang nilai photodiode 0-{} (0=Gelap, {}=Terang)", adc.max_value(), adc.max_value()),
+                    AdcPolarity::DarkHigh => format!("Rentang nilai photodiode 0-{} (0=Terang, {}=Gelap)", adc.max_value(), adc.max_value()),
+                };
+                ui.label(RichText::new(range_text).color(Color32::WHITE));
             });
             ui.horizontal(|ui| {
                 ui.label(RichText::new("⚠️ Catatan:").strong().color(Color32::RED));
-                ui.label(RichText::new("Asumsi: Nilai photodiode rendah = Terang, Nilai photodiode tinggi = Gelap").color(Color32::WHITE));
+                ui.label(RichText::new(format!("Polaritas sensor: {}. Ubah di Konfigurasi Sensor bila rangkaian berbeda.", adc.polarity.label())).color(Color32::WHITE));
             });
         });
     }
@@ -1,20 +1,51 @@
 mod measurements;
 mod screens;
 mod db;
+mod sqlite_db;
+mod storage;
+mod export;
+mod parquet_export;
+mod csv_import;
+mod cli;
+mod solvers;
+mod conversion;
+mod calibration;
+mod adc;
+mod calibration_model;
+mod calibration_profile;
+mod recompute;
+mod uncertainty;
+mod ppfd;
+mod dli;
+mod crop;
+mod photoperiod;
+mod filters;
+mod outliers;
+mod diagnostics;
+mod flicker;
+mod forecast;
 
 
 use eframe::{App, CreationContext, NativeOptions};
 use egui::{CentralPanel, Context, ViewportBuilder, TopBottomPanel, SidePanel, Layout, Color32, RichText, Frame, Stroke};
 
 
-use measurements::{Measurements, Value};
+use measurements::{Measurements, SolveResult, Value};
+use adc::AdcSettings;
+use calibration_model::CalibrationSettings;
+use conversion::{ConversionMode, LuxLookupTable};
+use solvers::LuxProblem;
+use storage::{ResultMetadata, SeriesKind, Storage, StorageBackend, StoredRecord};
 use screens::{
-    home_screen::HomeScreen,
+    home_screen::{HomeScreen, LightConditions},
     data_graphics_screen::DataGraphicsScreen,
     database_screen::{DatabaseScreen, DatabaseDataType}, 
     sensor_configuration_screen::SensorConfigurationScreen,
+    summary_screen::SummaryScreen,
+    diagnostics_screen::DiagnosticsScreen,
 };
 
+use std::collections::HashMap;
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -22,34 +53,52 @@ use std::sync::{Arc, Mutex};
 use serialport;
 
 
+// Port serial sekaligus dipakai sebagai ID sensor pada data yang disimpan.
+pub const SENSOR_PORT: &str = "COM4";
+
 #[derive(PartialEq)]
 enum AppScreen {
     Home,
     DataGraphics,
     Database,
     SensorConfiguration,
+    Summary,
+    Diagnostics,
 }
 
 pub enum AppEvent {
     NewtonRaphsonCalculated {
-        akar: f64,
-        history: Vec<f64>,
+        result: SolveResult,
     },
 }
 
 struct MyApp {
     pub measurements: Arc<Mutex<Measurements>>, 
     pub newton_raphson_lux_measurements: Arc<Mutex<Measurements>>, 
-    photodiode_data_receiver: mpsc::Receiver<Value>,
+    // Nilai photodiode dan timestamp perangkat (detik jam Arduino) bila firmware mengirimkannya.
+    photodiode_data_receiver: mpsc::Receiver<(Value, Option<f64>)>,
     current_photodiode_value: f64,
+    // Lux dan PPFD (µmol/m²/s) terakhir yang berhasil dihitung.
+    current_lux: Option<f64>,
+    current_ppfd: Option<f64>,
     current_screen: AppScreen,
+    // Lux terakhir yang konvergen per sensor, titik awal warm start.
+    last_lux_by_sensor: HashMap<String, f64>,
+    lux_lookup_table: Option<LuxLookupTable>,
+    samples_since_nr_validation: u32,
+    dli_tracker: dli::DliTracker,
+    // Waktu saat `start_time` thread serial diambil; x pada `Measurements` adalah detik sejak saat ini.
+    started_at: chrono::DateTime<chrono::Utc>,
 
     home_screen: HomeScreen,
     data_graphics_screen: DataGraphicsScreen,
     database_screen: DatabaseScreen,
     sensor_configuration_screen: SensorConfigurationScreen,
+    summary_screen: SummaryScreen,
+    diagnostics_screen: DiagnosticsScreen,
     
-    database_data: Arc<Mutex<Vec<mongodb::bson::Document>>>,
+    storage: Arc<dyn Storage>,
+    database_data: Arc<Mutex<Vec<StoredRecord>>>,
 
     serial_status_message: String,
     serial_status_receiver: mpsc::Receiver<String>,
@@ -62,7 +111,7 @@ struct MyApp {
 
 
 impl MyApp {
-    fn new(cc: &CreationContext<'_>) -> Self {
+    fn new(cc: &CreationContext<'_>, storage: Arc<dyn Storage>, storage_backend: StorageBackend) -> Self {
         let mut style = (*cc.egui_ctx.style()).clone();
         style.text_styles.insert(
             egui::TextStyle::Heading,
@@ -78,8 +127,32 @@ impl MyApp {
         let (status_tx, status_rx) = mpsc::channel(); 
         let (app_event_tx, app_event_rx) = mpsc::channel(); 
         let start_time = Instant::now();
+        let started_at = chrono::Utc::now();
 
-        let sensor_config_screen = SensorConfigurationScreen::new();
+        let mut sensor_config_screen = SensorConfigurationScreen::new(storage_backend.clone());
+        // Profil yang terakhir diaktifkan dipulihkan agar kalibrasi bertahan antar sesi.
+        match storage.list_calibration_profiles(None) {
+            Ok(profiles) => *sensor_config_screen.calibration_profiles.lock().unwrap() = profiles,
+            Err(e) => eprintln!("[DB Thread] GAGAL memuat profil kalibrasi: {:?}", e),
+        }
+        match storage.list_beds() {
+            Ok(beds) => *sensor_config_screen.beds.lock().unwrap() = beds,
+            Err(e) => eprintln!("[DB Thread] GAGAL memuat bedengan: {:?}", e),
+        }
+        match storage.active_calibration_profiles() {
+            Ok(active) => {
+                for (sensor_id, profile_id) in active {
+                    let profile = sensor_config_screen.calibration_profiles.lock().unwrap().iter()
+                        .find(|profile| profile.id == profile_id && profile.sensor_id == sensor_id)
+                        .cloned();
+                    match profile {
+                        Some(profile) => sensor_config_screen.activate_profile(&profile),
+                        None => eprintln!("[DB Thread] Profil aktif {} untuk sensor {} tidak ditemukan.", profile_id, sensor_id),
+                    }
+                }
+            },
+            Err(e) => eprintln!("[DB Thread] GAGAL memuat profil kalibrasi aktif: {:?}", e),
+        }
         let initial_baud_rate = sensor_config_screen.baud_rate; 
         
         let status_tx_clone = status_tx.clone();
@@ -94,7 +167,7 @@ impl MyApp {
 
 
         thread::spawn(move || {
-            let port_name = "COM4"; 
+            let port_name = SENSOR_PORT; 
             let baud_rate = initial_baud_rate; 
 
             if status_tx_clone.send(format!("Mencoba membuka port: {}...", port_name)).is_err() { return; }
@@ -107,29 +180,34 @@ impl MyApp {
                     if status_tx_clone.send(format!("Terhubung ke: {} ({} bps)", port_name, baud_rate)).is_err() { return; }
                     let mut serial_buf: Vec<u8> = vec![0; 256]; 
                     let mut received_string = String::new();
+                    let mut device_clock = flicker::DeviceClock::new();
 
-                    loop {
+                    'read: loop {
                         match port.read(serial_buf.as_mut_slice()) {
                             Ok(bytes_read) => {
                                 if bytes_read > 0 {
                                     received_string.push_str(&String::from_utf8_lossy(&serial_buf[..bytes_read]));
                                     
-                                    if let Some(newline_pos) = received_string.find('\n') {
+                                    // Semua baris lengkap diproses sekaligus agar burst Arduino tidak tertahan di buffer.
+                                    while let Some(newline_pos) = received_string.find('\n') {
                                         let line = received_string.drain(..newline_pos + 1).collect::<String>();
                                         let trimmed_line = line.trim();
                                         
                                         let mut photodiode_value: Option<f64> = None;
+                                        let mut device_time: Option<f64> = None;
 
-                                        if let Ok(val) = trimmed_line.parse::<f64>() {
+                                        if let Some((micros, val)) = flicker::parse_serial_line(trimmed_line) {
                                             photodiode_value = Some(val);
+                                            device_time = micros.map(|micros| device_clock.seconds(micros));
                                             if status_tx_clone.send(format!("Nilai Photodiode Diterima: {:.2}", val)).is_err() { return; }
                                         } else {
                                             if status_tx_clone.send(format!("Parsing ERROR (Photodiode): '{}'", trimmed_line)).is_err() { return; }
                                         }
                                         
-                                        if photodiode_tx.send(Value { x: start_time.elapsed().as_secs_f64(), y: photodiode_value.unwrap_or(0.0) }).is_err() { 
+                                        let value = Value { x: start_time.elapsed().as_secs_f64(), y: photodiode_value.unwrap_or(0.0) };
+                                        if photodiode_tx.send((value, device_time)).is_err() { 
                                             if status_tx_clone.send("Channel photodiode ditutup.".to_string()).is_err() { return; }
-                                            break; 
+                                            break 'read; 
                                         }
                                     }
                                 }
@@ -140,7 +218,6 @@ impl MyApp {
                                 break; 
                             }
                         }
-                        thread::sleep(Duration::from_millis(10)); 
                     }
                 },
                 Err(e) => {
@@ -151,6 +228,23 @@ impl MyApp {
         });
 
 
+        let retention_policy = Arc::clone(&sensor_config_screen.retention_policy);
+        let storage_for_retention = Arc::clone(&storage);
+
+        thread::spawn(move || {
+            loop {
+                let policy = retention_policy.lock().unwrap().clone();
+                if let Err(e) = storage_for_retention.ensure_schema(&policy) {
+                    eprintln!("[Retensi] GAGAL membuat index: {:?}", e);
+                }
+                if let Err(e) = storage_for_retention.apply_retention_policy(&policy) {
+                    eprintln!("[Retensi] GAGAL menerapkan kebijakan retensi: {:?}", e);
+                }
+                thread::sleep(Duration::from_secs(60 * 60));
+            }
+        });
+
+
         let max_data_points = 300; 
 
         Self {
@@ -158,7 +252,14 @@ impl MyApp {
             newton_raphson_lux_measurements: shared_newton_raphson_lux_measurements,
             photodiode_data_receiver: photodiode_rx,
             current_photodiode_value: 0.0, 
+            current_lux: None,
+            current_ppfd: None,
             current_screen: AppScreen::Home, 
+            last_lux_by_sensor: HashMap::new(),
+            lux_lookup_table: None,
+            samples_since_nr_validation: 0,
+            dli_tracker: dli::DliTracker::new(),
+            started_at,
 
             home_screen: HomeScreen::new(),
             data_graphics_screen: DataGraphicsScreen::new(
@@ -167,9 +268,12 @@ impl MyApp {
                 max_data_points, 
                 app_event_tx_for_graphics
             ), 
-            database_screen: DatabaseScreen::new(), 
+            database_screen: DatabaseScreen::new(storage_backend), 
             sensor_configuration_screen: sensor_config_screen, 
+            summary_screen: SummaryScreen::new(),
+            diagnostics_screen: DiagnosticsScreen::new(),
             
+            storage,
             database_data: Arc::new(Mutex::new(Vec::new())),
             serial_status_message: "Menunggu koneksi serial...".to_string(), 
             serial_status_receiver: status_rx,
@@ -188,26 +292,14 @@ impl App for MyApp {
 
         while let Ok(event) = self.app_event_receiver.try_recv() {
             match event {
-                AppEvent::NewtonRaphsonCalculated { akar, history } => {
-                    self.sensor_configuration_screen.update_nr_display_data(akar, history.clone()); 
-                    
-                    let akar_for_db = akar; 
-                    
-                    std::thread::spawn(move || {
-                        let rt = tokio::runtime::Runtime::new().unwrap();
-                        rt.block_on(async {
-                            match db::connect_db().await {
-                                Ok(db) => {
-                                    let _ = db::insert_newton_raphson_result(&db, akar_for_db, Vec::new()).await; 
-                                    eprintln!("[DB Thread] Berhasil menyimpan hasil Newton-Raphson: {:.8}", akar_for_db);
-                                },
-                                Err(e) => {
-                                    eprintln!("[DB Thread] GAGAL terhubung/menyimpan hasil Newton-Raphson: {:?}", e);
-                                }
-                            }
-                        });
-                    });
+                AppEvent::NewtonRaphsonCalculated { result } => {
+                    let converged = result.is_converged();
+                    self.sensor_configuration_screen.update_nr_display_data(result); 
+                    if !converged {
+                        continue;
+                    }
 
+                    // Hasil sudah disimpan bersama pembacaan photodiode (lengkap dengan CI, PPFD dan tanda outlier).
                     if self.current_screen == AppScreen::Database {
                         self.fetch_database_data(ctx, DatabaseDataType::NewtonRaphsonResults); 
                     }
@@ -221,85 +313,161 @@ impl App for MyApp {
              self.fetch_database_data(ctx, self.database_screen.current_display_type.clone());
         }
 
-        while let Ok(new_value) = self.photodiode_data_receiver.try_recv() {
+        while let Ok((new_value, device_time)) = self.photodiode_data_receiver.try_recv() {
             self.measurements.lock().unwrap().add_value(new_value); 
-            self.current_photodiode_value = new_value.y; 
-
-            const ARDUINO_MAX_VOLTAGE: f64 = 3.3; 
-            const ARDUINO_MAX_PHOTODIODE_VALUE: f64 = 1000.0; 
-
-            let scale_factor_to_voltage = ARDUINO_MAX_VOLTAGE / ARDUINO_MAX_PHOTODIODE_VALUE;
-            let v_out_terukur = new_value.y * scale_factor_to_voltage; 
-            
-            let calib_a_power = self.sensor_configuration_screen.calib_a_power;
-            let calib_b_power = self.sensor_configuration_screen.calib_b_power;
-            
-            let f = |lux_estimate: f64| -> f64 {
-                if lux_estimate <= 0.0 { 
-                    return f64::MAX; 
-                }
-                calib_a_power * lux_estimate.powf(calib_b_power) - v_out_terukur
-            };
-
-            let f_prime = |lux_estimate: f64| -> f64 {
-                if lux_estimate <= 0.0 {
-                    return f64::MAX; 
-                }
-                calib_a_power * calib_b_power * lux_estimate.powf(calib_b_power - 1.0)
-            };
+            // Deteksi lonjakan memakai nilai mentah, sebelum filter meredamnya.
+            let outlier = self.sensor_configuration_screen.outlier_detector_for_mut(SENSOR_PORT).check(new_value.x, new_value.y);
+            if let Some(flag) = outlier {
+                eprintln!("[Outlier] Nilai photodiode {:.2} ditandai ({}).", new_value.y, flag.label());
+            }
+            // Nilai mentah tetap tersimpan; konversi lux memakai nilai setelah rantai filter sensor.
+            let filtered_value = self.sensor_configuration_screen.filter_for_mut(SENSOR_PORT).apply(new_value.y);
+            self.data_graphics_screen.add_filtered(Value { x: new_value.x, y: filtered_value });
+            self.current_photodiode_value = filtered_value; 
 
             let mut x0: f64 = self.sensor_configuration_screen.initial_guess_nr;
-            let tolerance: f64 = self.sensor_configuration_screen.tolerance_nr;
-            let max_iterations: usize = self.sensor_configuration_screen.max_iterations_nr as usize; 
-            
             if x0 <= 0.0 {
                 eprintln!("[Newton-Raphson] Tebakan awal Lux ({}) tidak valid. Menggunakan 1.0.", x0);
                 x0 = 1.0;
             }
 
-            let (lux_newton_raphson_result, history_newton_raphson_values) =
-                Measurements::newton_raphson(f, f_prime, x0, tolerance, max_iterations);
-
-            let final_lux_nr = if lux_newton_raphson_result.is_finite() && lux_newton_raphson_result >= 0.0 {
-                lux_newton_raphson_result
-            } else {
-                eprintln!("[Newton-Raphson] Hasil tidak valid: {}. Menggunakan 0.0 Lux.", lux_newton_raphson_result);
-                0.0
+            let solver_method = self.sensor_configuration_screen.solver_method;
+            let calibration = self.sensor_configuration_screen.calibration_for(SENSOR_PORT);
+            // Analisis flicker memakai lux sesaat dari nilai mentah agar filter tidak meredam modulasi lampu.
+            let instantaneous_lux = calibration.model().inverse(calibration.adc.to_voltage(new_value.y)).unwrap_or(0.0);
+            self.data_graphics_screen.add_flicker_sample(Value { x: device_time.unwrap_or(new_value.x), y: instantaneous_lux }, device_time.is_some());
+            let v_out_terukur = calibration.adc.to_voltage(filtered_value);
+            let mut lux_problem = LuxProblem {
+                model: calibration.model(),
+                v_out: v_out_terukur,
+                x0,
+                tol: self.sensor_configuration_screen.tolerance_nr,
+                max_iter: self.sensor_configuration_screen.max_iterations_nr as usize,
             };
+            self.sensor_configuration_screen.last_raw_value = Some(new_value.y);
+            self.sensor_configuration_screen.last_photodiode_value = Some(filtered_value);
+            self.sensor_configuration_screen.last_v_out = Some(v_out_terukur);
 
-            println!("Nilai Photodiode dari Arduino (Scaled 0-1000): {:.2}", new_value.y);
+            println!("Nilai Photodiode dari Arduino (0-{}): {:.2} (terfilter {:.2})", calibration.adc.max_value(), new_value.y, filtered_value);
             println!("Tegangan Output Terukur (V_out): {:.4} V", v_out_terukur);
-            println!("Lux dari Newton-Raphson (Validasi): {:.2} Lux", final_lux_nr);
 
-            self.newton_raphson_lux_measurements.lock().unwrap().add_value(
-                Value { x: new_value.x, y: final_lux_nr }
-            );
+            let conversion_mode = self.sensor_configuration_screen.conversion_mode;
+            let (final_lux, nr_history_for_display) = match conversion_mode {
+                ConversionMode::NewtonRaphson => {
+                    let solve_result = self.solve_lux_nr(&mut lux_problem, x0);
+                    let nr_history_for_display: Vec<f64> = solve_result.history.iter().map(|v| v.y).collect();
+                    let final_lux_nr = solve_result.is_converged().then_some(solve_result.root);
 
-            let nr_history_for_display: Vec<f64> = history_newton_raphson_values.into_iter().map(|v| v.y).collect();
-            
-            if self.app_event_sender.send(AppEvent::NewtonRaphsonCalculated { 
-                akar: final_lux_nr, 
-                history: nr_history_for_display 
-            }).is_err() {
-                eprintln!("Gagal mengirim AppEvent::NewtonRaphsonCalculated ke SensorConfigurationScreen.");
+                    match final_lux_nr {
+                        Some(lux) => {
+                            println!("Lux dari {} (Validasi): {:.2} Lux ({} iterasi, {} langkah bisection, perkiraan error {:.2e})",
+                                solver_method.label(), lux, solve_result.iterations, solve_result.bisection_steps, solve_result.estimated_error);
+                        },
+                        None => {
+                            eprintln!("[{}] GAGAL menghitung lux untuk V_out {:.4} V: {} setelah {} iterasi ({}).",
+                                solver_method.label(), v_out_terukur, solve_result.status, solve_result.iterations, calibration.describe());
+                        }
+                    }
+
+                    if self.app_event_sender.send(AppEvent::NewtonRaphsonCalculated { 
+                        result: solve_result,
+                    }).is_err() {
+                        eprintln!("Gagal mengirim AppEvent::NewtonRaphsonCalculated ke SensorConfigurationScreen.");
+                    }
+                    (final_lux_nr, nr_history_for_display)
+                },
+                ConversionMode::Analytic | ConversionMode::LookupTable => {
+                    let fast_lux = if conversion_mode == ConversionMode::Analytic {
+                        calibration.model().inverse(v_out_terukur)
+                    } else {
+                        self.lookup_table(&calibration).lookup(filtered_value)
+                    };
+                    match fast_lux {
+                        Some(lux) => println!("Lux dari {}: {:.2} Lux", conversion_mode.label(), lux),
+                        None => eprintln!("[{}] GAGAL menghitung lux untuk V_out {:.4} V ({}).",
+                            conversion_mode.label(), v_out_terukur, calibration.describe()),
+                    }
+
+                    // NR tetap dijalankan sesekali sebagai pembanding hasil cepat.
+                    let interval = self.sensor_configuration_screen.nr_validation_interval;
+                    self.samples_since_nr_validation += 1;
+                    if interval > 0 && self.samples_since_nr_validation >= interval {
+                        self.samples_since_nr_validation = 0;
+                        let solve_result = self.solve_lux_nr(&mut lux_problem, x0);
+                        let status = match (fast_lux, solve_result.is_converged()) {
+                            (Some(lux), true) => {
+                                let discrepancy = (lux - solve_result.root).abs();
+                                if discrepancy > 0.01 * solve_result.root.abs().max(1.0) {
+                                    eprintln!("[Validasi NR] Selisih {} vs NR terlalu besar: {:.4} Lux vs {:.4} Lux.",
+                                        conversion_mode.label(), lux, solve_result.root);
+                                }
+                                format!("Validasi NR terakhir: {:.4} Lux vs NR {:.4} Lux (selisih {:.3e} Lux)", lux, solve_result.root, discrepancy)
+                            },
+                            (None, _) => format!("Validasi NR terakhir: {} gagal menghitung lux", conversion_mode.label()),
+                            (Some(_), false) => format!("Validasi NR terakhir: NR gagal ({})", solve_result.status),
+                        };
+                        self.sensor_configuration_screen.nr_validation_status = status;
+                    }
+                    (fast_lux, Vec::new())
+                }
+            };
+
+            let lux_uncertainty = final_lux.and_then(|lux| uncertainty::propagate(&calibration, filtered_value, lux));
+            let confidence_interval = lux_uncertainty.map(|u| u.interval());
+            self.sensor_configuration_screen.latest_uncertainty = lux_uncertainty;
+            let ppfd = final_lux.map(|lux| self.sensor_configuration_screen.ppfd_for(SENSOR_PORT).lux_to_ppfd(lux));
+            self.current_lux = final_lux;
+            self.current_ppfd = ppfd;
+
+            if let Some(lux) = final_lux {
+                self.newton_raphson_lux_measurements.lock().unwrap().add_value(
+                    Value { x: new_value.x, y: lux }
+                );
+                self.data_graphics_screen.add_confidence_interval(new_value.x, confidence_interval);
+            }
+            if outlier.is_some() {
+                self.data_graphics_screen.add_outlier(new_value, final_lux.map(|lux| Value { x: new_value.x, y: lux }));
+            }
+            if let Some(ppfd) = ppfd {
+                self.data_graphics_screen.add_ppfd(Value { x: new_value.x, y: ppfd });
+
+                let dli_settings = &self.data_graphics_screen.dli_settings;
+                let feeds_dli = outlier.is_none() || dli_settings.include_outliers;
+                let completed = if feeds_dli { self.dli_tracker.add(SENSOR_PORT, chrono::Utc::now(), ppfd, dli_settings) } else { None };
+                if let Some(completed) = completed {
+                    println!("DLI {} selesai: {:.2} mol/m²/hari (cakupan {:.1} jam)", completed.date, completed.dli, completed.covered_hours);
+                    self.data_graphics_screen.merge_daily_light_integrals(vec![completed.clone()]);
+                    let storage = Arc::clone(&self.storage);
+                    std::thread::spawn(move || {
+                        if let Err(e) = storage.save_daily_light_integral(&completed) {
+                            eprintln!("[DB Thread] Gagal menyimpan DLI harian: {:?}", e);
+                        }
+                    });
+                }
+                self.data_graphics_screen.running_dli = self.dli_tracker.running(SENSOR_PORT);
             }
 
             let photodiode_value_for_db = new_value.y; 
+            let calibration_profile = self.sensor_configuration_screen.active_profile_for(SENSOR_PORT);
+            let storage = Arc::clone(&self.storage);
 
             std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                    if let Ok(db) = db::connect_db().await {
-                        let _ = db::insert_photodiode_data(&db, photodiode_value_for_db).await; 
-                        let _ = db::insert_newton_raphson_result(&db, final_lux_nr, Vec::new()).await; 
-                    } else {
-                         eprintln!("[DB Thread] Gagal terhubung ke database untuk menyimpan data photodiode/NR.");
-                    }
-                });
+                let mut saved = storage.insert_reading(SENSOR_PORT, photodiode_value_for_db, outlier);
+                if let Some(lux) = final_lux {
+                    let metadata = ResultMetadata { calibration_profile, confidence_interval, ppfd, outlier };
+                    saved = saved.and_then(|_| storage.insert_result(SENSOR_PORT, lux, nr_history_for_display, &metadata));
+                }
+                if let Err(e) = saved {
+                    eprintln!("[DB Thread] Gagal menyimpan data photodiode/NR: {:?}", e);
+                }
             });
 
         }
 
+        if std::mem::take(&mut self.diagnostics_screen.run_request) || self.diagnostics_screen.auto_run_due() {
+            self.run_diagnostics();
+        }
+
         TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.add_space(10.0);
             ui.vertical_centered(|ui| {
@@ -338,6 +506,14 @@ impl App for MyApp {
                     if ui.button(RichText::new("⚙️ Sensor Configuration").size(18.0).color(Color32::WHITE)).clicked() {
                         self.current_screen = AppScreen::SensorConfiguration;
                     }
+                    ui.add_space(10.0);
+                    if ui.button(RichText::new("📅 Ringkasan Harian").size(18.0).color(Color32::WHITE)).clicked() {
+                        self.current_screen = AppScreen::Summary;
+                    }
+                    ui.add_space(10.0);
+                    if ui.button(RichText::new("🩺 Diagnostik Sensor").size(18.0).color(Color32::WHITE)).clicked() {
+                        self.current_screen = AppScreen::Diagnostics;
+                    }
                 });
             });
 
@@ -349,13 +525,304 @@ impl App for MyApp {
             .show(ctx, |ui| {
                 ui.add_space(10.0);
                 match self.current_screen {
-                    AppScreen::Home => self.home_screen.show(ui, self.current_photodiode_value),
-                    AppScreen::DataGraphics => self.data_graphics_screen.show(ui), 
+                    AppScreen::Home => {
+                        let adc = self.sensor_configuration_screen.calibration_for(SENSOR_PORT).adc;
+                        let conditions = LightConditions {
+                            lux: self.current_lux,
+                            ppfd: self.current_ppfd,
+                            light_source: self.sensor_configuration_screen.ppfd_for(SENSOR_PORT).source,
+                            dli_today: self.data_graphics_screen.running_dli.as_ref().map(|running| running.dli),
+                            dli_last_complete: self.data_graphics_screen.daily_light_integrals.lock().unwrap().iter()
+                                .rev()
+                                .find(|total| total.complete && total.sensor_id == SENSOR_PORT)
+                                .map(|total| total.dli),
+                            photoperiod_hours: self.summary_screen.latest_photoperiod(),
+                        };
+                        let beds = self.sensor_configuration_screen.beds.lock().unwrap().clone();
+                        let health = self.diagnostics_screen.reports.lock().unwrap().clone();
+                        self.home_screen.show(ui, self.current_photodiode_value, &adc, &conditions, &beds, &health);
+                    },
+                    AppScreen::DataGraphics => {
+                        self.data_graphics_screen.show(ui);
+
+                        if std::mem::take(&mut self.data_graphics_screen.history_request) {
+                            let storage = Arc::clone(&self.storage);
+                            let history = Arc::clone(&self.data_graphics_screen.history);
+                            let kind = self.data_graphics_screen.history_kind;
+                            let from = chrono::Utc::now() - chrono::Duration::days(self.data_graphics_screen.history_days as i64);
+                            std::thread::spawn(move || {
+                                match storage.query_range(kind, None, Some(from), None) {
+                                    Ok(records) => *history.lock().unwrap() = records,
+                                    Err(e) => eprintln!("[DB Thread] GAGAL memuat riwayat data: {:?}", e),
+                                }
+                            });
+                        }
+
+                        let dli_settings = self.data_graphics_screen.dli_settings.clone();
+                        let dli_from = dli_settings.local_date(chrono::Utc::now()) - chrono::Duration::days(self.data_graphics_screen.dli_days as i64 - 1);
+                        if std::mem::take(&mut self.data_graphics_screen.dli_load_request) {
+                            let storage = Arc::clone(&self.storage);
+                            let daily = Arc::clone(&self.data_graphics_screen.daily_light_integrals);
+                            let status = Arc::clone(&self.data_graphics_screen.dli_status);
+                            std::thread::spawn(move || {
+                                let message = match storage.list_daily_light_integrals(Some(SENSOR_PORT), Some(dli_from)) {
+                                    Ok(totals) => {
+                                        let message = format!("{} total DLI harian dimuat sejak {}.", totals.len(), dli_from);
+                                        *daily.lock().unwrap() = totals;
+                                        message
+                                    },
+                                    Err(e) => format!("GAGAL memuat DLI harian: {}", e),
+                                };
+                                *status.lock().unwrap() = message;
+                            });
+                        }
+
+                        if std::mem::take(&mut self.data_graphics_screen.dli_recompute_request) {
+                            let storage = Arc::clone(&self.storage);
+                            let daily = Arc::clone(&self.data_graphics_screen.daily_light_integrals);
+                            let status = Arc::clone(&self.data_graphics_screen.dli_status);
+                            let fallback_factor = self.sensor_configuration_screen.ppfd_for(SENSOR_PORT).factor();
+                            *status.lock().unwrap() = "Menghitung DLI dari riwayat...".to_string();
+                            std::thread::spawn(move || {
+                                let from = dli_from.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(dli_settings.utc_offset).unwrap().with_timezone(&chrono::Utc);
+                                let message = match storage.query_range(SeriesKind::NewtonRaphson, Some(SENSOR_PORT), Some(from), None) {
+                                    Ok(records) => {
+                                        let samples = dli::ppfd_samples(&records, fallback_factor, dli_settings.include_outliers);
+                                        let totals = dli::daily_totals(SENSOR_PORT, &samples, &dli_settings, chrono::Utc::now());
+                                        let saved = totals.iter()
+                                            .filter(|total| total.complete)
+                                            .map(|total| storage.save_daily_light_integral(total))
+                                            .collect::<Result<Vec<_>, _>>();
+                                        let message = match saved {
+                                            Ok(saved) => format!("DLI dihitung ulang dari {} sampel PPFD: {} hari selesai disimpan.", samples.len(), saved.len()),
+                                            Err(e) => format!("DLI dihitung, tetapi GAGAL disimpan: {}", e),
+                                        };
+                                        *daily.lock().unwrap() = totals.into_iter().filter(|total| total.complete).collect();
+                                        message
+                                    },
+                                    Err(e) => format!("GAGAL memuat riwayat untuk DLI: {}", e),
+                                };
+                                *status.lock().unwrap() = message;
+                            });
+                        }
+
+                        if std::mem::take(&mut self.data_graphics_screen.forecast_request) {
+                            let storage = Arc::clone(&self.storage);
+                            let forecast_result = Arc::clone(&self.data_graphics_screen.forecast);
+                            let projection_result = Arc::clone(&self.data_graphics_screen.dli_projection);
+                            let status = Arc::clone(&self.data_graphics_screen.forecast_status);
+                            let settings = self.data_graphics_screen.forecast_settings.clone();
+                            let dli_settings = self.data_graphics_screen.dli_settings.clone();
+                            let running_dli = self.data_graphics_screen.running_dli.clone();
+                            let ppfd_factor = self.sensor_configuration_screen.ppfd_for(SENSOR_PORT).factor();
+                            *status.lock().unwrap() = "Menyusun prakiraan lux...".to_string();
+                            std::thread::spawn(move || {
+                                let now = chrono::Utc::now();
+                                let bucket = chrono::Duration::minutes(settings.bucket_minutes as i64);
+                                let from = now - chrono::Duration::days(settings.history_days as i64);
+                                let message = match storage.aggregate(SeriesKind::NewtonRaphson, Some(SENSOR_PORT), Some(from), None, bucket) {
+                                    Ok(buckets) => {
+                                        let history: Vec<(chrono::DateTime<chrono::Utc>, f64)> = buckets.iter()
+                                            .map(|record| (record.bucket_start + bucket / 2, record.avg))
+                                            .collect();
+                                        match forecast::forecast(&history, &settings, &dli_settings, now) {
+                                            Ok(result) => {
+                                                // DLI live lebih rapat dari agregat; agregat dipakai bila aplikasi baru dibuka hari ini.
+                                                let accumulated = running_dli
+                                                    .filter(|running| running.date == dli_settings.local_date(now))
+                                                    .map(|running| running.dli)
+                                                    .unwrap_or_else(|| forecast::accumulated_from_history(&history, ppfd_factor, &settings, &dli_settings, now));
+                                                let projection = forecast::project_dli(&result, accumulated, ppfd_factor, &settings, &dli_settings, now);
+                                                let message = format!("Prakiraan {} titik dari {} bucket riwayat.", result.points.len(), result.observation_count);
+                                                *forecast_result.lock().unwrap() = Some(result);
+                                                *projection_result.lock().unwrap() = Some(projection);
+                                                message
+                                            },
+                                            Err(e) => format!("GAGAL menyusun prakiraan: {}", e),
+                                        }
+                                    },
+                                    Err(e) => format!("GAGAL memuat riwayat untuk prakiraan: {}", e),
+                                };
+                                *status.lock().unwrap() = message;
+                            });
+                        }
+                    },
                     AppScreen::Database => {
                         let data = self.database_data.lock().unwrap();
-                        self.database_screen.show(ui, &data) 
+                        self.database_screen.show(ui, &data);
+                        drop(data);
+
+                        if let Some((path, mut options)) = self.database_screen.export_request.take() {
+                            options.adc_by_sensor = self.sensor_configuration_screen.calibration_by_sensor.iter()
+                                .map(|(sensor_id, settings)| (sensor_id.clone(), settings.adc.clone()))
+                                .collect();
+                            let storage = Arc::clone(&self.storage);
+                            let export_status = Arc::clone(&self.database_screen.export_status);
+                            std::thread::spawn(move || {
+                                let message = match export::export_to_path(storage.as_ref(), &path, &options) {
+                                    Ok(count) => format!("{} baris diekspor ke {}", count, path.display()),
+                                    Err(e) => format!("GAGAL mengekspor data: {}", e),
+                                };
+                                *export_status.lock().unwrap() = message;
+                            });
+                        }
+
+                        if let Some((path, settings, sensor_id, source)) = self.database_screen.import_request.take() {
+                            let storage = Arc::clone(&self.storage);
+                            let import_status = Arc::clone(&self.database_screen.import_status);
+                            let database_data = Arc::clone(&self.database_data);
+                            std::thread::spawn(move || {
+                                let parsed = match csv_import::read_csv(&path, settings.delimiter, settings.has_header, None) {
+                                    Ok(parsed) => parsed,
+                                    Err(e) => {
+                                        *import_status.lock().unwrap() = e;
+                                        return;
+                                    }
+                                };
+                                let mut rows = Vec::new();
+                                let mut invalid = Vec::new();
+                                for row in csv_import::validate(&parsed, &settings) {
+                                    match row.result {
+                                        Ok(import_row) => rows.push(import_row),
+                                        Err(e) => invalid.push(format!("baris {}: {}", row.line, e)),
+                                    }
+                                }
+                                let message = match storage.insert_imported(&sensor_id, &source, &rows) {
+                                    Ok(count) => {
+                                        // Dikosongkan agar tabel dimuat ulang bersama data impor.
+                                        database_data.lock().unwrap().clear();
+                                        let mut message = format!("{} dokumen dari '{}' tersimpan untuk sensor {}", count, source, sensor_id);
+                                        if let Some(first) = invalid.first() {
+                                            message += &format!("; {} baris tidak valid dilewati (pertama {})", invalid.len(), first);
+                                        }
+                                        message
+                                    },
+                                    Err(e) => format!("GAGAL mengimpor data: {}", e),
+                                };
+                                *import_status.lock().unwrap() = message;
+                            });
+                        }
+                    },
+                    AppScreen::Summary => {
+                        let started_at = self.started_at;
+                        self.summary_screen.live_samples = self.newton_raphson_lux_measurements.lock().unwrap().values.iter()
+                            .map(|value| photoperiod::LuxSample::new(started_at + chrono::Duration::milliseconds((value.x * 1000.0) as i64), value.y))
+                            .collect();
+                        self.summary_screen.show(ui);
+
+                        if std::mem::take(&mut self.summary_screen.summary_request) {
+                            let storage = Arc::clone(&self.storage);
+                            let samples = Arc::clone(&self.summary_screen.stored_samples);
+                            let status = Arc::clone(&self.summary_screen.status);
+                            let offset = self.summary_screen.settings.utc_offset;
+                            let first_day = chrono::Utc::now().with_timezone(&offset).date_naive() - chrono::Duration::days(self.summary_screen.summary_days as i64 - 1);
+                            let from = first_day.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(offset).unwrap().with_timezone(&chrono::Utc);
+                            let bucket = chrono::Duration::minutes(1);
+                            *status.lock().unwrap() = "Memuat agregat lux...".to_string();
+                            std::thread::spawn(move || {
+                                let message = match storage.aggregate(SeriesKind::NewtonRaphson, Some(SENSOR_PORT), Some(from), None, bucket) {
+                                    Ok(buckets) => {
+                                        let message = format!("{} menit data lux dimuat sejak {}.", buckets.len(), first_day);
+                                        // Titik tengah bucket mewakili rata-rata; puncak memakai maksimum bucket.
+                                        *samples.lock().unwrap() = buckets.into_iter()
+                                            .map(|bucket_record| photoperiod::LuxSample {
+                                                timestamp: bucket_record.bucket_start + bucket / 2,
+                                                lux: bucket_record.avg,
+                                                peak_lux: bucket_record.max,
+                                            })
+                                            .collect();
+                                        message
+                                    },
+                                    Err(e) => format!("GAGAL memuat agregat lux: {}", e),
+                                };
+                                *status.lock().unwrap() = message;
+                            });
+                        }
+                    },
+                    AppScreen::Diagnostics => {
+                        self.diagnostics_screen.show(ui);
+                    },
+                    AppScreen::SensorConfiguration => {
+                        self.sensor_configuration_screen.show(ui);
+
+                        if let Some(profile) = self.sensor_configuration_screen.profile_save_request.take() {
+                            let storage = Arc::clone(&self.storage);
+                            let profiles = Arc::clone(&self.sensor_configuration_screen.calibration_profiles);
+                            let profile_status = Arc::clone(&self.sensor_configuration_screen.profile_status);
+                            std::thread::spawn(move || {
+                                let saved = storage.save_calibration_profile(&profile)
+                                    .and_then(|_| storage.record_profile_activation(&profile.sensor_id, &profile.id));
+                                let message = match saved {
+                                    Ok(()) => {
+                                        let message = format!("Profil {} tersimpan dan aktif", profile.id);
+                                        let mut profiles = profiles.lock().unwrap();
+                                        profiles.push(profile);
+                                        storage::sort_profiles(&mut profiles);
+                                        message
+                                    },
+                                    Err(e) => format!("GAGAL menyimpan profil {}: {}", profile.id, e),
+                                };
+                                *profile_status.lock().unwrap() = message;
+                            });
+                        }
+
+                        if let Some(bed) = self.sensor_configuration_screen.bed_save_request.take() {
+                            let storage = Arc::clone(&self.storage);
+                            let beds = Arc::clone(&self.sensor_configuration_screen.beds);
+                            let bed_status = Arc::clone(&self.sensor_configuration_screen.bed_status);
+                            std::thread::spawn(move || {
+                                let message = match storage.save_bed(&bed) {
+                                    Ok(()) => {
+                                        let message = format!("Bedengan {} tersimpan (tanam {})", bed.id, bed.planting_date);
+                                        let mut beds = beds.lock().unwrap();
+                                        beds.retain(|existing| existing.id != bed.id);
+                                        beds.push(bed);
+                                        beds.sort_by(|a, b| a.id.cmp(&b.id));
+                                        message
+                                    },
+                                    Err(e) => format!("GAGAL menyimpan bedengan {}: {}", bed.id, e),
+                                };
+                                *bed_status.lock().unwrap() = message;
+                            });
+                        }
+
+                        if let Some(request) = self.sensor_configuration_screen.cross_validation_request.take() {
+                            let result = Arc::clone(&self.sensor_configuration_screen.cross_validation);
+                            let status = Arc::clone(&self.sensor_configuration_screen.cross_validation_status);
+                            std::thread::spawn(move || {
+                                let started = Instant::now();
+                                let validation = request.run();
+                                *status.lock().unwrap() = format!("Validasi silang selesai dalam {:.1} detik.", started.elapsed().as_secs_f64());
+                                *result.lock().unwrap() = Some((request.method, validation));
+                            });
+                        }
+
+                        if let Some(request) = self.sensor_configuration_screen.recompute_request.take() {
+                            let storage = Arc::clone(&self.storage);
+                            let progress = Arc::clone(&self.sensor_configuration_screen.recompute_progress);
+                            progress.lock().unwrap().running = true;
+                            std::thread::spawn(move || {
+                                if let Err(e) = recompute::run_recompute(storage.as_ref(), &request, &progress) {
+                                    eprintln!("[DB Thread] GAGAL menghitung ulang lux: {:?}", e);
+                                    let mut progress = progress.lock().unwrap();
+                                    progress.running = false;
+                                    progress.message = format!("GAGAL menghitung ulang lux: {}", e);
+                                }
+                            });
+                        }
+
+                        if let Some((sensor_id, profile_id)) = self.sensor_configuration_screen.profile_activation_request.take() {
+                            let storage = Arc::clone(&self.storage);
+                            let profile_status = Arc::clone(&self.sensor_configuration_screen.profile_status);
+                            std::thread::spawn(move || {
+                                let message = match storage.record_profile_activation(&sensor_id, &profile_id) {
+                                    Ok(()) => format!("Profil {} aktif untuk sensor {}", profile_id, sensor_id),
+                                    Err(e) => format!("GAGAL mencatat aktivasi profil {}: {}", profile_id, e),
+                                };
+                                *profile_status.lock().unwrap() = message;
+                            });
+                        }
                     },
-                    AppScreen::SensorConfiguration => self.sensor_configuration_screen.show(ui),
                 }
 
                 ui.add_space(10.0); 
@@ -385,6 +852,111 @@ impl App for MyApp {
 }
 
 impl MyApp {
+    // Menjalankan solver terpilih, dengan warm start dari lux terakhir sensor bila aktif.
+    fn solve_lux_nr(&mut self, lux_problem: &mut LuxProblem, x0: f64) -> SolveResult {
+        let solver_method = self.sensor_configuration_screen.solver_method;
+
+        // Pembacaan berurutan hampir sama, jadi lux terakhir biasanya tebakan awal yang jauh lebih dekat.
+        let warm_start_lux = self.last_lux_by_sensor.get(SENSOR_PORT).copied()
+            .filter(|lux| *lux > 0.0 && self.sensor_configuration_screen.warm_start_enabled);
+        let solve_result = match warm_start_lux {
+            Some(lux) => {
+                lux_problem.x0 = lux;
+                let warm_result = lux_problem.solve(solver_method);
+                if warm_result.is_converged() {
+                    self.sensor_configuration_screen.iteration_stats.record(true, warm_result.iterations);
+                    warm_result
+                } else {
+                    eprintln!("[Newton-Raphson] Warm start dari {:.2} Lux gagal ({}). Mengulang dari tebakan awal {}.",
+                        lux, warm_result.status, x0);
+                    lux_problem.x0 = x0;
+                    let cold_result = lux_problem.solve(solver_method);
+                    self.sensor_configuration_screen.iteration_stats
+                        .record(true, warm_result.iterations + cold_result.iterations);
+                    cold_result
+                }
+            },
+            None => {
+                let cold_result = lux_problem.solve(solver_method);
+                self.sensor_configuration_screen.iteration_stats.record(false, cold_result.iterations);
+                cold_result
+            }
+        };
+        if solve_result.is_converged() {
+            self.last_lux_by_sensor.insert(SENSOR_PORT.to_string(), solve_result.root);
+        }
+        solve_result
+    }
+
+    // LUT dibangun ulang hanya saat model atau parameter kalibrasi berubah.
+    fn lookup_table(&mut self, settings: &CalibrationSettings) -> &LuxLookupTable {
+        if !self.lux_lookup_table.as_ref().is_some_and(|table| table.matches(settings)) {
+            self.lux_lookup_table = Some(LuxLookupTable::new(settings));
+        }
+        self.lux_lookup_table.as_ref().unwrap()
+    }
+
+    // Setiap sensor dianalisis dari agregat photodiode per menit; drift dihitung bila sensor acuan dipilih.
+    fn run_diagnostics(&mut self) {
+        let mut sensors: Vec<String> = std::iter::once(SENSOR_PORT.to_string())
+            .chain(self.sensor_configuration_screen.calibration_by_sensor.keys().cloned())
+            .chain(self.sensor_configuration_screen.beds.lock().unwrap().iter().map(|bed| bed.sensor_id.clone()))
+            .collect();
+        sensors.sort();
+        sensors.dedup();
+        self.diagnostics_screen.known_sensors = sensors.clone();
+        self.diagnostics_screen.last_run = Some(Instant::now());
+
+        let settings = self.diagnostics_screen.settings.clone();
+        let adc_by_sensor: HashMap<String, AdcSettings> = sensors.iter()
+            .map(|sensor_id| (sensor_id.clone(), self.sensor_configuration_screen.calibration_for(sensor_id).adc))
+            .collect();
+        let storage = Arc::clone(&self.storage);
+        let reports = Arc::clone(&self.diagnostics_screen.reports);
+        let status = Arc::clone(&self.diagnostics_screen.status);
+        *status.lock().unwrap() = "Memeriksa kesehatan sensor...".to_string();
+
+        std::thread::spawn(move || {
+            let now = chrono::Utc::now();
+            let from = now - chrono::Duration::seconds((settings.window_hours * 3600.0) as i64);
+            let bucket = chrono::Duration::minutes(1);
+            let mut buckets_by_sensor = HashMap::new();
+            for sensor_id in &sensors {
+                match storage.aggregate(SeriesKind::Photodiode, Some(sensor_id), Some(from), None, bucket) {
+                    Ok(buckets) => { buckets_by_sensor.insert(sensor_id.clone(), buckets); },
+                    Err(e) => {
+                        *status.lock().unwrap() = format!("GAGAL memuat data sensor {}: {}", sensor_id, e);
+                        return;
+                    },
+                }
+            }
+
+            let mut results = Vec::new();
+            for sensor_id in &sensors {
+                let adc = &adc_by_sensor[sensor_id];
+                let buckets = &buckets_by_sensor[sensor_id];
+                let mut health = diagnostics::analyse(sensor_id, buckets, bucket, adc, &settings, now);
+                if let Some(reference) = settings.reference_sensor.as_ref().filter(|reference| *reference != sensor_id) {
+                    if let (Some(reference_buckets), Some(reference_adc)) = (buckets_by_sensor.get(reference), adc_by_sensor.get(reference)) {
+                        if let Some(drift) = diagnostics::estimate_drift(buckets, adc, reference, reference_buckets, reference_adc, &settings) {
+                            health.apply_drift(drift, &settings);
+                        }
+                    }
+                }
+                results.push(health);
+            }
+
+            let unhealthy = results.iter().filter(|health| !health.is_healthy()).count();
+            for health in results.iter().filter(|health| !health.is_healthy()) {
+                for warning in health.active_warnings() {
+                    eprintln!("[Diagnostik] Sensor {}: {} ({})", health.sensor_id, warning.issue.label(), warning.detail);
+                }
+            }
+            *status.lock().unwrap() = format!("{} sensor diperiksa, {} perlu diperiksa.", results.len(), unhealthy);
+            *reports.lock().unwrap() = results;
+        });
+    }
+
     fn fetch_database_data(&mut self, ctx: &Context, data_type: DatabaseDataType) {
         let database_data_arc = Arc::clone(&self.database_data);
         {
@@ -393,43 +965,51 @@ impl MyApp {
         }
         ctx.request_repaint(); 
 
+        let storage = Arc::clone(&self.storage);
+
         std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let docs = rt.block_on(async {
-                match db::connect_db().await {
-                    Ok(db_conn) => {
-                        eprintln!("[DB Thread] Berhasil terhubung ke database untuk fetch data.");
-                        match data_type {
-                            DatabaseDataType::PhotodiodeData => {
-                                eprintln!("[DB Thread] Fetching photodiode data...");
-                                db::get_all_photodiode_data(&db_conn).await.unwrap_or_else(|e| {
-                                    eprintln!("[DB Thread] GAGAL mengambil data photodiode: {:?}", e); 
-                                    Vec::new() 
-                                })
-                            },
-                            DatabaseDataType::NewtonRaphsonResults => {
-                                eprintln!("[DB Thread] Fetching Newton-Raphson results...");
-                                db::get_all_newton_raphson_results(&db_conn).await.unwrap_or_else(|e| {
-                                    eprintln!("[DB Thread] GAGAL mengambil hasil Newton-Raphson: {:?}", e); 
-                                    Vec::new() 
-                                })
-                            },
-                        }
-                    },
-                    Err(e) => {
-                        eprintln!("[DB Thread] GAGAL terhubung ke database untuk fetch data: {:?}", e); 
-                        Vec::new()
-                    }
-                }
+            let kind = match data_type {
+                DatabaseDataType::PhotodiodeData => {
+                    eprintln!("[DB Thread] Fetching photodiode data...");
+                    SeriesKind::Photodiode
+                },
+                DatabaseDataType::NewtonRaphsonResults => {
+                    eprintln!("[DB Thread] Fetching Newton-Raphson results...");
+                    SeriesKind::NewtonRaphson
+                },
+            };
+            let records = storage.query_range(kind, None, None, None).unwrap_or_else(|e| {
+                eprintln!("[DB Thread] GAGAL mengambil data dari database: {:?}", e); 
+                Vec::new() 
             });
             let mut data = database_data_arc.lock().unwrap();
-            *data = docs;
+            *data = records;
         });
     }
 }
 
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(exit_code) = cli::run(&args) {
+        std::process::exit(exit_code);
+    }
+
+    let storage_backend = match StorageBackend::from_args(&args) {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let storage = match storage::open_storage(&storage_backend) {
+        Ok(storage) => storage,
+        Err(e) => {
+            eprintln!("GAGAL membuka penyimpanan {}: {:?}", storage_backend.label(), e);
+            std::process::exit(1);
+        }
+    };
+
     let native_options = NativeOptions {
         viewport: ViewportBuilder::default()
             .with_inner_size([900.0, 700.0]) 
@@ -442,7 +1022,7 @@ fn main() -> eframe::Result<()> {
         "Sistem Pemantauan Intensitas Cahaya Tanaman Selada (Lactuca sativa)", 
         native_options,
         Box::new(|cc| {
-            Ok(Box::new(MyApp::new(cc)))
+            Ok(Box::new(MyApp::new(cc, storage, storage_backend)))
         }),
     )
 }
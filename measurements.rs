@@ -1,10 +1,78 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveStatus {
+    Converged,
+    MaxIterations,
+    NoBracket,
+    InvalidFunction,
+    ZeroDerivative,
+}
+
+impl std::fmt::Display for SolveStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            SolveStatus::Converged => "Konvergen",
+            SolveStatus::MaxIterations => "Iterasi maksimum tercapai sebelum konvergen",
+            SolveStatus::NoBracket => "Akar tidak terapit (f(a) dan f(b) bertanda sama)",
+            SolveStatus::InvalidFunction => "Fungsi menghasilkan nilai tidak valid (NaN/tak hingga)",
+            SolveStatus::ZeroDerivative => "Turunan atau kemiringan mendekati nol",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SolveResult {
+    pub root: f64,
+    pub status: SolveStatus,
+    pub history: Vec<Value>,
+    // f(x_n) untuk setiap entri `history`.
+    pub residuals: Vec<f64>,
+    pub iterations: usize,
+    // Perkiraan error akar: |x_n - x_(n-1)| pada iterasi terakhir.
+    pub estimated_error: f64,
+    // Langkah yang diganti bisection oleh pengaman (Newton terjaga, Brent).
+    pub bisection_steps: usize,
+}
+
+impl SolveResult {
+    pub fn new() -> Self {
+        Self {
+            root: f64::NAN,
+            status: SolveStatus::InvalidFunction,
+            history: Vec::new(),
+            residuals: Vec::new(),
+            iterations: 0,
+            estimated_error: f64::NAN,
+            bisection_steps: 0,
+        }
+    }
+
+    // Melengkapi residual dan perkiraan error dari riwayat iterasi.
+    pub fn finish<F>(mut self, f: F) -> Self
+    where
+        F: Fn(f64) -> f64,
+    {
+        self.residuals = self.history.iter().map(|value| f(value.y)).collect();
+        self.estimated_error = match self.history.as_slice() {
+            [.., before, last] => (last.y - before.y).abs(),
+            [_] if self.is_converged() => 0.0,
+            _ => f64::NAN,
+        };
+        self
+    }
+
+    pub fn is_converged(&self) -> bool {
+        self.status == SolveStatus::Converged
+    }
+}
+
 #[derive(Clone)]
 pub struct Measurements {
     pub values: Vec<Value>,
     pub max_data_points: usize,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Value {
     pub x: f64,
     pub y: f64,
@@ -18,39 +86,145 @@ impl Measurements {
         }
     }
 
-    pub fn newton_raphson<F, Fp>(f: F, fp: Fp, x0: f64, tol: f64, max_iter: usize) -> (f64, Vec<Value>)
+    pub fn newton_raphson<F, Fp>(f: F, fp: Fp, x0: f64, tol: f64, max_iter: usize) -> SolveResult
     where
         F: Fn(f64) -> f64,
         Fp: Fn(f64) -> f64,
     {
+        let mut result = SolveResult::new();
         let mut x = x0;
-        let mut results_history = Vec::new(); 
-        results_history.push(Value { x: 0.0, y: x0 }); 
+        result.history.push(Value { x: 0.0, y: x0 });
+        result.status = SolveStatus::MaxIterations;
 
         for i in 0..max_iter {
             let fx = f(x);
             let fpx = fp(x);
 
+            if !fx.is_finite() || !fpx.is_finite() {
+                result.status = SolveStatus::InvalidFunction;
+                break;
+            }
             if fpx.abs() < 1e-12 {
-                println!("Newton-Raphson: Turunan mendekati nol pada iterasi {}. Menghentikan.", i);
+                result.status = SolveStatus::ZeroDerivative;
                 break;
             }
 
             let x_new = x - fx / fpx;
-            results_history.push(Value { x: (i + 1) as f64, y: x_new }); 
+            result.iterations += 1;
+            result.history.push(Value { x: (i + 1) as f64, y: x_new });
 
             if (x_new - x).abs() < tol {
-                println!("Newton-Raphson: Konvergensi tercapai pada iterasi {}. x = {:.8}", i + 1, x_new);
                 x = x_new;
+                result.status = SolveStatus::Converged;
                 break;
             }
             x = x_new;
+        }
 
-            if i == max_iter - 1 {
-                println!("Newton-Raphson: Iterasi maksimum tercapai. Hasil terakhir: x = {:.8}", x);
+        result.root = x;
+        result.finish(f)
+    }
+    
+    // Newton-Raphson yang dijaga oleh interval [lower, upper] yang mengapit akar: langkah Newton yang keluar
+    // dari interval atau tidak cukup memperkecilnya diganti langkah bisection, sehingga selalu konvergen.
+    pub fn safeguarded_newton<F, Fp>(f: F, fp: Fp, lower: f64, upper: f64, x0: f64, tol: f64, max_iter: usize) -> SolveResult
+    where
+        F: Fn(f64) -> f64,
+        Fp: Fn(f64) -> f64,
+    {
+        Self::safeguarded_newton_steps(&f, fp, lower, upper, x0, tol, max_iter).finish(f)
+    }
+
+    fn safeguarded_newton_steps<F, Fp>(f: F, fp: Fp, lower: f64, upper: f64, x0: f64, tol: f64, max_iter: usize) -> SolveResult
+    where
+        F: Fn(f64) -> f64,
+        Fp: Fn(f64) -> f64,
+    {
+        let mut result = SolveResult::new();
+
+        let f_lower = f(lower);
+        let f_upper = f(upper);
+        if !f_lower.is_finite() || !f_upper.is_finite() {
+            return result;
+        }
+        if f_lower == 0.0 || f_upper == 0.0 {
+            result.root = if f_lower == 0.0 { lower } else { upper };
+            result.status = SolveStatus::Converged;
+            result.history.push(Value { x: 0.0, y: result.root });
+            return result;
+        }
+        if f_lower.signum() == f_upper.signum() {
+            result.status = SolveStatus::NoBracket;
+            return result;
+        }
+
+        // `lo` selalu sisi dengan f negatif.
+        let (mut lo, mut hi) = if f_lower < 0.0 { (lower, upper) } else { (upper, lower) };
+        let mut x = if x0 > lower.min(upper) && x0 < lower.max(upper) { x0 } else { 0.5 * (lower + upper) };
+        let mut step_before_last = (upper - lower).abs();
+        let mut last_step = step_before_last;
+        result.history.push(Value { x: 0.0, y: x });
+
+        for i in 0..max_iter {
+            let fx = f(x);
+            let fpx = fp(x);
+            if !fx.is_finite() {
+                result.root = x;
+                result.status = SolveStatus::InvalidFunction;
+                return result;
+            }
+            if fx == 0.0 {
+                result.root = x;
+                result.status = SolveStatus::Converged;
+                return result;
+            }
+            if fx < 0.0 { lo = x; } else { hi = x; }
+
+            let newton_x = x - fx / fpx;
+            let inside_bracket = (newton_x - lo) * (newton_x - hi) < 0.0;
+            let shrinking_fast = (2.0 * fx).abs() <= (step_before_last * fpx).abs();
+
+            result.iterations += 1;
+            let x_new = if fpx.is_finite() && fpx != 0.0 && inside_bracket && shrinking_fast {
+                step_before_last = last_step;
+                last_step = (x - newton_x).abs();
+                newton_x
+            } else {
+                result.bisection_steps += 1;
+                step_before_last = last_step;
+                last_step = 0.5 * (hi - lo).abs();
+                0.5 * (lo + hi)
+            };
+            result.history.push(Value { x: (i + 1) as f64, y: x_new });
+
+            if (x_new - x).abs() < tol {
+                result.root = x_new;
+                result.status = SolveStatus::Converged;
+                return result;
+            }
+            x = x_new;
+        }
+
+        result.root = x;
+        result.status = SolveStatus::MaxIterations;
+        result
+    }
+
+    // Melebarkan batas atas (x2) sampai f(lower) dan f(upper) berbeda tanda, maksimal sampai `max_upper`.
+    pub fn expand_upper_bracket<F>(f: F, lower: f64, initial_upper: f64, max_upper: f64) -> Option<f64>
+    where
+        F: Fn(f64) -> f64,
+    {
+        let f_lower = f(lower);
+        let mut upper = initial_upper;
+        while upper <= max_upper {
+            let f_upper = f(upper);
+            if f_upper.is_finite() && f_lower.is_finite() && f_upper.signum() != f_lower.signum() {
+                return Some(upper);
             }
+            upper *= 2.0;
         }
-        (x, results_history)
+        None
     }
     
     pub fn add_value(&mut self, value: Value) {
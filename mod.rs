@@ -2,3 +2,5 @@ pub mod home_screen;
 pub mod data_graphics_screen;
 pub mod database_screen;
 pub mod sensor_configuration_screen;
+pub mod summary_screen;
+pub mod diagnostics_screen;
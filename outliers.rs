@@ -0,0 +1,226 @@
+// src/outliers.rs
+// Deteksi lonjakan pada nilai mentah photodiode (debu, bayangan pekerja, gangguan listrik).
+// Sampel yang ditandai tidak masuk jendela acuan; bila penyimpangan bertahan beberapa sampel
+// berturut-turut, dianggap perubahan level sungguhan (mis. lampu dinyalakan) dan jendela diisi ulang.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Mengubah MAD menjadi perkiraan simpangan baku untuk distribusi normal.
+const MAD_TO_SIGMA: f64 = 1.4826;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OutlierFlag {
+    Hampel,
+    ZScore,
+    RateOfChange,
+}
+
+impl OutlierFlag {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutlierFlag::Hampel => "Hampel",
+            OutlierFlag::ZScore => "Z-Score",
+            OutlierFlag::RateOfChange => "Laju Perubahan",
+        }
+    }
+
+    // Nilai yang disimpan di database.
+    pub fn key(&self) -> &'static str {
+        match self {
+            OutlierFlag::Hampel => "hampel",
+            OutlierFlag::ZScore => "zscore",
+            OutlierFlag::RateOfChange => "rate",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        [OutlierFlag::Hampel, OutlierFlag::ZScore, OutlierFlag::RateOfChange].into_iter().find(|flag| flag.key() == key)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlierSettings {
+    pub hampel_enabled: bool,
+    pub hampel_threshold: f64,
+    pub zscore_enabled: bool,
+    pub zscore_threshold: f64,
+    pub rate_enabled: bool,
+    // Hitungan ADC per detik.
+    pub max_rate: f64,
+    pub window: usize,
+    // Penyimpangan di bawah nilai ini (hitungan ADC) tidak pernah ditandai, agar sinyal yang sangat
+    // stabil (MAD atau simpangan baku mendekati nol) tidak memicu tanda pada noise kecil.
+    pub min_deviation: f64,
+    // Jumlah sampel berturut-turut yang ditandai sebelum dianggap perubahan level. Minimal 2: dengan 1,
+    // setiap sampel menyimpang langsung diterima sebagai level baru dan tidak pernah ditandai.
+    pub persistence: usize,
+}
+
+impl OutlierSettings {
+    pub const MIN_WINDOW: usize = 5;
+    pub const MAX_WINDOW: usize = 201;
+    pub const MIN_PERSISTENCE: usize = 2;
+
+    pub fn new() -> Self {
+        Self {
+            hampel_enabled: true,
+            hampel_threshold: 3.0,
+            zscore_enabled: false,
+            zscore_threshold: 3.0,
+            rate_enabled: false,
+            max_rate: 200.0,
+            window: 15,
+            min_deviation: 5.0,
+            persistence: 5,
+        }
+    }
+
+    pub fn any_enabled(&self) -> bool {
+        self.hampel_enabled || self.zscore_enabled || self.rate_enabled
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if 2 * middle == values.len() { 0.5 * (values[middle - 1] + values[middle]) } else { values[middle] }
+}
+
+#[derive(Clone, Debug)]
+pub struct OutlierDetector {
+    pub settings: OutlierSettings,
+    // (detik, nilai) sampel acuan yang tidak ditandai.
+    window: VecDeque<(f64, f64)>,
+    pending: Vec<(f64, f64)>,
+    pub flagged_count: usize,
+    pub checked_count: usize,
+}
+
+impl OutlierDetector {
+    pub fn new() -> Self {
+        Self {
+            settings: OutlierSettings::new(),
+            window: VecDeque::new(),
+            pending: Vec::new(),
+            flagged_count: 0,
+            checked_count: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.pending.clear();
+    }
+
+    fn evaluate(&self, time: f64, value: f64) -> Option<OutlierFlag> {
+        let settings = &self.settings;
+        if settings.rate_enabled {
+            if let Some(&(last_time, last_value)) = self.window.back() {
+                let dt = time - last_time;
+                if dt > 0.0 && (value - last_value).abs() > settings.min_deviation && (value - last_value).abs() / dt > settings.max_rate {
+                    return Some(OutlierFlag::RateOfChange);
+                }
+            }
+        }
+        if self.window.len() < OutlierSettings::MIN_WINDOW {
+            return None;
+        }
+
+        let values: Vec<f64> = self.window.iter().map(|&(_, v)| v).collect();
+        if settings.hampel_enabled {
+            let center = median(&mut values.clone());
+            let mut deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+            let sigma = MAD_TO_SIGMA * median(&mut deviations);
+            let deviation = (value - center).abs();
+            if deviation > settings.min_deviation && deviation > settings.hampel_threshold * sigma {
+                return Some(OutlierFlag::Hampel);
+            }
+        }
+        if settings.zscore_enabled {
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+            let deviation = (value - mean).abs();
+            if deviation > settings.min_deviation && deviation > settings.zscore_threshold * variance.sqrt() {
+                return Some(OutlierFlag::ZScore);
+            }
+        }
+        None
+    }
+
+    // `time` dalam detik (monoton). Mengembalikan tanda untuk sampel ini, `None` bila wajar.
+    pub fn check(&mut self, time: f64, value: f64) -> Option<OutlierFlag> {
+        if !self.settings.any_enabled() || !value.is_finite() {
+            return None;
+        }
+        self.checked_count += 1;
+        let window = self.settings.window.clamp(OutlierSettings::MIN_WINDOW, OutlierSettings::MAX_WINDOW);
+
+        let flag = self.evaluate(time, value);
+        match flag {
+            Some(_) => self.pending.push((time, value)),
+            None => self.pending.clear(),
+        }
+        if self.pending.len() >= self.settings.persistence.max(OutlierSettings::MIN_PERSISTENCE) {
+            // Penyimpangan bertahan: level baru diterima sebagai acuan.
+            self.window = self.pending.drain(..).collect();
+            return None;
+        }
+        if flag.is_none() {
+            self.window.push_back((time, value));
+            while self.window.len() > window {
+                self.window.pop_front();
+            }
+        } else {
+            self.flagged_count += 1;
+        }
+        flag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(persistence: usize) -> OutlierDetector {
+        let mut detector = OutlierDetector::new();
+        detector.settings.persistence = persistence;
+        detector
+    }
+
+    // Sinyal stabil dengan noise kecil agar MAD tidak nol.
+    fn baseline(i: usize) -> f64 {
+        500.0 + [0.0, 1.5, -1.0, 2.0, -2.0, 0.5][i % 6]
+    }
+
+    #[test]
+    fn hampel_flags_single_spike() {
+        let mut detector = detector(5);
+        for i in 0..30 {
+            assert_eq!(detector.check(i as f64, baseline(i)), None, "sampel {}", i);
+        }
+        assert_eq!(detector.check(30.0, 900.0), Some(OutlierFlag::Hampel));
+        assert_eq!(detector.check(31.0, baseline(31)), None);
+        assert_eq!(detector.flagged_count, 1);
+    }
+
+    #[test]
+    fn persistent_step_is_accepted_as_new_level() {
+        let mut detector = detector(3);
+        for i in 0..30 {
+            detector.check(i as f64, baseline(i));
+        }
+        let flags: Vec<_> = (30..36).map(|i| detector.check(i as f64, baseline(i) + 300.0)).collect();
+        assert_eq!(flags[..2], [Some(OutlierFlag::Hampel); 2]);
+        assert!(flags[2..].iter().all(Option::is_none), "{:?}", flags);
+    }
+
+    #[test]
+    fn persistence_of_one_still_flags_spikes() {
+        let mut detector = detector(1);
+        for i in 0..30 {
+            detector.check(i as f64, baseline(i));
+        }
+        assert_eq!(detector.check(30.0, 900.0), Some(OutlierFlag::Hampel));
+    }
+}
@@ -0,0 +1,184 @@
+// src/parquet_export.rs
+// Ekspor Parquet untuk analisis musiman: satu file per hari (`date=YYYY-MM-DD/part-0.parquet`)
+// dengan skema tetap agar bisa dibaca langsung oleh tools kolumnar.
+
+use arrow_array::builder::{Float64Builder, StringBuilder, TimestampMillisecondBuilder, UInt32Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, NaiveDate, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::adc::AdcSettings;
+use crate::export::ExportOptions;
+use crate::recompute;
+use crate::storage::{SeriesKind, Storage, StorageResult, StoredRecord};
+
+// Data photodiode dan hasil NR disimpan terpisah; keduanya dipasangkan bila selisih waktunya sekecil ini.
+const MATCH_TOLERANCE_MS: i64 = 2_000;
+
+#[derive(Clone, Debug)]
+pub struct AnalyticsRow {
+    pub timestamp: DateTime<Utc>,
+    pub sensor_id: Option<String>,
+    pub raw_adc: Option<f64>,
+    pub voltage: Option<f64>,
+    pub lux: Option<f64>,
+    pub nr_iterations: Option<u32>,
+}
+
+pub fn schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("timestamp", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), false),
+        Field::new("sensor_id", DataType::Utf8, true),
+        Field::new("raw_adc", DataType::Float64, true),
+        Field::new("voltage", DataType::Float64, true),
+        Field::new("lux", DataType::Float64, true),
+        Field::new("nr_iterations", DataType::UInt32, true),
+    ]))
+}
+
+fn nr_iterations(record: &StoredRecord) -> Option<u32> {
+    // Riwayat iterasi diawali tebakan awal x0.
+    if record.iterations_history.is_empty() {
+        None
+    } else {
+        Some((record.iterations_history.len() - 1) as u32)
+    }
+}
+
+// Setiap pembacaan photodiode dipasangkan dengan hasil NR terdekat dari sensor yang sama.
+// Hasil NR tanpa pasangan (mis. impor lux saja) tetap ditulis dengan kolom mentah kosong. Set hasil
+// hitung ulang tidak ikut: timestamp-nya sama dengan data live sehingga akan bersaing sebagai pasangan.
+pub fn join_rows(readings: Vec<StoredRecord>, results: Vec<StoredRecord>, adc_by_sensor: &BTreeMap<String, AdcSettings>) -> Vec<AnalyticsRow> {
+    let default_adc = AdcSettings::new();
+    let mut results_by_sensor: HashMap<Option<String>, Vec<(i64, StoredRecord, bool)>> = HashMap::new();
+    for result in results.into_iter().filter(|result| !result.source.as_deref().is_some_and(recompute::is_result_set)) {
+        if let Some(timestamp) = result.timestamp {
+            results_by_sensor.entry(result.sensor_id.clone()).or_default()
+                .push((timestamp.timestamp_millis(), result, false));
+        }
+    }
+    for candidates in results_by_sensor.values_mut() {
+        candidates.sort_by_key(|(ts, _, _)| *ts);
+    }
+
+    let mut rows = Vec::new();
+    for reading in readings {
+        let Some(timestamp) = reading.timestamp else { continue };
+        let ts = timestamp.timestamp_millis();
+
+        let mut matched: Option<&StoredRecord> = None;
+        if let Some(candidates) = results_by_sensor.get_mut(&reading.sensor_id) {
+            let split = candidates.partition_point(|(candidate_ts, _, _)| *candidate_ts < ts);
+            let nearest = [split.checked_sub(1), Some(split)].into_iter()
+                .flatten()
+                .filter(|&i| i < candidates.len() && !candidates[i].2)
+                .min_by_key(|&i| (candidates[i].0 - ts).abs())
+                .filter(|&i| (candidates[i].0 - ts).abs() <= MATCH_TOLERANCE_MS);
+            if let Some(i) = nearest {
+                candidates[i].2 = true;
+                matched = Some(&candidates[i].1);
+            }
+        }
+
+        rows.push(AnalyticsRow {
+            timestamp,
+            sensor_id: reading.sensor_id.clone(),
+            raw_adc: reading.value,
+            voltage: reading.value.map(|value| {
+                let adc = reading.sensor_id.as_ref().and_then(|id| adc_by_sensor.get(id)).unwrap_or(&default_adc);
+                adc.to_voltage(value)
+            }),
+            lux: matched.and_then(|result| result.value),
+            nr_iterations: matched.and_then(nr_iterations),
+        });
+    }
+
+    for candidates in results_by_sensor.into_values() {
+        for (_, result, used) in candidates {
+            if used {
+                continue;
+            }
+            if let Some(timestamp) = result.timestamp {
+                rows.push(AnalyticsRow {
+                    timestamp,
+                    sensor_id: result.sensor_id.clone(),
+                    raw_adc: None,
+                    voltage: None,
+                    lux: result.value,
+                    nr_iterations: nr_iterations(&result),
+                });
+            }
+        }
+    }
+
+    rows.sort_by_key(|row| row.timestamp);
+    rows
+}
+
+fn to_record_batch(rows: &[AnalyticsRow]) -> StorageResult<RecordBatch> {
+    let mut timestamps = TimestampMillisecondBuilder::with_capacity(rows.len()).with_timezone("UTC");
+    let mut sensor_ids = StringBuilder::new();
+    let mut raw_adc = Float64Builder::with_capacity(rows.len());
+    let mut voltage = Float64Builder::with_capacity(rows.len());
+    let mut lux = Float64Builder::with_capacity(rows.len());
+    let mut iterations = UInt32Builder::with_capacity(rows.len());
+
+    for row in rows {
+        timestamps.append_value(row.timestamp.timestamp_millis());
+        sensor_ids.append_option(row.sensor_id.as_deref());
+        raw_adc.append_option(row.raw_adc);
+        voltage.append_option(row.voltage);
+        lux.append_option(row.lux);
+        iterations.append_option(row.nr_iterations);
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(timestamps.finish()),
+        Arc::new(sensor_ids.finish()),
+        Arc::new(raw_adc.finish()),
+        Arc::new(voltage.finish()),
+        Arc::new(lux.finish()),
+        Arc::new(iterations.finish()),
+    ];
+    Ok(RecordBatch::try_new(schema(), columns)?)
+}
+
+// `options.series`, `columns` dan `decimal_separator` diabaikan karena skemanya tetap.
+// Mengembalikan jumlah baris yang ditulis.
+pub fn export_partitioned(storage: &dyn Storage, dir: &Path, options: &ExportOptions) -> StorageResult<usize> {
+    let sensor_id = options.sensor_id.as_deref();
+    let readings = storage.query_range(SeriesKind::Photodiode, sensor_id, options.from, options.to)?;
+    let results = storage.query_range(SeriesKind::NewtonRaphson, sensor_id, options.from, options.to)?;
+    let rows = join_rows(readings, results, &options.adc_by_sensor);
+
+    // Batas hari mengikuti zona waktu ekspor agar satu partisi = satu hari di greenhouse.
+    let mut rows_by_day: BTreeMap<NaiveDate, Vec<AnalyticsRow>> = BTreeMap::new();
+    for row in rows {
+        let day = row.timestamp.with_timezone(&options.utc_offset).date_naive();
+        rows_by_day.entry(day).or_default().push(row);
+    }
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+
+    let mut written = 0;
+    for (day, day_rows) in rows_by_day {
+        let partition_dir = dir.join(format!("date={}", day.format("%Y-%m-%d")));
+        fs::create_dir_all(&partition_dir)?;
+
+        let file = File::create(partition_dir.join("part-0.parquet"))?;
+        let mut writer = ArrowWriter::try_new(file, schema(), Some(properties.clone()))?;
+        writer.write(&to_record_batch(&day_rows)?)?;
+        writer.close()?;
+        written += day_rows.len();
+    }
+    Ok(written)
+}
@@ -0,0 +1,228 @@
+// src/photoperiod.rs
+// Deteksi terang/gelap dari deret lux dengan histeresis dan debounce, lalu statistik harian:
+// fotoperiode, jam di atas ambang, puncak lux, dan kebocoran cahaya malam.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhotoperiodSettings {
+    // Terang dimulai saat lux mencapai ambang ini.
+    pub light_threshold_lux: f64,
+    // Gelap dimulai di bawah ambang · (1 - histeresis) agar fluktuasi di sekitar ambang tidak bolak-balik.
+    pub hysteresis: f64,
+    // Perubahan keadaan harus bertahan selama ini sebelum dianggap transisi (mis. awan lewat diabaikan).
+    pub min_state_seconds: f64,
+    // Lux di atas nilai ini saat keadaan gelap dihitung sebagai kebocoran cahaya malam.
+    pub leakage_threshold_lux: f64,
+    // Transisi yang berubah lebih dari 2 · ambang dalam jendela ini dianggap lampu, bukan matahari.
+    pub abrupt_window_seconds: f64,
+    pub max_gap_seconds: f64,
+    pub utc_offset: FixedOffset,
+}
+
+impl PhotoperiodSettings {
+    pub fn new() -> Self {
+        Self {
+            light_threshold_lux: 50.0,
+            hysteresis: 0.2,
+            min_state_seconds: 600.0,
+            leakage_threshold_lux: 1.0,
+            abrupt_window_seconds: 120.0,
+            max_gap_seconds: 900.0,
+            utc_offset: FixedOffset::east_opt(7 * 3600).unwrap(),
+        }
+    }
+
+    fn off_threshold(&self) -> f64 {
+        self.light_threshold_lux * (1.0 - self.hysteresis.clamp(0.0, 0.9))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LuxSample {
+    pub timestamp: DateTime<Utc>,
+    pub lux: f64,
+    // Nilai maksimum dalam bucket agregat; sama dengan `lux` untuk data mentah.
+    pub peak_lux: f64,
+}
+
+impl LuxSample {
+    pub fn new(timestamp: DateTime<Utc>, lux: f64) -> Self {
+        Self { timestamp, lux, peak_lux: lux }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransitionKind {
+    Sunrise,
+    Sunset,
+    LightsOn,
+    LightsOff,
+}
+
+impl TransitionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransitionKind::Sunrise => "Matahari Terbit",
+            TransitionKind::Sunset => "Matahari Terbenam",
+            TransitionKind::LightsOn => "Lampu Nyala",
+            TransitionKind::LightsOff => "Lampu Padam",
+        }
+    }
+
+    pub fn is_light_start(&self) -> bool {
+        matches!(self, TransitionKind::Sunrise | TransitionKind::LightsOn)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transition {
+    pub timestamp: DateTime<Utc>,
+    pub kind: TransitionKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailyLightStats {
+    pub date: NaiveDate,
+    // Durasi keadaan terang (setelah histeresis dan debounce).
+    pub photoperiod_hours: f64,
+    // Durasi mentah dengan lux di atas ambang.
+    pub hours_above_threshold: f64,
+    pub peak_lux: f64,
+    pub peak_time: Option<DateTime<Utc>>,
+    pub night_leakage_hours: f64,
+    pub night_mean_lux: Option<f64>,
+    pub covered_hours: f64,
+    pub sample_count: usize,
+    pub transitions: Vec<Transition>,
+}
+
+impl DailyLightStats {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            photoperiod_hours: 0.0,
+            hours_above_threshold: 0.0,
+            peak_lux: 0.0,
+            peak_time: None,
+            night_leakage_hours: 0.0,
+            night_mean_lux: None,
+            covered_hours: 0.0,
+            sample_count: 0,
+            transitions: Vec::new(),
+        }
+    }
+}
+
+fn seconds_between(a: DateTime<Utc>, b: DateTime<Utc>) -> f64 {
+    (b - a).num_milliseconds() as f64 / 1000.0
+}
+
+// Transisi terkonfirmasi: keadaan baru bertahan minimal `min_state_seconds`. Waktu transisi adalah
+// sampel pertama yang melewati ambang, bukan saat konfirmasi.
+pub fn detect_transitions(samples: &[LuxSample], settings: &PhotoperiodSettings) -> (bool, Vec<Transition>) {
+    let Some(first) = samples.first() else {
+        return (false, Vec::new());
+    };
+    let initial_light = first.lux >= settings.light_threshold_lux;
+    let mut light = initial_light;
+    let mut candidate: Option<usize> = None;
+    let mut transitions = Vec::new();
+
+    for (i, sample) in samples.iter().enumerate() {
+        let crosses = if light { sample.lux < settings.off_threshold() } else { sample.lux >= settings.light_threshold_lux };
+        match (crosses, candidate) {
+            (true, None) => candidate = Some(i),
+            (false, Some(_)) => candidate = None,
+            _ => {},
+        }
+        if let Some(start) = candidate {
+            if seconds_between(samples[start].timestamp, sample.timestamp) >= settings.min_state_seconds {
+                light = !light;
+                candidate = None;
+                transitions.push(Transition {
+                    timestamp: samples[start].timestamp,
+                    kind: classify(samples, start, light, settings),
+                });
+            }
+        }
+    }
+    (initial_light, transitions)
+}
+
+fn classify(samples: &[LuxSample], index: usize, light_start: bool, settings: &PhotoperiodSettings) -> TransitionKind {
+    let crossing = samples[index];
+    let before = samples[..index].iter()
+        .rev()
+        .take_while(|sample| seconds_between(sample.timestamp, crossing.timestamp) <= settings.abrupt_window_seconds)
+        .last();
+    let abrupt = before.is_some_and(|before| (crossing.lux - before.lux).abs() >= 2.0 * settings.light_threshold_lux);
+    match (light_start, abrupt) {
+        (true, false) => TransitionKind::Sunrise,
+        (true, true) => TransitionKind::LightsOn,
+        (false, false) => TransitionKind::Sunset,
+        (false, true) => TransitionKind::LightsOff,
+    }
+}
+
+// Statistik per hari lokal. Durasi tiap selang sampel masuk ke hari sampel awalnya; selang lebih
+// panjang dari `max_gap_seconds` dianggap celah dan tidak dihitung.
+pub fn daily_stats(samples: &[LuxSample], settings: &PhotoperiodSettings) -> Vec<DailyLightStats> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by_key(|sample| sample.timestamp);
+    sorted.retain(|sample| sample.lux.is_finite());
+
+    let (initial_light, transitions) = detect_transitions(&sorted, settings);
+    let local_date = |timestamp: DateTime<Utc>| timestamp.with_timezone(&settings.utc_offset).date_naive();
+
+    let mut days: BTreeMap<NaiveDate, DailyLightStats> = BTreeMap::new();
+    let mut night_lux: BTreeMap<NaiveDate, (f64, f64)> = BTreeMap::new();
+    for transition in &transitions {
+        let date = local_date(transition.timestamp);
+        days.entry(date).or_insert_with(|| DailyLightStats::new(date)).transitions.push(*transition);
+    }
+
+    let mut light = initial_light;
+    let mut next_transition = transitions.iter().peekable();
+    for (i, sample) in sorted.iter().enumerate() {
+        while next_transition.peek().is_some_and(|transition| transition.timestamp <= sample.timestamp) {
+            light = next_transition.next().unwrap().kind.is_light_start();
+        }
+        let date = local_date(sample.timestamp);
+        let day = days.entry(date).or_insert_with(|| DailyLightStats::new(date));
+        day.sample_count += 1;
+        if sample.peak_lux > day.peak_lux || day.peak_time.is_none() {
+            day.peak_lux = sample.peak_lux;
+            day.peak_time = Some(sample.timestamp);
+        }
+
+        let Some(next) = sorted.get(i + 1) else { continue };
+        let dt = seconds_between(sample.timestamp, next.timestamp);
+        if dt <= 0.0 || dt > settings.max_gap_seconds {
+            continue;
+        }
+        let hours = dt / 3600.0;
+        day.covered_hours += hours;
+        if light {
+            day.photoperiod_hours += hours;
+        } else {
+            if sample.lux > settings.leakage_threshold_lux {
+                day.night_leakage_hours += hours;
+            }
+            let (sum, duration) = night_lux.entry(date).or_default();
+            *sum += sample.lux * hours;
+            *duration += hours;
+        }
+        if sample.lux >= settings.light_threshold_lux {
+            day.hours_above_threshold += hours;
+        }
+    }
+
+    for (date, (sum, duration)) in night_lux {
+        if let Some(day) = days.get_mut(&date) {
+            day.night_mean_lux = (duration > 0.0).then(|| sum / duration);
+        }
+    }
+    days.into_values().collect()
+}
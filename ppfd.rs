@@ -0,0 +1,130 @@
+// src/ppfd.rs
+// Konversi lux ke PPFD (µmol/m²/s, foton 400–700 nm). Faktornya bergantung pada spektrum sumber cahaya:
+// faktor = ∫ E(λ) · λ / (h·c·N_A) dλ  /  (683 · ∫ E(λ) · V(λ) dλ), dengan E(λ) spektrum relatif sumber.
+
+use serde::{Deserialize, Serialize};
+
+// Fungsi luminositas fotopik CIE 1931, 380–780 nm per 10 nm.
+const PHOTOPIC_START_NM: f64 = 380.0;
+const PHOTOPIC_STEP_NM: f64 = 10.0;
+const PHOTOPIC: [f64; 41] = [
+    0.000039, 0.00012, 0.000396, 0.00121, 0.004, 0.0116, 0.023, 0.038, 0.06, 0.091,
+    0.139, 0.208, 0.323, 0.503, 0.71, 0.862, 0.954, 0.995, 0.995, 0.952,
+    0.87, 0.757, 0.631, 0.503, 0.381, 0.265, 0.175, 0.107, 0.061, 0.032,
+    0.017, 0.00821, 0.0041, 0.00209, 0.00105, 0.00052, 0.000249, 0.00012, 0.00006, 0.00003,
+    0.000015,
+];
+
+const MAX_LUMINOUS_EFFICACY: f64 = 683.0;
+// µmol foton per joule pada panjang gelombang 1 nm: 1e-9 / (h · c) / N_A · 1e6.
+const MICROMOL_PER_JOULE_NM: f64 = 1e-9 / (6.62607015e-34 * 299_792_458.0) / 6.02214076e23 * 1e6;
+const PAR_RANGE_NM: (f64, f64) = (400.0, 700.0);
+const INTEGRATION_STEP_NM: f64 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LightSource {
+    Sunlight,
+    WhiteLed,
+    RedBlueLed,
+    HighPressureSodium,
+}
+
+impl LightSource {
+    pub const ALL: [LightSource; 4] = [
+        LightSource::Sunlight,
+        LightSource::WhiteLed,
+        LightSource::RedBlueLed,
+        LightSource::HighPressureSodium,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LightSource::Sunlight => "Sinar Matahari",
+            LightSource::WhiteLed => "LED Putih",
+            LightSource::RedBlueLed => "LED Merah/Biru",
+            LightSource::HighPressureSodium => "HPS (Sodium Tekanan Tinggi)",
+        }
+    }
+}
+
+fn photopic(wavelength_nm: f64) -> f64 {
+    let position = (wavelength_nm - PHOTOPIC_START_NM) / PHOTOPIC_STEP_NM;
+    if position < 0.0 || position > (PHOTOPIC.len() - 1) as f64 {
+        return 0.0;
+    }
+    let lower = position.floor() as usize;
+    let upper = (lower + 1).min(PHOTOPIC.len() - 1);
+    let fraction = position - lower as f64;
+    PHOTOPIC[lower] + fraction * (PHOTOPIC[upper] - PHOTOPIC[lower])
+}
+
+fn gaussian(wavelength_nm: f64, peak_nm: f64, fwhm_nm: f64) -> f64 {
+    let sigma = fwhm_nm / (2.0 * (2.0 * 2f64.ln()).sqrt());
+    (-0.5 * ((wavelength_nm - peak_nm) / sigma).powi(2)).exp()
+}
+
+// Radiasi benda hitam (skala relatif), pendekatan spektrum matahari di permukaan.
+fn planck(wavelength_nm: f64, temperature_k: f64) -> f64 {
+    const SECOND_RADIATION_CONSTANT_NM_K: f64 = 1.438_776_877e7;
+    let x = wavelength_nm / 1000.0;
+    1.0 / (x.powi(5) * ((SECOND_RADIATION_CONSTANT_NM_K / (wavelength_nm * temperature_k)).exp_m1()))
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PpfdSettings {
+    pub source: LightSource,
+    // Porsi daya radiasi merah (660 nm) pada LED merah/biru; sisanya biru (450 nm).
+    pub red_fraction: f64,
+    // Faktor µmol/m²/s per lux dari pengukuran quantum sensor; `None` = hitung dari spektrum.
+    pub factor_override: Option<f64>,
+}
+
+impl PpfdSettings {
+    pub fn new() -> Self {
+        Self {
+            source: LightSource::Sunlight,
+            red_fraction: 0.8,
+            factor_override: None,
+        }
+    }
+
+    // Spektrum daya relatif model untuk sumber yang dipilih.
+    pub fn spectral_power(&self, wavelength_nm: f64) -> f64 {
+        match self.source {
+            LightSource::Sunlight => planck(wavelength_nm, 5800.0),
+            // Puncak biru chip InGaN ditambah pita lebar fosfor kuning.
+            LightSource::WhiteLed => 0.5 * gaussian(wavelength_nm, 450.0, 20.0) + gaussian(wavelength_nm, 600.0, 140.0),
+            LightSource::RedBlueLed => {
+                let red = self.red_fraction.clamp(0.0, 1.0);
+                red * gaussian(wavelength_nm, 660.0, 20.0) + (1.0 - red) * gaussian(wavelength_nm, 450.0, 20.0)
+            },
+            // Pita natrium D yang melebar di sekitar 590–600 nm plus garis 569 nm dan 819 nm.
+            LightSource::HighPressureSodium => gaussian(wavelength_nm, 600.0, 60.0)
+                + 0.25 * gaussian(wavelength_nm, 569.0, 8.0)
+                + 0.3 * gaussian(wavelength_nm, 819.0, 15.0),
+        }
+    }
+
+    pub fn spectral_factor(&self) -> f64 {
+        let (mut photons, mut luminous) = (0.0, 0.0);
+        let mut wavelength = PHOTOPIC_START_NM;
+        let end = PHOTOPIC_START_NM + PHOTOPIC_STEP_NM * (PHOTOPIC.len() - 1) as f64;
+        while wavelength <= end {
+            let power = self.spectral_power(wavelength);
+            if (PAR_RANGE_NM.0..=PAR_RANGE_NM.1).contains(&wavelength) {
+                photons += power * wavelength * MICROMOL_PER_JOULE_NM;
+            }
+            luminous += power * photopic(wavelength);
+            wavelength += INTEGRATION_STEP_NM;
+        }
+        photons / (MAX_LUMINOUS_EFFICACY * luminous)
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.factor_override.unwrap_or_else(|| self.spectral_factor())
+    }
+
+    pub fn lux_to_ppfd(&self, lux: f64) -> f64 {
+        lux * self.factor()
+    }
+}
@@ -0,0 +1,185 @@
+// src/recompute.rs
+// Menghitung ulang lux dari data photodiode mentah dengan profil kalibrasi tertentu. Hasil lama tidak
+// ditimpa: setiap proses menulis set hasil baru yang dibedakan lewat label sumber, sehingga seri lama
+// dan baru bisa dibandingkan di layar grafik.
+
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+
+use crate::calibration_profile::CalibrationProfile;
+use crate::conversion::{ConversionMode, LuxLookupTable};
+use crate::filters::FilterChain;
+use crate::outliers::OutlierFlag;
+use crate::ppfd::PpfdSettings;
+use crate::solvers::{LuxProblem, SolverMethod};
+use crate::storage::{SeriesKind, Storage, StorageResult};
+use crate::uncertainty::{self, ConfidenceInterval};
+
+pub const RESULT_SET_PREFIX: &str = "rekalkulasi";
+
+// Jumlah hasil yang ditulis per batch; progres diperbarui setiap batch.
+const WRITE_BATCH_SIZE: usize = 500;
+
+#[derive(Clone, Debug)]
+pub struct RecomputedResult {
+    pub timestamp: DateTime<Utc>,
+    pub lux: f64,
+    pub iterations_history: Vec<f64>,
+    pub confidence_interval: Option<ConfidenceInterval>,
+    pub ppfd: Option<f64>,
+    // Tanda outlier dari pembacaan photodiode asal.
+    pub outlier: Option<OutlierFlag>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecomputeRequest {
+    pub sensor_id: String,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub profile: CalibrationProfile,
+    pub ppfd: PpfdSettings,
+    // Rantai filter sensor seperti pada konversi live; keadaannya direset sebelum proses dimulai.
+    pub filter: FilterChain,
+    pub mode: ConversionMode,
+    pub solver_method: SolverMethod,
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl RecomputeRequest {
+    // "rekalkulasi <ID profil> @ <waktu mulai>", unik per proses sehingga menjadi versi set hasil.
+    pub fn result_set_label(&self, started_at: DateTime<Utc>) -> String {
+        format!("{} {} @ {}", RESULT_SET_PREFIX, self.profile.id, started_at.format("%Y-%m-%d %H:%M:%S"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecomputeProgress {
+    pub running: bool,
+    pub total: usize,
+    pub processed: usize,
+    pub written: usize,
+    // Pembacaan yang tidak bisa dikonversi (di luar jangkauan model atau solver gagal).
+    pub failed: usize,
+    pub result_set: Option<String>,
+    pub message: String,
+    pub cancel_requested: bool,
+}
+
+impl RecomputeProgress {
+    pub fn new() -> Self {
+        Self {
+            running: false,
+            total: 0,
+            processed: 0,
+            written: 0,
+            failed: 0,
+            result_set: None,
+            message: String::new(),
+            cancel_requested: false,
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 { 0.0 } else { self.processed as f32 / self.total as f32 }
+    }
+}
+
+pub fn is_result_set(source: &str) -> bool {
+    source.starts_with(RESULT_SET_PREFIX)
+}
+
+fn convert(problem: &mut LuxProblem, request: &RecomputeRequest, lookup_table: Option<&LuxLookupTable>, photodiode_value: f64) -> Option<(f64, Vec<f64>)> {
+    problem.v_out = request.profile.settings.adc.to_voltage(photodiode_value);
+    match (request.mode, lookup_table) {
+        (ConversionMode::LookupTable, Some(table)) => table.lookup(photodiode_value).map(|lux| (lux, Vec::new())),
+        (ConversionMode::Analytic, _) => problem.model.inverse(problem.v_out).map(|lux| (lux, Vec::new())),
+        _ => {
+            let result = problem.solve(request.solver_method);
+            if !result.is_converged() {
+                return None;
+            }
+            // Warm start dari hasil sebelumnya, seperti pada konversi live.
+            problem.x0 = result.root;
+            Some((result.root, result.history.iter().map(|v| v.y).collect()))
+        }
+    }
+}
+
+// Dijalankan di thread latar belakang; `progress` dibaca UI setiap frame.
+pub fn run_recompute(storage: &dyn Storage, request: &RecomputeRequest, progress: &Mutex<RecomputeProgress>) -> StorageResult<usize> {
+    let started_at = Utc::now();
+    let result_set = request.result_set_label(started_at);
+    {
+        let mut progress = progress.lock().unwrap();
+        *progress = RecomputeProgress::new();
+        progress.running = true;
+        progress.result_set = Some(result_set.clone());
+        progress.message = "Membaca data photodiode...".to_string();
+    }
+
+    let readings = storage.query_range(SeriesKind::Photodiode, Some(&request.sensor_id), request.from, request.to)?;
+    progress.lock().unwrap().total = readings.len();
+
+    let settings = &request.profile.settings;
+    let ppfd_factor = request.ppfd.factor();
+    let lookup_table = (request.mode == ConversionMode::LookupTable).then(|| LuxLookupTable::new(settings));
+    let mut problem = LuxProblem {
+        model: settings.model(),
+        v_out: 0.0,
+        x0: 1.0,
+        tol: request.tolerance,
+        max_iter: request.max_iterations,
+    };
+
+    let mut filter = request.filter.clone();
+    filter.reset();
+
+    let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+    let mut written = 0;
+    for (i, reading) in readings.iter().enumerate() {
+        // Ringkasan per jam dari retensi sudah berupa rata-rata, jadi tidak dilewatkan filter.
+        let value = match reading.summarized_count {
+            Some(_) => reading.value,
+            None => reading.value.map(|raw_value| filter.apply(raw_value)),
+        };
+        let converted = match (reading.timestamp, value) {
+            (Some(timestamp), Some(photodiode_value)) => convert(&mut problem, request, lookup_table.as_ref(), photodiode_value)
+                .map(|(lux, iterations_history)| RecomputedResult {
+                    timestamp,
+                    lux,
+                    iterations_history,
+                    confidence_interval: uncertainty::propagate(settings, photodiode_value, lux).map(|u| u.interval()),
+                    ppfd: Some(lux * ppfd_factor),
+                    outlier: reading.outlier,
+                }),
+            _ => None,
+        };
+        match converted {
+            Some(result) => batch.push(result),
+            None => progress.lock().unwrap().failed += 1,
+        }
+
+        let last = i + 1 == readings.len();
+        if batch.len() >= WRITE_BATCH_SIZE || last {
+            written += storage.insert_recomputed(&request.sensor_id, &result_set, &request.profile.id, &batch)?;
+            batch.clear();
+
+            let mut progress = progress.lock().unwrap();
+            progress.processed = i + 1;
+            progress.written = written;
+            progress.message = format!("Menghitung ulang {} / {} pembacaan...", i + 1, readings.len());
+            if progress.cancel_requested && !last {
+                progress.running = false;
+                progress.message = format!("Dibatalkan setelah {} pembacaan; {} hasil sudah tersimpan di '{}'.", i + 1, written, result_set);
+                return Ok(written);
+            }
+        }
+    }
+
+    let mut progress = progress.lock().unwrap();
+    progress.running = false;
+    progress.processed = readings.len();
+    progress.message = format!("Selesai: {} hasil tersimpan di '{}' ({} gagal).", written, result_set, progress.failed);
+    Ok(written)
+}
@@ -1,46 +1,1111 @@
 use egui::{Ui, RichText, Color32, Grid, ScrollArea};
-use crate::measurements::Value;
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+use crate::adc::{AdcPolarity, AdcSettings};
+use crate::calibration::{self, CalibrationPoint, FitResult};
+use crate::calibration_model::{CalibrationModelKind, CalibrationSettings, PowerLawModel};
+use crate::calibration_profile::{CalibrationProfile, FitStatistics};
+use crate::crop::{Bed, CropProfile};
+use crate::conversion::{ConversionMode, CrossValidation, CrossValidationRequest};
+use crate::filters::{FilterChain, FilterKind, FilterStage};
+use crate::measurements::SolveResult;
+use crate::outliers::{OutlierDetector, OutlierSettings};
+use crate::ppfd::{LightSource, PpfdSettings};
+use crate::recompute::{RecomputeProgress, RecomputeRequest};
+use crate::solvers::{IterationStats, LuxProblem, SolverComparison, SolverMethod};
+use crate::storage::{RetentionPolicy, StorageBackend};
+use crate::uncertainty::{LuxUncertainty, COVERAGE_FACTOR};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 pub struct SensorConfigurationScreen {
-    pub calib_a_power: f64, 
-    pub calib_b_power: f64,
+    // Model kalibrasi per ID sensor; sensor tanpa entri memakai `CalibrationSettings::new()`.
+    pub calibration_by_sensor: BTreeMap<String, CalibrationSettings>,
+    pub calibration_sensor_id: String,
+
+    pub calibration_points: Vec<CalibrationPoint>,
+    pub calibration_photodiode_input: f64,
+    pub calibration_reference_lux: f64,
+    pub calibration_fit: Option<Result<FitResult, String>>,
+
+    // Diisi main.rs dari database saat start dan setelah profil baru tersimpan.
+    pub calibration_profiles: Arc<Mutex<Vec<CalibrationProfile>>>,
+    // Profil terakhir yang diterapkan per sensor.
+    pub active_profile_by_sensor: BTreeMap<String, String>,
+    pub profile_name_input: String,
+    pub profile_reference_instrument: String,
+    pub profile_notes: String,
+    pub profile_save_request: Option<CalibrationProfile>,
+    // (ID sensor, ID profil) untuk dicatat ke riwayat aktivasi.
+    pub profile_activation_request: Option<(String, String)>,
+    pub profile_status: Arc<Mutex<String>>,
+
+    // Diisi main.rs dari database saat start dan setelah bedengan tersimpan.
+    pub beds: Arc<Mutex<Vec<Bed>>>,
+    pub bed_name_input: String,
+    pub bed_crop: String,
+    // Format YYYY-MM-DD.
+    pub bed_planting_date_input: String,
+    pub bed_save_request: Option<Bed>,
+    pub bed_status: Arc<Mutex<String>>,
+
+    // Filter nilai mentah per ID sensor, diterapkan main.rs sebelum konversi ke lux.
+    pub filter_by_sensor: BTreeMap<String, FilterChain>,
+    pub filter_add_kind: FilterKind,
+
+    // Detektor lonjakan per ID sensor, diterapkan main.rs pada nilai mentah.
+    pub outlier_by_sensor: BTreeMap<String, OutlierDetector>,
+
+    // Sumber cahaya untuk konversi lux ke PPFD per ID sensor.
+    pub ppfd_by_sensor: BTreeMap<String, PpfdSettings>,
+
+    pub recompute_profile_id: Option<String>,
+    // Rentang hitung ulang dalam hari terakhir (0 = seluruh riwayat).
+    pub recompute_days: u32,
+    pub recompute_request: Option<RecomputeRequest>,
+    pub recompute_progress: Arc<Mutex<RecomputeProgress>>,
 
     pub initial_guess_nr: f64,
     pub tolerance_nr: f64,
     pub max_iterations_nr: u32,
+    pub solver_method: SolverMethod,
+    pub warm_start_enabled: bool,
+    pub conversion_mode: ConversionMode,
+    // Pada mode analitik/LUT, NR dijalankan setiap N sampel sebagai pembanding (0 = mati).
+    pub nr_validation_interval: u32,
+    pub nr_validation_status: String,
+    // Validasi silang menyelesaikan setiap level ADC (131.072 titik pada 16 bit), jadi dijalankan di thread main.rs.
+    pub cross_validation_request: Option<CrossValidationRequest>,
+    pub cross_validation: Arc<Mutex<Option<(SolverMethod, CrossValidation)>>>,
+    pub cross_validation_status: Arc<Mutex<String>>,
+    pub iteration_stats: IterationStats,
     
     pub baud_rate: u32,
 
-    pub newton_raphson_iter_results: Vec<Value>,
-    pub newton_raphson_akar: Option<f64>,
+    pub retention_policy: Arc<Mutex<RetentionPolicy>>,
+    pub storage_backend: StorageBackend,
+
+    pub latest_solve: Option<SolveResult>,
+
+    // Pembacaan terakhir (setelah filter), dipakai panel perbandingan metode dan kalibrasi.
+    pub last_raw_value: Option<f64>,
+    pub last_photodiode_value: Option<f64>,
+    pub last_v_out: Option<f64>,
+    pub latest_uncertainty: Option<LuxUncertainty>,
+    pub solver_comparison: Vec<SolverComparison>,
 }
 
 impl SensorConfigurationScreen {
-    pub fn new() -> Self {
+    pub fn new(storage_backend: StorageBackend) -> Self {
         Self {
-            calib_a_power: 0.0001, 
-            calib_b_power: 1.05,   
+            calibration_by_sensor: BTreeMap::new(),
+            calibration_sensor_id: crate::SENSOR_PORT.to_string(),
+
+            calibration_points: Vec::new(),
+            calibration_photodiode_input: 0.0,
+            calibration_reference_lux: 0.0,
+            calibration_fit: None,
+
+            calibration_profiles: Arc::new(Mutex::new(Vec::new())),
+            active_profile_by_sensor: BTreeMap::new(),
+            profile_name_input: String::new(),
+            profile_reference_instrument: String::new(),
+            profile_notes: String::new(),
+            profile_save_request: None,
+            profile_activation_request: None,
+            profile_status: Arc::new(Mutex::new(String::new())),
+
+            beds: Arc::new(Mutex::new(Vec::new())),
+            bed_name_input: String::new(),
+            bed_crop: CropProfile::lettuce().name.to_string(),
+            bed_planting_date_input: chrono::Local::now().date_naive().to_string(),
+            bed_save_request: None,
+            bed_status: Arc::new(Mutex::new(String::new())),
+
+            filter_by_sensor: BTreeMap::new(),
+            filter_add_kind: FilterKind::MovingAverage,
+            outlier_by_sensor: BTreeMap::new(),
+
+            ppfd_by_sensor: BTreeMap::new(),
+
+            recompute_profile_id: None,
+            recompute_days: 30,
+            recompute_request: None,
+            recompute_progress: Arc::new(Mutex::new(RecomputeProgress::new())),
 
             initial_guess_nr: 1.0, 
             tolerance_nr: 1e-6,    
             max_iterations_nr: 20, 
+            solver_method: SolverMethod::SafeguardedNewton,
+            warm_start_enabled: true,
+            conversion_mode: ConversionMode::NewtonRaphson,
+            nr_validation_interval: 100,
+            nr_validation_status: String::new(),
+            cross_validation_request: None,
+            cross_validation: Arc::new(Mutex::new(None)),
+            cross_validation_status: Arc::new(Mutex::new(String::new())),
+            iteration_stats: IterationStats::new(),
             
             baud_rate: 9600,
 
-            newton_raphson_iter_results: Vec::new(),
-            newton_raphson_akar: None,
+            retention_policy: Arc::new(Mutex::new(RetentionPolicy::new())),
+            storage_backend,
+
+            latest_solve: None,
+
+            last_raw_value: None,
+            last_photodiode_value: None,
+            last_v_out: None,
+            latest_uncertainty: None,
+            solver_comparison: Vec::new(),
+        }
+    }
+
+    pub fn update_nr_display_data(&mut self, result: SolveResult) {
+        self.latest_solve = Some(result);
+    }
+
+    pub fn calibration_for(&self, sensor_id: &str) -> CalibrationSettings {
+        self.calibration_by_sensor.get(sensor_id).cloned().unwrap_or_else(CalibrationSettings::new)
+    }
+
+    pub fn filter_for_mut(&mut self, sensor_id: &str) -> &mut FilterChain {
+        self.filter_by_sensor.entry(sensor_id.to_string()).or_insert_with(FilterChain::new)
+    }
+
+    pub fn outlier_detector_for_mut(&mut self, sensor_id: &str) -> &mut OutlierDetector {
+        self.outlier_by_sensor.entry(sensor_id.to_string()).or_insert_with(OutlierDetector::new)
+    }
+
+    pub fn ppfd_for(&self, sensor_id: &str) -> PpfdSettings {
+        self.ppfd_by_sensor.get(sensor_id).cloned().unwrap_or_else(PpfdSettings::new)
+    }
+
+    // Profil aktif hanya dianggap berlaku selama pengaturan sensor belum diubah manual.
+    pub fn active_profile_for(&self, sensor_id: &str) -> Option<String> {
+        let profile_id = self.active_profile_by_sensor.get(sensor_id)?;
+        let profiles = self.calibration_profiles.lock().unwrap();
+        let profile = profiles.iter().find(|profile| &profile.id == profile_id)?;
+        (profile.settings == self.calibration_for(sensor_id)).then(|| profile_id.clone())
+    }
+
+    pub fn activate_profile(&mut self, profile: &CalibrationProfile) {
+        self.calibration_by_sensor.insert(profile.sensor_id.clone(), profile.settings.clone());
+        self.active_profile_by_sensor.insert(profile.sensor_id.clone(), profile.id.clone());
+    }
+
+    fn selected_calibration_mut(&mut self) -> &mut CalibrationSettings {
+        self.calibration_by_sensor.entry(self.calibration_sensor_id.clone()).or_insert_with(CalibrationSettings::new)
+    }
+
+    fn show_calibration_model(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui_h| {
+            ui_h.label(RichText::new("ID Sensor:").color(Color32::WHITE));
+            ui_h.text_edit_singleline(&mut self.calibration_sensor_id);
+        });
+        ui.add_space(5.0);
+
+        let settings = self.selected_calibration_mut();
+        Grid::new("calibration_model_grid")
+            .num_columns(2)
+            .spacing([40.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Model:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("calibration_model_combo")
+                    .selected_text(settings.kind.label())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for kind in CalibrationModelKind::ALL {
+                            ui_combo.selectable_value(&mut settings.kind, kind, kind.label());
+                        }
+                    });
+                ui_grid.end_row();
+
+                match settings.kind {
+                    CalibrationModelKind::PowerLaw => {
+                        ui_grid.label(RichText::new("Konstanta A (Power Law):").color(Color32::WHITE));
+                        let a_changed = ui_grid.add(egui::DragValue::new(&mut settings.power_law.a)
+                            .speed(0.00001) 
+                            .fixed_decimals(6)).changed(); 
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Konstanta B (Power Law):").color(Color32::WHITE));
+                        let b_changed = ui_grid.add(egui::DragValue::new(&mut settings.power_law.b)
+                            .speed(0.01) 
+                            .fixed_decimals(4)).changed(); 
+                        ui_grid.end_row();
+
+                        // Kovarians hanya berlaku untuk A dan B hasil fit.
+                        if a_changed || b_changed {
+                            settings.power_law_covariance = None;
+                        }
+                        ui_grid.label(RichText::new("Ketidakpastian A, B (1σ):").color(Color32::WHITE));
+                        ui_grid.label(match settings.power_law_covariance {
+                            Some(covariance) => format!("σA = {:.3e}, σB = {:.3e}, ρ = {:.3}",
+                                covariance[0][0].sqrt(), covariance[1][1].sqrt(),
+                                covariance[0][1] / (covariance[0][0] * covariance[1][1]).sqrt()),
+                            None => "- (isi manual, tidak ikut dipropagasi)".to_string(),
+                        });
+                        ui_grid.end_row();
+                    },
+                    CalibrationModelKind::Logarithmic => {
+                        ui_grid.label(RichText::new("Offset C (V):").color(Color32::WHITE));
+                        ui_grid.add(egui::DragValue::new(&mut settings.logarithmic.offset)
+                            .speed(0.001)
+                            .fixed_decimals(4));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Kemiringan D (V per ln lux):").color(Color32::WHITE));
+                        ui_grid.add(egui::DragValue::new(&mut settings.logarithmic.slope)
+                            .speed(0.001)
+                            .fixed_decimals(4));
+                        ui_grid.end_row();
+                    },
+                    CalibrationModelKind::Polynomial => {
+                        let mut degree = settings.polynomial.coefficients.len().saturating_sub(1);
+                        ui_grid.label(RichText::new("Derajat Polinomial:").color(Color32::WHITE));
+                        if ui_grid.add(egui::DragValue::new(&mut degree).speed(0.1).range(1..=8)).changed() {
+                            settings.polynomial.coefficients.resize(degree + 1, 0.0);
+                        }
+                        ui_grid.end_row();
+
+                        for (i, coefficient) in settings.polynomial.coefficients.iter_mut().enumerate() {
+                            ui_grid.label(RichText::new(format!("c{} (lux^{}):", i, i)).color(Color32::WHITE));
+                            ui_grid.add(egui::DragValue::new(coefficient)
+                                .speed(1e-6)
+                                .min_decimals(6)
+                                .max_decimals(12));
+                            ui_grid.end_row();
+                        }
+                    },
+                    CalibrationModelKind::Piecewise => {
+                        let mut remove_index = None;
+                        for (i, (lux, voltage)) in settings.piecewise.points.iter_mut().enumerate() {
+                            ui_grid.horizontal(|ui_h| {
+                                if ui_h.small_button("✖").on_hover_text("Hapus titik").clicked() {
+                                    remove_index = Some(i);
+                                }
+                                ui_h.label(RichText::new(format!("Titik {}:", i + 1)).color(Color32::WHITE));
+                            });
+                            ui_grid.horizontal(|ui_h| {
+                                ui_h.add(egui::DragValue::new(lux).speed(10.0).suffix(" lux").range(0.0..=f64::MAX));
+                                ui_h.add(egui::DragValue::new(voltage).speed(0.001).suffix(" V").fixed_decimals(4));
+                            });
+                            ui_grid.end_row();
+                        }
+                        if let Some(i) = remove_index {
+                            settings.piecewise.points.remove(i);
+                        }
+
+                        ui_grid.label("");
+                        if ui_grid.button("Tambah Titik").clicked() {
+                            let next = settings.piecewise.points.last().map(|(lux, voltage)| (lux + 1000.0, *voltage)).unwrap_or((0.0, 0.0));
+                            settings.piecewise.points.push(next);
+                        }
+                        ui_grid.end_row();
+                    }
+                }
+            });
+
+        if settings.kind == CalibrationModelKind::Piecewise {
+            settings.piecewise.points.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let monotonic = settings.piecewise.points.windows(2).all(|w| w[1].0 > w[0].0 && w[1].1 > w[0].1);
+            if settings.piecewise.points.len() < 2 || !monotonic {
+                ui.label(RichText::new("Tabel butuh minimal 2 titik dengan lux dan tegangan yang naik terus.").color(Color32::RED).italics());
+            }
+        }
+        ui.add_space(10.0);
+        ui.label(RichText::new(format!("Model aktif: {}", settings.describe())).color(Color32::WHITE));
+        ui.label(RichText::new("Setiap sensor dapat memakai model berbeda; pilih model yang paling cocok dengan data kalibrasi Anda.").color(Color32::GRAY).italics());
+        ui.label(RichText::new("Ini akan mempengaruhi akurasi hasil Lux dari Newton-Raphson.").color(Color32::RED).italics());
+    }
+
+    fn show_adc_settings(&mut self, ui: &mut Ui) {
+        ui.label(RichText::new(format!("Sensor: {}", self.calibration_sensor_id)).color(Color32::WHITE));
+        ui.add_space(5.0);
+
+        let adc = &mut self.selected_calibration_mut().adc;
+        Grid::new("adc_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Resolusi ADC:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut adc.resolution_bits)
+                    .speed(0.1)
+                    .range(AdcSettings::MIN_BITS..=AdcSettings::MAX_BITS)
+                    .suffix(" bit"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Skala Penuh Nilai Mentah:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    let mut scaled = adc.full_scale.is_some();
+                    if ui_h.checkbox(&mut scaled, "Diskalakan firmware").changed() {
+                        adc.full_scale = scaled.then_some(AdcSettings::FIRMWARE_FULL_SCALE);
+                    }
+                    if let Some(full_scale) = adc.full_scale.as_mut() {
+                        ui_h.add(egui::DragValue::new(full_scale)
+                            .speed(1.0)
+                            .range(1.0..=1_000_000.0)
+                            .prefix("0-"));
+                    }
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Tegangan Referensi:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut adc.reference_voltage)
+                    .speed(0.01)
+                    .range(0.1..=24.0)
+                    .suffix(" V"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Offset (hitungan ADC):").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut adc.offset).speed(0.5));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Gain:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut adc.gain)
+                    .speed(0.001)
+                    .range(0.001..=1000.0)
+                    .fixed_decimals(3));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Toleransi Vref (1σ):").color(Color32::WHITE));
+                let mut tolerance_percent = adc.reference_tolerance * 100.0;
+                if ui_grid.add(egui::DragValue::new(&mut tolerance_percent)
+                    .speed(0.05)
+                    .range(0.0..=20.0)
+                    .suffix(" %")).changed() {
+                    adc.reference_tolerance = tolerance_percent / 100.0;
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Polaritas:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("adc_polarity_combo")
+                    .selected_text(adc.polarity.label())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for polarity in AdcPolarity::ALL {
+                            ui_combo.selectable_value(&mut adc.polarity, polarity, polarity.label());
+                        }
+                    });
+                ui_grid.end_row();
+            });
+        ui.add_space(10.0);
+        ui.label(RichText::new(format!("Rentang nilai mentah 0-{}; V_out = (nilai - offset) · gain · Vref / {}{}.",
+            adc.max_value(), adc.max_value(),
+            if adc.polarity == AdcPolarity::DarkHigh { ", dibalik agar naik seiring cahaya" } else { "" })).color(Color32::GRAY).italics());
+        if adc.full_scale.is_none() {
+            ui.label(RichText::new("Tanpa skala firmware, skala penuh = 2^bit - 1. Firmware bawaan mengirim 0-1000, jadi lux akan bergeser bila sensor memakai firmware itu.").color(Color32::YELLOW).italics());
+        }
+        ui.label(RichText::new("Pengaturan ini ikut tersimpan di profil kalibrasi dan dipakai konversi lux serta status cahaya di layar utama.").color(Color32::GRAY).italics());
+
+        if let Some(uncertainty) = self.latest_uncertainty {
+            ui.add_space(10.0);
+            ui.label(RichText::new("Ketidakpastian Lux Pembacaan Terakhir").color(Color32::WHITE).strong());
+            Grid::new("lux_uncertainty_grid")
+                .num_columns(2)
+                .spacing([40.0, 6.0])
+                .show(ui, |ui_grid| {
+                    let rows = [
+                        ("Kuantisasi ADC (±½ LSB):", uncertainty.quantization),
+                        ("Toleransi Vref:", uncertainty.reference_voltage),
+                        ("Parameter Kalibrasi A, B:", uncertainty.calibration),
+                        ("Gabungan (1σ):", uncertainty.std_dev()),
+                    ];
+                    for (label, value) in rows {
+                        ui_grid.label(RichText::new(label).color(Color32::WHITE));
+                        ui_grid.label(format!("{:.3} Lux", value));
+                        ui_grid.end_row();
+                    }
+
+                    let interval = uncertainty.interval();
+                    ui_grid.label(RichText::new(format!("Interval 95% (k = {}):", COVERAGE_FACTOR)).color(Color32::WHITE));
+                    ui_grid.label(format!("{:.2} – {:.2} Lux (nilai {:.2} Lux)", interval.lower, interval.upper, uncertainty.lux));
+                    ui_grid.end_row();
+                });
+        }
+    }
+
+    fn show_filters(&mut self, ui: &mut Ui) {
+        ui.label(RichText::new(format!("Sensor: {}", self.calibration_sensor_id)).color(Color32::WHITE));
+        ui.add_space(5.0);
+
+        let sensor_id = self.calibration_sensor_id.clone();
+        let add_kind = &mut self.filter_add_kind;
+        let chain = self.filter_by_sensor.entry(sensor_id).or_insert_with(FilterChain::new);
+        let before = chain.clone();
+        ui.checkbox(&mut chain.enabled, "Aktifkan filter sebelum konversi lux");
+        ui.add_space(5.0);
+
+        let mut remove = None;
+        let mut move_up = None;
+        Grid::new("filter_chain_grid")
+            .num_columns(4)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui_grid| {
+                for (i, stage) in chain.stages.iter_mut().enumerate() {
+                    ui_grid.label(format!("{}.", i + 1));
+                    egui::ComboBox::from_id_salt(("filter_kind_combo", i))
+                        .selected_text(stage.kind.label())
+                        .show_ui(ui_grid, |ui_combo| {
+                            for kind in FilterKind::ALL {
+                                ui_combo.selectable_value(&mut stage.kind, kind, kind.label());
+                            }
+                        });
+                    ui_grid.horizontal(|ui_h| match stage.kind {
+                        FilterKind::MovingAverage | FilterKind::Median => {
+                            ui_h.add(egui::DragValue::new(&mut stage.window)
+                                .speed(0.2)
+                                .range(1..=FilterStage::MAX_WINDOW)
+                                .prefix("jendela ")
+                                .suffix(" sampel"));
+                        },
+                        FilterKind::Exponential => {
+                            ui_h.add(egui::DragValue::new(&mut stage.alpha)
+                                .speed(0.01)
+                                .range(0.01..=1.0)
+                                .prefix("α = "));
+                        },
+                        FilterKind::Kalman => {
+                            ui_h.add(egui::DragValue::new(&mut stage.process_noise)
+                                .speed(0.1)
+                                .range(0.0..=10_000.0)
+                                .prefix("Q = "));
+                            ui_h.add(egui::DragValue::new(&mut stage.measurement_noise)
+                                .speed(0.5)
+                                .range(0.001..=100_000.0)
+                                .prefix("R = "));
+                        },
+                    });
+                    ui_grid.horizontal(|ui_h| {
+                        if i > 0 && ui_h.small_button("⬆").clicked() {
+                            move_up = Some(i);
+                        }
+                        if ui_h.small_button("Hapus").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    ui_grid.end_row();
+                }
+            });
+        if let Some(i) = move_up {
+            chain.stages.swap(i - 1, i);
+        }
+        if let Some(i) = remove {
+            chain.stages.remove(i);
+        }
+
+        ui.horizontal(|ui_h| {
+            egui::ComboBox::from_id_salt("filter_add_combo")
+                .selected_text(add_kind.label())
+                .show_ui(ui_h, |ui_combo| {
+                    for kind in FilterKind::ALL {
+                        ui_combo.selectable_value(add_kind, kind, kind.label());
+                    }
+                });
+            if ui_h.button("Tambah Tahap").clicked() {
+                chain.stages.push(FilterStage::new(*add_kind));
+            }
+        });
+
+        // Keadaan filter lama tidak berlaku lagi setelah susunan atau parameter berubah.
+        if *chain != before {
+            chain.reset();
+        }
+        ui.label(RichText::new(format!("Rantai aktif: {}", chain.describe())).color(Color32::WHITE));
+
+        ui.add_space(10.0);
+        if let (Some(raw), Some(filtered)) = (self.last_raw_value, self.last_photodiode_value) {
+            ui.label(RichText::new(format!("Nilai terakhir: mentah {:.2} → terfilter {:.2}", raw, filtered)).color(Color32::WHITE));
+        }
+        ui.label(RichText::new("Nilai mentah tetap disimpan ke database; hasil lux dihitung dari nilai terfilter. Hitung ulang riwayat memakai nilai mentah tanpa filter.").color(Color32::GRAY).italics());
+    }
+
+    fn show_outlier_settings(&mut self, ui: &mut Ui) {
+        ui.label(RichText::new(format!("Sensor: {}", self.calibration_sensor_id)).color(Color32::WHITE));
+        ui.add_space(5.0);
+
+        let detector = self.outlier_by_sensor.entry(self.calibration_sensor_id.clone()).or_insert_with(OutlierDetector::new);
+        let before = detector.settings.clone();
+        let settings = &mut detector.settings;
+        Grid::new("outlier_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 8.0])
+            .show(ui, |ui_grid| {
+                ui_grid.checkbox(&mut settings.hampel_enabled, "Filter Hampel (median ± k·MAD)");
+                ui_grid.add(egui::DragValue::new(&mut settings.hampel_threshold)
+                    .speed(0.1)
+                    .range(1.0..=10.0)
+                    .prefix("k = "));
+                ui_grid.end_row();
+
+                ui_grid.checkbox(&mut settings.zscore_enabled, "Z-Score Jendela Bergulir");
+                ui_grid.add(egui::DragValue::new(&mut settings.zscore_threshold)
+                    .speed(0.1)
+                    .range(1.0..=10.0)
+                    .prefix("z = "));
+                ui_grid.end_row();
+
+                ui_grid.checkbox(&mut settings.rate_enabled, "Batas Laju Perubahan");
+                ui_grid.add(egui::DragValue::new(&mut settings.max_rate)
+                    .speed(1.0)
+                    .range(1.0..=100_000.0)
+                    .suffix(" hitungan/detik"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Jendela Acuan:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.window)
+                    .speed(0.2)
+                    .range(OutlierSettings::MIN_WINDOW..=OutlierSettings::MAX_WINDOW)
+                    .suffix(" sampel"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Penyimpangan Minimum:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.min_deviation)
+                    .speed(0.5)
+                    .range(0.0..=10_000.0)
+                    .suffix(" hitungan"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Level Baru Setelah:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut settings.persistence)
+                    .speed(0.2)
+                    .range(OutlierSettings::MIN_PERSISTENCE..=100)
+                    .suffix(" sampel berturut-turut"));
+                ui_grid.end_row();
+            });
+        if detector.settings != before {
+            detector.reset();
+        }
+
+        ui.add_space(10.0);
+        if detector.checked_count > 0 {
+            ui.label(RichText::new(format!("{} dari {} sampel sesi ini ditandai ({:.1}%).",
+                detector.flagged_count, detector.checked_count,
+                100.0 * detector.flagged_count as f64 / detector.checked_count as f64)).color(Color32::WHITE));
+        }
+        ui.label(RichText::new("Sampel yang ditandai tetap disimpan beserta tandanya dan diberi tanda silang merah di grafik. Penyimpangan yang bertahan dianggap perubahan cahaya sungguhan.").color(Color32::GRAY).italics());
+    }
+
+    fn show_ppfd_settings(&mut self, ui: &mut Ui) {
+        ui.label(RichText::new(format!("Sensor: {}", self.calibration_sensor_id)).color(Color32::WHITE));
+        ui.add_space(5.0);
+
+        let ppfd = self.ppfd_by_sensor.entry(self.calibration_sensor_id.clone()).or_insert_with(PpfdSettings::new);
+        Grid::new("ppfd_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Sumber Cahaya:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("ppfd_source_combo")
+                    .selected_text(ppfd.source.label())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for source in LightSource::ALL {
+                            ui_combo.selectable_value(&mut ppfd.source, source, source.label());
+                        }
+                    });
+                ui_grid.end_row();
+
+                if ppfd.source == LightSource::RedBlueLed {
+                    ui_grid.label(RichText::new("Porsi Daya Merah (660 nm):").color(Color32::WHITE));
+                    let mut red_percent = ppfd.red_fraction * 100.0;
+                    if ui_grid.add(egui::Slider::new(&mut red_percent, 0.0..=100.0).suffix(" %")).changed() {
+                        ppfd.red_fraction = red_percent / 100.0;
+                    }
+                    ui_grid.end_row();
+                }
+
+                ui_grid.label(RichText::new("Faktor Manual:").color(Color32::WHITE));
+                ui_grid.horizontal(|ui_h| {
+                    let mut use_override = ppfd.factor_override.is_some();
+                    if ui_h.checkbox(&mut use_override, "Pakai faktor dari quantum sensor").changed() {
+                        ppfd.factor_override = use_override.then(|| ppfd.spectral_factor());
+                    }
+                    if let Some(factor) = ppfd.factor_override.as_mut() {
+                        ui_h.add(egui::DragValue::new(factor)
+                            .speed(0.0001)
+                            .range(0.0001..=1.0)
+                            .fixed_decimals(5));
+                    }
+                });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Faktor Konversi:").color(Color32::WHITE));
+                ui_grid.label(format!("{:.5} µmol/m²/s per Lux (spektrum: {:.5})", ppfd.factor(), ppfd.spectral_factor()));
+                ui_grid.end_row();
+            });
+
+        ui.add_space(10.0);
+        if let Some(lux) = self.latest_uncertainty.map(|u| u.lux) {
+            let ppfd = self.ppfd_for(&self.calibration_sensor_id);
+            ui.label(RichText::new(format!("Pembacaan terakhir: {:.2} Lux ≈ {:.1} µmol/m²/s", lux, ppfd.lux_to_ppfd(lux))).color(Color32::WHITE));
+        }
+        ui.label(RichText::new("Faktor dihitung dari spektrum model sumber cahaya (foton 400–700 nm dibanding respons mata). PPFD ikut tersimpan bersama hasil lux.").color(Color32::GRAY).italics());
+    }
+
+    fn show_calibration_fit(&mut self, ui: &mut Ui) {
+        let adc = self.calibration_for(&self.calibration_sensor_id).adc;
+        Grid::new("calibration_input_grid")
+            .num_columns(3)
+            .spacing([20.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Nilai Photodiode:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.calibration_photodiode_input)
+                    .speed(1.0)
+                    .range(0.0..=adc.max_value()));
+                if ui_grid.add_enabled(self.last_photodiode_value.is_some(), egui::Button::new("Pakai Pembacaan Terakhir")).clicked() {
+                    self.calibration_photodiode_input = self.last_photodiode_value.unwrap_or_default();
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Lux Meter Referensi:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.calibration_reference_lux)
+                    .speed(1.0)
+                    .suffix(" lux")
+                    .range(0.0..=200000.0));
+                if ui_grid.button("Catat Pasangan").clicked() {
+                    self.calibration_points.push(CalibrationPoint {
+                        photodiode_value: self.calibration_photodiode_input,
+                        voltage: adc.to_voltage(self.calibration_photodiode_input),
+                        reference_lux: self.calibration_reference_lux,
+                    });
+                    self.calibration_fit = None;
+                }
+                ui_grid.end_row();
+            });
+        ui.label(RichText::new("Letakkan lux meter di samping sensor, catat beberapa pasangan pada intensitas cahaya berbeda (minimal 3).").color(Color32::GRAY).italics());
+        ui.add_space(10.0);
+
+        if self.calibration_points.is_empty() {
+            ui.label(RichText::new("Belum ada titik kalibrasi.").color(Color32::GRAY));
+            return;
+        }
+
+        let residuals = match &self.calibration_fit {
+            Some(Ok(fit)) => Some(fit.residuals.clone()),
+            _ => None,
+        };
+        let mut remove_index = None;
+        Grid::new("calibration_points_grid")
+            .num_columns(5)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui_grid| {
+                for header in ["No", "Photodiode", "V_out (V)", "Lux Referensi", "Residual (V)"] {
+                    ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                }
+                ui_grid.end_row();
+
+                for (i, point) in self.calibration_points.iter().enumerate() {
+                    ui_grid.horizontal(|ui_h| {
+                        if ui_h.small_button("✖").on_hover_text("Hapus titik").clicked() {
+                            remove_index = Some(i);
+                        }
+                        ui_h.label(format!("{}", i + 1));
+                    });
+                    ui_grid.label(format!("{:.2}", point.photodiode_value));
+                    ui_grid.label(format!("{:.4}", point.voltage));
+                    ui_grid.label(format!("{:.2}", point.reference_lux));
+                    ui_grid.label(residuals.as_ref()
+                        .and_then(|r| r.get(i))
+                        .map(|r| format!("{:+.5}", r))
+                        .unwrap_or_else(|| "-".to_string()));
+                    ui_grid.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.calibration_points.remove(i);
+            self.calibration_fit = None;
+        }
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui_h| {
+            if ui_h.button("Hitung Fit Power Law (Levenberg–Marquardt)").clicked() {
+                self.calibration_fit = Some(calibration::fit_power_law(&self.calibration_points));
+            }
+            if ui_h.button("Hapus Semua Titik").clicked() {
+                self.calibration_points.clear();
+                self.calibration_fit = None;
+            }
+        });
+
+        match &self.calibration_fit {
+            None => {},
+            Some(Err(e)) => {
+                ui.label(RichText::new(format!("GAGAL: {}", e)).color(Color32::RED));
+            },
+            Some(Ok(fit)) => {
+                let fit = fit.clone();
+                ui.add_space(10.0);
+                Grid::new("calibration_fit_grid")
+                    .num_columns(2)
+                    .spacing([40.0, 6.0])
+                    .show(ui, |ui_grid| {
+                        ui_grid.label(RichText::new("A Hasil Fit:").color(Color32::WHITE));
+                        ui_grid.label(format!("{:.8}", fit.calib_a));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("B Hasil Fit:").color(Color32::WHITE));
+                        ui_grid.label(format!("{:.5}", fit.calib_b));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("R²:").color(Color32::WHITE));
+                        ui_grid.label(format!("{:.6}", fit.r_squared));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("RMSE:").color(Color32::WHITE));
+                        ui_grid.label(format!("{:.5} V", fit.rmse));
+                        ui_grid.end_row();
+
+                        ui_grid.label(RichText::new("Iterasi:").color(Color32::WHITE));
+                        let iteration_text = if fit.converged {
+                            format!("{} (konvergen)", fit.iterations)
+                        } else {
+                            format!("{} (belum konvergen)", fit.iterations)
+                        };
+                        ui_grid.label(iteration_text);
+                        ui_grid.end_row();
+                    });
+
+                let measured: Vec<[f64; 2]> = self.calibration_points.iter()
+                    .map(|p| [p.reference_lux, p.voltage])
+                    .collect();
+                let max_lux = self.calibration_points.iter().map(|p| p.reference_lux).fold(1.0, f64::max);
+                Plot::new("calibration_fit_plot")
+                    .width(ui.available_width())
+                    .height(220.0)
+                    .include_x(0.0)
+                    .include_y(0.0)
+                    .legend(Legend::default())
+                    .x_axis_label("Lux")
+                    .y_axis_label("V_out (V)")
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new("Fit A·lux^B", PlotPoints::from(calibration::fit_curve(&fit, max_lux * 1.1, 200)))
+                            .color(Color32::LIGHT_BLUE)
+                            .width(2.0));
+                        plot_ui.points(Points::new("Titik Kalibrasi", PlotPoints::from(measured))
+                            .color(Color32::YELLOW)
+                            .radius(4.0));
+                    });
+
+                if ui.button(format!("Terapkan A dan B ke Sensor {}", self.calibration_sensor_id)).clicked() {
+                    let settings = self.selected_calibration_mut();
+                    settings.kind = CalibrationModelKind::PowerLaw;
+                    settings.power_law = PowerLawModel { a: fit.calib_a, b: fit.calib_b };
+                    settings.power_law_covariance = fit.covariance;
+                }
+            }
+        }
+    }
+
+    fn show_calibration_profiles(&mut self, ui: &mut Ui) {
+        let sensor_id = self.calibration_sensor_id.clone();
+        let fit_statistics = match &self.calibration_fit {
+            Some(Ok(fit)) => Some(FitStatistics {
+                r_squared: fit.r_squared,
+                rmse: fit.rmse,
+                point_count: self.calibration_points.len(),
+            }),
+            _ => None,
+        };
+
+        Grid::new("calibration_profile_form_grid")
+            .num_columns(2)
+            .spacing([40.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Nama Profil:").color(Color32::WHITE));
+                ui_grid.text_edit_singleline(&mut self.profile_name_input);
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Lux Meter Referensi:").color(Color32::WHITE));
+                ui_grid.text_edit_singleline(&mut self.profile_reference_instrument);
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Catatan:").color(Color32::WHITE));
+                ui_grid.text_edit_multiline(&mut self.profile_notes);
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Statistik Fit:").color(Color32::WHITE));
+                ui_grid.label(match &fit_statistics {
+                    Some(stats) => format!("R² {:.6}, RMSE {:.5} V, {} titik", stats.r_squared, stats.rmse, stats.point_count),
+                    None => "-".to_string(),
+                });
+                ui_grid.end_row();
+            });
+
+        let name = self.profile_name_input.trim().to_string();
+        let can_save = !name.is_empty() && !name.contains('/') && self.profile_save_request.is_none();
+        if ui.add_enabled(can_save, egui::Button::new(format!("Simpan Model Sensor {} sebagai Profil", sensor_id))).clicked() {
+            let settings = self.calibration_for(&sensor_id);
+            let mut profile = CalibrationProfile::new(&name, &sensor_id, settings, &self.calibration_profiles.lock().unwrap());
+            profile.reference_instrument = self.profile_reference_instrument.trim().to_string();
+            profile.notes = self.profile_notes.trim().to_string();
+            profile.fit = fit_statistics;
+            // Berlaku setelah main.rs menambahkannya ke daftar profil (lihat `active_profile_for`).
+            self.active_profile_by_sensor.insert(sensor_id.clone(), profile.id.clone());
+            self.profile_save_request = Some(profile);
+        }
+        if name.contains('/') {
+            ui.label(RichText::new("Nama profil tidak boleh mengandung '/'.").color(Color32::RED).italics());
+        }
+        let status = self.profile_status.lock().unwrap().clone();
+        if !status.is_empty() {
+            ui.label(RichText::new(status).color(Color32::WHITE));
+        }
+        ui.add_space(10.0);
+
+        let active_id = self.active_profile_for(&sensor_id);
+        match &active_id {
+            Some(id) => ui.label(RichText::new(format!("Profil aktif sensor {}: {}", sensor_id, id)).color(Color32::LIGHT_GREEN)),
+            None => ui.label(RichText::new(format!("Sensor {} memakai pengaturan manual (tanpa profil).", sensor_id)).color(Color32::GRAY)),
+        };
+
+        let profiles: Vec<CalibrationProfile> = self.calibration_profiles.lock().unwrap().iter()
+            .filter(|profile| profile.sensor_id == sensor_id)
+            .cloned()
+            .collect();
+        if profiles.is_empty() {
+            ui.label(RichText::new("Belum ada profil tersimpan untuk sensor ini.").color(Color32::GRAY));
+            return;
+        }
+
+        let mut activate = None;
+        Grid::new("calibration_profiles_grid")
+            .num_columns(6)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui_grid| {
+                for header in ["Profil", "Dibuat", "Model", "Referensi", "R² / RMSE", ""] {
+                    ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                }
+                ui_grid.end_row();
+
+                for profile in &profiles {
+                    let label = RichText::new(format!("{} v{}", profile.name, profile.version));
+                    let label = if active_id.as_ref() == Some(&profile.id) { label.color(Color32::LIGHT_GREEN).strong() } else { label };
+                    ui_grid.label(label).on_hover_text(&profile.notes);
+                    ui_grid.label(profile.created_at.format("%Y-%m-%d %H:%M").to_string());
+                    ui_grid.label(profile.settings.describe()).on_hover_text(profile.settings.adc.describe());
+                    ui_grid.label(&profile.reference_instrument);
+                    ui_grid.label(profile.fit.as_ref()
+                        .map(|fit| format!("{:.5} / {:.4} V", fit.r_squared, fit.rmse))
+                        .unwrap_or_else(|| "-".to_string()));
+                    if ui_grid.button("Aktifkan").clicked() {
+                        activate = Some(profile.clone());
+                    }
+                    ui_grid.end_row();
+                }
+            });
+
+        if let Some(profile) = activate {
+            self.activate_profile(&profile);
+            self.profile_activation_request = Some((profile.sensor_id.clone(), profile.id.clone()));
+        }
+        ui.label(RichText::new("Profil tidak pernah diubah; menyimpan dengan nama yang sama membuat versi baru. Setiap hasil lux mencatat ID profil yang aktif.").color(Color32::GRAY).italics());
+    }
+
+    fn show_beds(&mut self, ui: &mut Ui) {
+        let crops = CropProfile::all();
+        let planting_date = chrono::NaiveDate::parse_from_str(self.bed_planting_date_input.trim(), "%Y-%m-%d");
+        Grid::new("bed_form_grid")
+            .num_columns(2)
+            .spacing([40.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Nama Bedengan:").color(Color32::WHITE));
+                ui_grid.text_edit_singleline(&mut self.bed_name_input);
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Tanaman:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("bed_crop_combo")
+                    .selected_text(&self.bed_crop)
+                    .show_ui(ui_grid, |ui_combo| {
+                        for crop in &crops {
+                            ui_combo.selectable_value(&mut self.bed_crop, crop.name.to_string(), format!("{} ({})", crop.name, crop.scientific_name));
+                        }
+                    });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Tanggal Tanam (YYYY-MM-DD):").color(Color32::WHITE));
+                ui_grid.text_edit_singleline(&mut self.bed_planting_date_input);
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Sensor:").color(Color32::WHITE));
+                ui_grid.label(&self.calibration_sensor_id);
+                ui_grid.end_row();
+            });
+
+        let name = self.bed_name_input.trim().to_string();
+        let can_save = !name.is_empty() && planting_date.is_ok() && self.bed_save_request.is_none();
+        if ui.add_enabled(can_save, egui::Button::new("Simpan Bedengan")).clicked() {
+            if let Ok(planting_date) = planting_date {
+                self.bed_save_request = Some(Bed {
+                    id: name,
+                    sensor_id: self.calibration_sensor_id.clone(),
+                    crop: self.bed_crop.clone(),
+                    planting_date,
+                });
+            }
+        }
+        if planting_date.is_err() {
+            ui.label(RichText::new("Tanggal tanam tidak valid, gunakan format YYYY-MM-DD.").color(Color32::RED).italics());
+        }
+        let status = self.bed_status.lock().unwrap().clone();
+        if !status.is_empty() {
+            ui.label(RichText::new(status).color(Color32::WHITE));
+        }
+        ui.add_space(10.0);
+
+        let today = chrono::Local::now().date_naive();
+        let beds = self.beds.lock().unwrap().clone();
+        if beds.is_empty() {
+            ui.label(RichText::new("Belum ada bedengan. Layar utama memakai klasifikasi cahaya umum sampai bedengan disimpan.").color(Color32::GRAY));
+        } else {
+            let mut edit = None;
+            Grid::new("beds_grid")
+                .num_columns(7)
+                .striped(true)
+                .spacing([20.0, 6.0])
+                .show(ui, |ui_grid| {
+                    for header in ["Bedengan", "Sensor", "Tanaman", "Tanggal Tanam", "HST", "Tahap", ""] {
+                        ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                    }
+                    ui_grid.end_row();
+
+                    for bed in &beds {
+                        let days = bed.days_after_planting(today);
+                        ui_grid.label(&bed.id);
+                        ui_grid.label(&bed.sensor_id);
+                        ui_grid.label(&bed.crop);
+                        ui_grid.label(bed.planting_date.to_string());
+                        ui_grid.label(days.to_string());
+                        ui_grid.label(bed.crop_profile().map(|crop| crop.stage_for(days).stage.label()).unwrap_or("-"));
+                        if ui_grid.button("Ubah").clicked() {
+                            edit = Some(bed.clone());
+                        }
+                        ui_grid.end_row();
+                    }
+                });
+            if let Some(bed) = edit {
+                self.bed_name_input = bed.id;
+                self.bed_crop = bed.crop;
+                self.bed_planting_date_input = bed.planting_date.to_string();
+            }
+        }
+
+        if let Some(crop) = CropProfile::find(&self.bed_crop) {
+            ui.add_space(10.0);
+            ui.label(RichText::new(format!("Target Cahaya {} ({})", crop.name, crop.scientific_name)).color(Color32::WHITE).strong());
+            Grid::new("crop_targets_grid")
+                .num_columns(6)
+                .striped(true)
+                .spacing([20.0, 6.0])
+                .show(ui, |ui_grid| {
+                    for header in ["Tahap", "Mulai (HST)", "Lux", "PPFD", "DLI", "Fotoperiode"] {
+                        ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                    }
+                    ui_grid.end_row();
+
+                    for stage in &crop.stages {
+                        ui_grid.label(stage.stage.label());
+                        ui_grid.label(stage.start_day.to_string());
+                        ui_grid.label(stage.lux.describe("Lux"));
+                        ui_grid.label(stage.ppfd.describe("µmol/m²/s"));
+                        ui_grid.label(stage.dli.describe("mol/m²/hari"));
+                        ui_grid.label(stage.photoperiod_hours.describe("jam"));
+                        ui_grid.end_row();
+                    }
+                });
         }
+        ui.label(RichText::new("HST = hari setelah tanam. Menyimpan dengan nama bedengan yang sama mengganti tanggal tanamnya.").color(Color32::GRAY).italics());
     }
 
-    pub fn update_nr_display_data(&mut self, akar: f64, history: Vec<f64>) {
-        self.newton_raphson_akar = Some(akar);
-        self.newton_raphson_iter_results.clear();
-        for (i, val) in history.iter().enumerate() {
-            self.newton_raphson_iter_results.push(Value { x: i as f64, y: *val });
+    fn show_recompute(&mut self, ui: &mut Ui) {
+        let sensor_id = self.calibration_sensor_id.clone();
+        let profiles: Vec<CalibrationProfile> = self.calibration_profiles.lock().unwrap().iter()
+            .filter(|profile| profile.sensor_id == sensor_id)
+            .cloned()
+            .collect();
+        if profiles.is_empty() {
+            ui.label(RichText::new(format!("Simpan profil kalibrasi untuk sensor {} terlebih dahulu.", sensor_id)).color(Color32::GRAY));
+            return;
+        }
+        if !profiles.iter().any(|profile| Some(&profile.id) == self.recompute_profile_id.as_ref()) {
+            self.recompute_profile_id = self.active_profile_for(&sensor_id).or_else(|| profiles.last().map(|profile| profile.id.clone()));
+        }
+
+        Grid::new("recompute_grid")
+            .num_columns(2)
+            .spacing([40.0, 10.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Profil Kalibrasi:").color(Color32::WHITE));
+                egui::ComboBox::from_id_salt("recompute_profile_combo")
+                    .selected_text(self.recompute_profile_id.clone().unwrap_or_default())
+                    .show_ui(ui_grid, |ui_combo| {
+                        for profile in &profiles {
+                            ui_combo.selectable_value(&mut self.recompute_profile_id, Some(profile.id.clone()), &profile.id);
+                        }
+                    });
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Rentang Data:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.recompute_days)
+                    .speed(1.0)
+                    .range(0..=3650)
+                    .custom_formatter(|days, _| if days == 0.0 { "Semua riwayat".to_string() } else { format!("{} hari terakhir", days) }));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Metode Konversi:").color(Color32::WHITE));
+                let method = if self.conversion_mode == ConversionMode::NewtonRaphson {
+                    format!("{} ({})", self.conversion_mode.label(), self.solver_method.label())
+                } else {
+                    self.conversion_mode.label().to_string()
+                };
+                ui_grid.label(method);
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Filter Nilai Mentah:").color(Color32::WHITE));
+                ui_grid.label(self.filter_for_mut(&sensor_id).describe());
+                ui_grid.end_row();
+            });
+
+        let ppfd = self.ppfd_for(&sensor_id);
+        let filter = self.filter_for_mut(&sensor_id).clone();
+        let mut progress = self.recompute_progress.lock().unwrap();
+        ui.add_space(10.0);
+        ui.horizontal(|ui_h| {
+            let can_start = !progress.running && self.recompute_request.is_none();
+            if ui_h.add_enabled(can_start, egui::Button::new("Hitung Ulang Lux")).clicked() {
+                let profile = profiles.iter().find(|profile| Some(&profile.id) == self.recompute_profile_id.as_ref());
+                if let Some(profile) = profile {
+                    self.recompute_request = Some(RecomputeRequest {
+                        sensor_id: sensor_id.clone(),
+                        from: (self.recompute_days > 0).then(|| chrono::Utc::now() - chrono::Duration::days(self.recompute_days as i64)),
+                        to: None,
+                        profile: profile.clone(),
+                        ppfd: ppfd.clone(),
+                        filter: filter.clone(),
+                        mode: self.conversion_mode,
+                        solver_method: self.solver_method,
+                        tolerance: self.tolerance_nr,
+                        max_iterations: self.max_iterations_nr as usize,
+                    });
+                }
+            }
+            if ui_h.add_enabled(progress.running && !progress.cancel_requested, egui::Button::new("Batalkan")).clicked() {
+                progress.cancel_requested = true;
+            }
+        });
+
+        if progress.running || progress.total > 0 {
+            ui.add(egui::ProgressBar::new(progress.fraction())
+                .show_percentage()
+                .animate(progress.running));
         }
+        if !progress.message.is_empty() {
+            ui.label(RichText::new(&progress.message).color(Color32::WHITE));
+        }
+        ui.label(RichText::new("Hasil lama tidak ditimpa. Set hasil baru dapat dibandingkan dengan data lama di Riwayat Data Tersimpan pada layar grafik.").color(Color32::GRAY).italics());
     }
 
     pub fn show(&mut self, ui: &mut Ui) {
@@ -55,28 +1120,81 @@ impl SensorConfigurationScreen {
 
                 ui.group(|ui| {
                     ui.add_space(5.0);
-                    ui.heading(RichText::new("Kalibrasi Sensor Photodiode (Model Power Law)").color(Color32::LIGHT_GREEN).strong());
+                    ui.heading(RichText::new("Model Kalibrasi Sensor Photodiode").color(Color32::LIGHT_GREEN).strong());
                     ui.add_space(10.0);
+                    self.show_calibration_model(ui);
+                });
 
-                    Grid::new("calibration_power_law_grid")
-                        .num_columns(2)
-                        .spacing([40.0, 10.0])
-                        .show(ui, |ui_grid| {
-                            ui_grid.label(RichText::new("Konstanta A (Power Law):").color(Color32::WHITE));
-                            ui_grid.add(egui::DragValue::new(&mut self.calib_a_power)
-                                .speed(0.00001) 
-                                .fixed_decimals(6)); 
-                            ui_grid.end_row();
+                ui.add_space(30.0);
 
-                            ui_grid.label(RichText::new("Konstanta B (Power Law):").color(Color32::WHITE));
-                            ui_grid.add(egui::DragValue::new(&mut self.calib_b_power)
-                                .speed(0.01) 
-                                .fixed_decimals(2)); 
-                            ui_grid.end_row();
-                        });
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Pengaturan ADC & Tegangan Referensi").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_adc_settings(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Filter Sinyal Photodiode").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_filters(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Deteksi Outlier & Lonjakan").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_outlier_settings(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Konversi PPFD (Hortikultura)").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_ppfd_settings(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Bedengan & Profil Tanaman").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_beds(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Kalibrasi dari Lux Meter Referensi").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_calibration_fit(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Profil Kalibrasi").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_calibration_profiles(ui);
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Hitung Ulang Riwayat Lux").color(Color32::LIGHT_GREEN).strong());
                     ui.add_space(10.0);
-                    ui.label(RichText::new("Sesuaikan 'Konstanta A' dan 'Konstanta B' berdasarkan hasil kalibrasi Power Law Anda.").color(Color32::GRAY).italics());
-                    ui.label(RichText::new("Ini akan mempengaruhi akurasi hasil Lux dari Newton-Raphson.").color(Color32::RED).italics());
+                    self.show_recompute(ui);
                 });
 
                 ui.add_space(30.0);
@@ -105,6 +1223,48 @@ impl SensorConfigurationScreen {
 
                 ui.add_space(30.0);
 
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new(format!("Retensi Data {}", self.storage_backend.label())).color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+
+                    let mut policy = self.retention_policy.lock().unwrap();
+                    Grid::new("retention_policy_grid")
+                        .num_columns(2)
+                        .spacing([40.0, 10.0])
+                        .show(ui, |ui_grid| {
+                            ui_grid.label(RichText::new("Ringkas Data Lama per Jam:").color(Color32::WHITE));
+                            ui_grid.checkbox(&mut policy.downsampling_enabled, "Aktif");
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Simpan Data Mentah Selama:").color(Color32::WHITE));
+                            ui_grid.add_enabled(policy.downsampling_enabled, egui::DragValue::new(&mut policy.raw_retention_days)
+                                .speed(1.0)
+                                .suffix(" hari")
+                                .range(1..=3650));
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Hapus Otomatis (TTL):").color(Color32::WHITE));
+                            ui_grid.checkbox(&mut policy.ttl_enabled, "Aktif");
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Umur Maksimum Data Mentah:").color(Color32::WHITE));
+                            ui_grid.add_enabled(policy.ttl_enabled, egui::DragValue::new(&mut policy.raw_ttl_days)
+                                .speed(1.0)
+                                .suffix(" hari")
+                                .range(1..=3650));
+                            ui_grid.end_row();
+                        });
+                    ui.add_space(10.0);
+                    ui.label(RichText::new("Data mentah yang lebih lama dari batas retensi diringkas menjadi rata-rata per jam, lalu dihapus.").color(Color32::GRAY).italics());
+                    if policy.ttl_enabled && policy.downsampling_enabled && policy.raw_ttl_days <= policy.raw_retention_days {
+                        ui.label(RichText::new("Umur TTL lebih pendek dari masa retensi: data mentah akan terhapus sebelum sempat diringkas.").color(Color32::RED).italics());
+                    }
+                    ui.label(RichText::new("Perubahan diterapkan oleh proses latar belakang setiap jam.").color(Color32::GRAY).italics());
+                });
+
+                ui.add_space(30.0);
+
                 ui.group(|ui| {
                     ui.add_space(5.0);
                     ui.heading(RichText::new("Konfigurasi Metode Newton-Raphson").color(Color32::LIGHT_BLUE).strong());
@@ -114,6 +1274,16 @@ impl SensorConfigurationScreen {
                         .num_columns(2)
                         .spacing([40.0, 10.0])
                         .show(ui, |ui_grid| {
+                            ui_grid.label(RichText::new("Metode Pencarian Akar:").color(Color32::WHITE));
+                            egui::ComboBox::from_id_salt("solver_method_combo")
+                                .selected_text(self.solver_method.label())
+                                .show_ui(ui_grid, |ui_combo| {
+                                    for method in SolverMethod::ALL {
+                                        ui_combo.selectable_value(&mut self.solver_method, method, method.label());
+                                    }
+                                });
+                            ui_grid.end_row();
+
                             ui_grid.label(RichText::new("Tebakan Awal (x₀):").color(Color32::WHITE));
                             ui_grid.add(egui::DragValue::new(&mut self.initial_guess_nr)
                                 .speed(0.1));
@@ -130,38 +1300,207 @@ impl SensorConfigurationScreen {
                                 .speed(1.0)
                                 .fixed_decimals(0));
                             ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Warm Start dari Lux Terakhir:").color(Color32::WHITE));
+                            ui_grid.checkbox(&mut self.warm_start_enabled, "Aktif");
+                            ui_grid.end_row();
                         });
                     ui.add_space(10.0);
                     ui.label(RichText::new("Parameter ini mempengaruhi akurasi dan kecepatan konvergensi metode Newton-Raphson.").color(Color32::GRAY).italics());
+                    ui.label(RichText::new("Newton murni, Secant dan Halley tidak dijaga interval sehingga bisa divergen untuk tebakan awal yang buruk.").color(Color32::GRAY).italics());
+                    ui.label(RichText::new("Warm start memulai iterasi dari lux terakhir yang konvergen per sensor; bila gagal, diulang dari tebakan awal.").color(Color32::GRAY).italics());
+
+                    ui.add_space(10.0);
+                    let format_average = |average: Option<f64>, solves: usize| match average {
+                        Some(average) => format!("{:.2} iterasi ({} perhitungan)", average, solves),
+                        None => "-".to_string(),
+                    };
+                    Grid::new("iteration_stats_grid")
+                        .num_columns(2)
+                        .spacing([40.0, 6.0])
+                        .show(ui, |ui_grid| {
+                            ui_grid.label(RichText::new("Rata-rata dari Tebakan Awal:").color(Color32::WHITE));
+                            ui_grid.label(format_average(self.iteration_stats.average_cold(), self.iteration_stats.cold_solves));
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Rata-rata dengan Warm Start:").color(Color32::WHITE));
+                            ui_grid.label(format_average(self.iteration_stats.average_warm(), self.iteration_stats.warm_solves));
+                            ui_grid.end_row();
+                        });
+                    if ui.button("Reset Statistik Iterasi").clicked() {
+                        self.iteration_stats = IterationStats::new();
+                    }
                     ui.label(RichText::new("Grafik hasil perhitungan numerik dapat dilihat di halaman 'Data Graphics'.").color(Color32::GRAY).italics());
                 });
 
+                ui.add_space(30.0);
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Mode Konversi Lux").color(Color32::LIGHT_BLUE).strong());
+                    ui.add_space(10.0);
+
+                    Grid::new("conversion_mode_grid")
+                        .num_columns(2)
+                        .spacing([40.0, 10.0])
+                        .show(ui, |ui_grid| {
+                            ui_grid.label(RichText::new("Mode Konversi:").color(Color32::WHITE));
+                            egui::ComboBox::from_id_salt("conversion_mode_combo")
+                                .selected_text(self.conversion_mode.label())
+                                .show_ui(ui_grid, |ui_combo| {
+                                    for mode in ConversionMode::ALL {
+                                        ui_combo.selectable_value(&mut self.conversion_mode, mode, mode.label());
+                                    }
+                                });
+                            ui_grid.end_row();
+
+                            ui_grid.label(RichText::new("Validasi NR Setiap:").color(Color32::WHITE));
+                            ui_grid.add_enabled(self.conversion_mode != ConversionMode::NewtonRaphson,
+                                egui::DragValue::new(&mut self.nr_validation_interval)
+                                    .speed(1.0)
+                                    .suffix(" sampel")
+                                    .range(0..=10000));
+                            ui_grid.end_row();
+                        });
+                    ui.add_space(10.0);
+                    ui.label(RichText::new("Invers analitik dan LUT jauh lebih ringan dari iterasi NR; NR tetap dijalankan berkala sebagai validasi (0 = mati).").color(Color32::GRAY).italics());
+                    if !self.nr_validation_status.is_empty() {
+                        ui.label(RichText::new(&self.nr_validation_status).color(Color32::WHITE));
+                    }
+
+                    ui.add_space(10.0);
+                    if ui.button("Validasi Silang Seluruh Rentang ADC").clicked() && self.cross_validation_request.is_none() {
+                        *self.cross_validation_status.lock().unwrap() = "Validasi silang berjalan...".to_string();
+                        self.cross_validation_request = Some(CrossValidationRequest {
+                            settings: self.calibration_for(&self.calibration_sensor_id),
+                            method: self.solver_method,
+                            x0: if self.initial_guess_nr > 0.0 { self.initial_guess_nr } else { 1.0 },
+                            tol: self.tolerance_nr,
+                            max_iter: self.max_iterations_nr as usize,
+                        });
+                    }
+                    let status = self.cross_validation_status.lock().unwrap().clone();
+                    if !status.is_empty() {
+                        ui.label(RichText::new(status).color(Color32::WHITE));
+                    }
+
+                    if let Some((method, validation)) = self.cross_validation.lock().unwrap().as_ref() {
+                        ui.add_space(5.0);
+                        Grid::new("cross_validation_grid")
+                            .num_columns(4)
+                            .striped(true)
+                            .spacing([20.0, 6.0])
+                            .show(ui, |ui_grid| {
+                                for header in ["Terhadap Invers Model", "Selisih Maks (Lux)", "Selisih Relatif Maks", "Pada Nilai Photodiode"] {
+                                    ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                                }
+                                ui_grid.end_row();
+
+                                for (label, discrepancy) in [(method.label(), &validation.nr_vs_inverse), ("Tabel Lookup (LUT)", &validation.lut_vs_inverse)] {
+                                    ui_grid.label(label);
+                                    ui_grid.label(format!("{:.3e}", discrepancy.max_abs));
+                                    ui_grid.label(format!("{:.3e}", discrepancy.max_relative));
+                                    ui_grid.label(format!("{:.1}", discrepancy.at_photodiode_value));
+                                    ui_grid.end_row();
+                                }
+                            });
+                        ui.label(RichText::new(format!("{} titik diuji (setiap nilai ADC dan titik tengahnya).", validation.checked_points)).color(Color32::GRAY).italics());
+                        if !validation.failures.is_empty() {
+                            ui.label(RichText::new(format!("{} titik gagal dihitung, mis. nilai photodiode {:.1}.",
+                                validation.failures.len(), validation.failures[0])).color(Color32::RED));
+                        }
+                    }
+                });
+
                 ui.add_space(30.0);
                 ui.group(|ui| {
                     ui.add_space(5.0);
                     ui.heading(RichText::new("Riwayat Iterasi Newton-Raphson Terbaru").color(Color32::YELLOW).strong());
                     ui.add_space(10.0);
 
-                    if let Some(akar) = self.newton_raphson_akar {
-                        ui.label(RichText::new(format!("Akar Terakhir: {:.8}", akar)).color(Color32::LIGHT_GREEN).strong());
-                    } else {
-                        ui.label(RichText::new("Belum ada perhitungan Newton-Raphson.").color(Color32::GRAY));
+                    match &self.latest_solve {
+                        None => {
+                            ui.label(RichText::new("Belum ada perhitungan Newton-Raphson.").color(Color32::GRAY));
+                            ui.add_space(5.0);
+                            ui.label(RichText::new("Riwayat iterasi akan muncul di sini setelah perhitungan pertama.").color(Color32::GRAY).italics());
+                        },
+                        Some(result) => {
+                            if result.is_converged() {
+                                ui.label(RichText::new(format!("Akar Terakhir: {:.8}", result.root)).color(Color32::LIGHT_GREEN).strong());
+                            } else {
+                                ui.label(RichText::new(format!("Perhitungan terakhir GAGAL: {}", result.status)).color(Color32::RED).strong());
+                            }
+                            ui.label(RichText::new(format!("Berhenti karena: {} setelah {} iterasi, perkiraan error {:.3e}",
+                                result.status, result.iterations, result.estimated_error)).color(Color32::WHITE));
+                            ui.add_space(5.0);
+
+                            ScrollArea::vertical()
+                                .max_height(200.0)
+                                .show(ui, |ui_scroll_content| {
+                                    ui_scroll_content.add_space(5.0);
+                                    ui_scroll_content.label(RichText::new("Iterasi | X_n | f(X_n)").color(Color32::WHITE).strong());
+                                    ui_scroll_content.separator();
+                                    for (value, residual) in result.history.iter().zip(&result.residuals) {
+                                        ui_scroll_content.label(format!("{:.0} | {:.8} | {:.3e}", value.x, value.y, residual));
+                                    }
+                                });
+                        }
                     }
+                });
+
+                ui.add_space(30.0);
+                ui.group(|ui| {
                     ui.add_space(5.0);
+                    ui.heading(RichText::new("Perbandingan Metode Numerik").color(Color32::YELLOW).strong());
+                    ui.add_space(10.0);
+
+                    match self.last_v_out {
+                        Some(v_out) => {
+                            ui.label(RichText::new(format!("Pembacaan terakhir: V_out = {:.4} V", v_out)).color(Color32::WHITE));
+                            if ui.button("Bandingkan Semua Metode").clicked() {
+                                let settings = self.calibration_for(&self.calibration_sensor_id);
+                                let lux_problem = LuxProblem {
+                                    model: settings.model(),
+                                    v_out,
+                                    x0: if self.initial_guess_nr > 0.0 { self.initial_guess_nr } else { 1.0 },
+                                    tol: self.tolerance_nr,
+                                    max_iter: self.max_iterations_nr as usize,
+                                };
+                                self.solver_comparison = lux_problem.compare_all();
+                            }
+                        },
+                        None => {
+                            ui.label(RichText::new("Menunggu pembacaan sensor pertama untuk dibandingkan.").color(Color32::GRAY).italics());
+                        }
+                    }
+
+                    if !self.solver_comparison.is_empty() {
+                        ui.add_space(10.0);
+                        Grid::new("solver_comparison_grid")
+                            .num_columns(6)
+                            .striped(true)
+                            .spacing([20.0, 6.0])
+                            .show(ui, |ui_grid| {
+                                for header in ["Metode", "Status", "Iterasi", "Lux", "|f(x)| (V)", "Waktu"] {
+                                    ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                                }
+                                ui_grid.end_row();
 
-                    if self.newton_raphson_iter_results.is_empty() {
-                        ui.label(RichText::new("Riwayat iterasi akan muncul di sini setelah perhitungan pertama.").color(Color32::GRAY).italics());
-                    } else {
-                        ScrollArea::vertical()
-                            .max_height(200.0)
-                            .show(ui, |ui_scroll_content| {
-                                ui_scroll_content.add_space(5.0);
-                                ui_scroll_content.label(RichText::new("Iterasi | X_n").color(Color32::WHITE).strong());
-                                ui_scroll_content.separator();
-                                for result in &self.newton_raphson_iter_results {
-                                    ui_scroll_content.label(format!("{:.0} | {:.8}", result.x, result.y));
+                                for comparison in &self.solver_comparison {
+                                    let result = &comparison.result;
+                                    let color = if result.is_converged() { Color32::LIGHT_GREEN } else { Color32::RED };
+                                    ui_grid.label(comparison.method.label());
+                                    ui_grid.label(RichText::new(result.status.to_string()).color(color));
+                                    ui_grid.label(result.iterations.to_string());
+                                    ui_grid.label(format!("{:.6}", result.root));
+                                    let residual = result.residuals.last().map(|r| r.abs()).unwrap_or(f64::NAN);
+                                    ui_grid.label(format!("{:.3e}", residual))
+                                        .on_hover_text(format!("Perkiraan error |xₙ - xₙ₋₁| = {:.3e}", result.estimated_error));
+                                    ui_grid.label(format!("{:.2} µs", comparison.elapsed.as_secs_f64() * 1e6));
+                                    ui_grid.end_row();
                                 }
                             });
+                        ui.add_space(5.0);
+                        ui.label(RichText::new("Waktu adalah rata-rata dari beberapa kali penyelesaian pada pembacaan yang sama.").color(Color32::GRAY).italics());
                     }
                 });
 
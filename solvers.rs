@@ -0,0 +1,530 @@
+// src/solvers.rs
+// Metode pencarian akar untuk menghitung lux dari tegangan photodiode. Metode dipilih di halaman
+// Konfigurasi Sensor, dan semua metode dapat dibandingkan pada pembacaan yang sama.
+
+use std::time::{Duration, Instant};
+
+use crate::calibration_model::{CalibrationModel, MAX_LUX_BRACKET};
+use crate::measurements::{Measurements, SolveResult, SolveStatus, Value};
+
+// Setiap metode dijalankan berulang kali saat dibandingkan agar waktu per penyelesaian tidak didominasi noise timer.
+const TIMING_REPEATS: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolverMethod {
+    SafeguardedNewton,
+    NewtonRaphson,
+    Bisection,
+    Secant,
+    RegulaFalsi,
+    Brent,
+    Halley,
+}
+
+impl SolverMethod {
+    pub const ALL: [SolverMethod; 7] = [
+        SolverMethod::SafeguardedNewton,
+        SolverMethod::NewtonRaphson,
+        SolverMethod::Bisection,
+        SolverMethod::Secant,
+        SolverMethod::RegulaFalsi,
+        SolverMethod::Brent,
+        SolverMethod::Halley,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolverMethod::SafeguardedNewton => "Newton-Raphson Terjaga (Bisection)",
+            SolverMethod::NewtonRaphson => "Newton-Raphson Murni",
+            SolverMethod::Bisection => "Bisection",
+            SolverMethod::Secant => "Secant",
+            SolverMethod::RegulaFalsi => "Regula Falsi (Illinois)",
+            SolverMethod::Brent => "Brent",
+            SolverMethod::Halley => "Halley",
+        }
+    }
+}
+
+// f, turunan pertama dan kedua, interval [lower, upper] yang mengapit akar dan tebakan awal x0.
+// Metode terbuka (Newton murni, secant, Halley) hanya memakai x0 dan bisa divergen.
+pub struct RootProblem<'a> {
+    pub f: &'a dyn Fn(f64) -> f64,
+    pub fp: &'a dyn Fn(f64) -> f64,
+    pub fpp: &'a dyn Fn(f64) -> f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub x0: f64,
+    pub tol: f64,
+    pub max_iter: usize,
+}
+
+fn push_history(result: &mut SolveResult, x: f64) {
+    result.history.push(Value { x: result.history.len() as f64, y: x });
+}
+
+// Memeriksa nilai fungsi di ujung interval. `Some` berarti pencarian sudah selesai (akar di ujung atau tidak terapit).
+fn check_bracket(problem: &RootProblem, f_lower: f64, f_upper: f64) -> Option<SolveResult> {
+    let mut result = SolveResult::new();
+    if !f_lower.is_finite() || !f_upper.is_finite() {
+        return Some(result);
+    }
+    if f_lower == 0.0 || f_upper == 0.0 {
+        let root = if f_lower == 0.0 { problem.lower } else { problem.upper };
+        result.root = root;
+        result.status = SolveStatus::Converged;
+        push_history(&mut result, root);
+        return Some(result);
+    }
+    if f_lower.signum() == f_upper.signum() {
+        result.status = SolveStatus::NoBracket;
+        return Some(result);
+    }
+    None
+}
+
+pub fn solve(method: SolverMethod, problem: &RootProblem) -> SolveResult {
+    match method {
+        SolverMethod::SafeguardedNewton => Measurements::safeguarded_newton(
+            problem.f, problem.fp, problem.lower, problem.upper, problem.x0, problem.tol, problem.max_iter),
+        SolverMethod::NewtonRaphson => Measurements::newton_raphson(
+            problem.f, problem.fp, problem.x0, problem.tol, problem.max_iter),
+        SolverMethod::Bisection => bisection(problem).finish(problem.f),
+        SolverMethod::Secant => secant(problem).finish(problem.f),
+        SolverMethod::RegulaFalsi => regula_falsi(problem).finish(problem.f),
+        SolverMethod::Brent => brent(problem).finish(problem.f),
+        SolverMethod::Halley => halley(problem).finish(problem.f),
+    }
+}
+
+fn bisection(problem: &RootProblem) -> SolveResult {
+    let f = problem.f;
+    let f_lower = f(problem.lower);
+    if let Some(result) = check_bracket(problem, f_lower, f(problem.upper)) {
+        return result;
+    }
+
+    let mut result = SolveResult::new();
+    let (mut a, mut b, mut fa) = (problem.lower, problem.upper, f_lower);
+    let mut mid = 0.5 * (a + b);
+    push_history(&mut result, mid);
+
+    for _ in 0..problem.max_iter {
+        let f_mid = f(mid);
+        if !f_mid.is_finite() {
+            result.root = mid;
+            return result;
+        }
+        if f_mid.signum() == fa.signum() {
+            a = mid;
+            fa = f_mid;
+        } else {
+            b = mid;
+        }
+
+        result.iterations += 1;
+        let mid_new = 0.5 * (a + b);
+        push_history(&mut result, mid_new);
+        if f_mid == 0.0 || 0.5 * (b - a).abs() < problem.tol {
+            result.root = if f_mid == 0.0 { mid } else { mid_new };
+            result.status = SolveStatus::Converged;
+            return result;
+        }
+        mid = mid_new;
+    }
+
+    result.root = mid;
+    result.status = SolveStatus::MaxIterations;
+    result
+}
+
+fn secant(problem: &RootProblem) -> SolveResult {
+    let f = problem.f;
+    let mut result = SolveResult::new();
+    // Titik kedua diambil sedikit di samping x0 sehingga langkah pertama mendekati langkah Newton.
+    let mut x_prev = problem.x0;
+    let mut x = problem.x0 + 1e-3 * problem.x0.abs().max(1.0);
+    let mut f_prev = f(x_prev);
+    push_history(&mut result, x_prev);
+
+    for _ in 0..problem.max_iter {
+        let fx = f(x);
+        if !fx.is_finite() || !f_prev.is_finite() {
+            result.root = x;
+            return result;
+        }
+        let slope = (fx - f_prev) / (x - x_prev);
+        if slope.abs() < 1e-12 {
+            result.root = x;
+            result.status = SolveStatus::ZeroDerivative;
+            return result;
+        }
+
+        result.iterations += 1;
+        let x_new = x - fx / slope;
+        push_history(&mut result, x_new);
+        if (x_new - x).abs() < problem.tol {
+            result.root = x_new;
+            result.status = SolveStatus::Converged;
+            return result;
+        }
+        x_prev = x;
+        f_prev = fx;
+        x = x_new;
+    }
+
+    result.root = x;
+    result.status = SolveStatus::MaxIterations;
+    result
+}
+
+// Varian Illinois: nilai f di ujung yang tidak bergerak dua kali berturut-turut dibagi dua
+// agar tidak terjebak konvergensi satu sisi seperti regula falsi biasa.
+fn regula_falsi(problem: &RootProblem) -> SolveResult {
+    let f = problem.f;
+    let (mut a, mut b) = (problem.lower, problem.upper);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if let Some(result) = check_bracket(problem, fa, fb) {
+        return result;
+    }
+
+    let mut result = SolveResult::new();
+    let mut x = (a * fb - b * fa) / (fb - fa);
+    let mut last_side = 0;
+    push_history(&mut result, x);
+
+    for _ in 0..problem.max_iter {
+        let fx = f(x);
+        if !fx.is_finite() {
+            result.root = x;
+            return result;
+        }
+        if fx == 0.0 {
+            result.root = x;
+            result.status = SolveStatus::Converged;
+            return result;
+        }
+        if fx.signum() == fb.signum() {
+            b = x;
+            fb = fx;
+            if last_side == -1 {
+                fa *= 0.5;
+            }
+            last_side = -1;
+        } else {
+            a = x;
+            fa = fx;
+            if last_side == 1 {
+                fb *= 0.5;
+            }
+            last_side = 1;
+        }
+
+        result.iterations += 1;
+        let x_new = (a * fb - b * fa) / (fb - fa);
+        push_history(&mut result, x_new);
+        if (x_new - x).abs() < problem.tol {
+            result.root = x_new;
+            result.status = SolveStatus::Converged;
+            return result;
+        }
+        x = x_new;
+    }
+
+    result.root = x;
+    result.status = SolveStatus::MaxIterations;
+    result
+}
+
+// Metode Brent: interpolasi invers kuadratik/secant dengan bisection sebagai pengaman.
+fn brent(problem: &RootProblem) -> SolveResult {
+    let f = problem.f;
+    let (mut a, mut b) = (problem.lower, problem.upper);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if let Some(result) = check_bracket(problem, fa, fb) {
+        return result;
+    }
+
+    let mut result = SolveResult::new();
+    let (mut c, mut fc) = (b, fb);
+    let mut d = b - a;
+    let mut e = d;
+    push_history(&mut result, b);
+
+    for _ in 0..problem.max_iter {
+        if fb.signum() == fc.signum() {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tol1 = 2.0 * f64::EPSILON * b.abs() + 0.5 * problem.tol;
+        let xm = 0.5 * (c - b);
+        if xm.abs() <= tol1 || fb == 0.0 {
+            // Setelah pertukaran di atas, b bisa berbeda dari iterasi terakhir yang dicatat.
+            if result.history.last().map(|value| value.y) != Some(b) {
+                push_history(&mut result, b);
+            }
+            result.root = b;
+            result.status = SolveStatus::Converged;
+            return result;
+        }
+
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * xm * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (s * (2.0 * xm * q * (q - r) - (b - a) * (r - 1.0)), (q - 1.0) * (r - 1.0) * (s - 1.0))
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            let min1 = 3.0 * xm * q - (tol1 * q).abs();
+            let min2 = (e * q).abs();
+            if 2.0 * p < min1.min(min2) {
+                e = d;
+                d = p / q;
+            } else {
+                d = xm;
+                e = d;
+                result.bisection_steps += 1;
+            }
+        } else {
+            d = xm;
+            e = d;
+            result.bisection_steps += 1;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 { d } else { tol1.copysign(xm) };
+        fb = f(b);
+        result.iterations += 1;
+        push_history(&mut result, b);
+        if !fb.is_finite() {
+            result.root = b;
+            result.status = SolveStatus::InvalidFunction;
+            return result;
+        }
+    }
+
+    result.root = b;
+    result.status = SolveStatus::MaxIterations;
+    result
+}
+
+fn halley(problem: &RootProblem) -> SolveResult {
+    let mut result = SolveResult::new();
+    let mut x = problem.x0;
+    push_history(&mut result, x);
+
+    for _ in 0..problem.max_iter {
+        let fx = (problem.f)(x);
+        let fpx = (problem.fp)(x);
+        let fppx = (problem.fpp)(x);
+        if !fx.is_finite() || !fpx.is_finite() || !fppx.is_finite() {
+            result.root = x;
+            return result;
+        }
+        let denominator = 2.0 * fpx * fpx - fx * fppx;
+        if denominator.abs() < 1e-12 {
+            result.root = x;
+            result.status = SolveStatus::ZeroDerivative;
+            return result;
+        }
+
+        result.iterations += 1;
+        let x_new = x - 2.0 * fx * fpx / denominator;
+        push_history(&mut result, x_new);
+        if (x_new - x).abs() < problem.tol {
+            result.root = x_new;
+            result.status = SolveStatus::Converged;
+            return result;
+        }
+        x = x_new;
+    }
+
+    result.root = x;
+    result.status = SolveStatus::MaxIterations;
+    result
+}
+
+#[derive(Clone, Debug)]
+pub struct SolverComparison {
+    pub method: SolverMethod,
+    pub result: SolveResult,
+    pub elapsed: Duration,
+}
+
+pub fn compare_all(problem: &RootProblem) -> Vec<SolverComparison> {
+    SolverMethod::ALL.iter().map(|&method| {
+        let started = Instant::now();
+        for _ in 1..TIMING_REPEATS {
+            solve(method, problem);
+        }
+        let result = solve(method, problem);
+        let elapsed = started.elapsed() / TIMING_REPEATS;
+        SolverComparison { method, result, elapsed }
+    }).collect()
+}
+
+// g(lux) = V_out untuk model kalibrasi sensor, diselesaikan untuk lux.
+#[derive(Clone, Copy)]
+pub struct LuxProblem<'a> {
+    pub model: &'a dyn CalibrationModel,
+    pub v_out: f64,
+    pub x0: f64,
+    pub tol: f64,
+    pub max_iter: usize,
+}
+
+impl LuxProblem<'_> {
+    fn with_root_problem<R>(&self, run: impl FnOnce(&RootProblem) -> R) -> R {
+        let (model, v_out) = (self.model, self.v_out);
+        let f = |lux: f64| model.voltage(lux) - v_out;
+        let fp = |lux: f64| model.derivative(lux);
+        let fpp = |lux: f64| model.second_derivative(lux);
+
+        // Lux tidak mungkin negatif, jadi akar dicari pada [0, batas atas] yang mengapit perubahan tanda f.
+        // Bila tidak ditemukan, metode terkurung melaporkan NoBracket alih-alih menebak nilai.
+        let upper = Measurements::expand_upper_bracket(f, 0.0, (2.0 * self.x0).max(1.0), MAX_LUX_BRACKET)
+            .unwrap_or(MAX_LUX_BRACKET);
+
+        run(&RootProblem {
+            f: &f,
+            fp: &fp,
+            fpp: &fpp,
+            lower: 0.0,
+            upper,
+            x0: self.x0,
+            tol: self.tol,
+            max_iter: self.max_iter,
+        })
+    }
+
+    pub fn solve(&self, method: SolverMethod) -> SolveResult {
+        self.with_root_problem(|problem| solve(method, problem))
+    }
+
+    pub fn compare_all(&self) -> Vec<SolverComparison> {
+        self.with_root_problem(compare_all)
+    }
+}
+
+// Rata-rata iterasi per penyelesaian, dipisah antara start dari tebakan awal tetap dan warm start
+// dari lux terakhir, untuk melihat penghematan warm start.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IterationStats {
+    pub cold_solves: usize,
+    pub cold_iterations: usize,
+    pub warm_solves: usize,
+    pub warm_iterations: usize,
+}
+
+impl IterationStats {
+    pub fn new() -> Self {
+        Self {
+            cold_solves: 0,
+            cold_iterations: 0,
+            warm_solves: 0,
+            warm_iterations: 0,
+        }
+    }
+
+    pub fn record(&mut self, warm_start: bool, iterations: usize) {
+        if warm_start {
+            self.warm_solves += 1;
+            self.warm_iterations += iterations;
+        } else {
+            self.cold_solves += 1;
+            self.cold_iterations += iterations;
+        }
+    }
+
+    pub fn average_cold(&self) -> Option<f64> {
+        (self.cold_solves > 0).then(|| self.cold_iterations as f64 / self.cold_solves as f64)
+    }
+
+    pub fn average_warm(&self) -> Option<f64> {
+        (self.warm_solves > 0).then(|| self.warm_iterations as f64 / self.warm_solves as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRACKETED: [SolverMethod; 4] = [
+        SolverMethod::SafeguardedNewton,
+        SolverMethod::Bisection,
+        SolverMethod::RegulaFalsi,
+        SolverMethod::Brent,
+    ];
+
+    fn with_problem<R>(f: &dyn Fn(f64) -> f64, fp: &dyn Fn(f64) -> f64, lower: f64, upper: f64, x0: f64, max_iter: usize, run: impl FnOnce(&RootProblem) -> R) -> R {
+        let fpp = |_: f64| 2.0;
+        let problem = RootProblem { f, fp, fpp: &fpp, lower, upper, x0, tol: 1e-10, max_iter };
+        run(&problem)
+    }
+
+    #[test]
+    fn all_methods_converge_to_sqrt_two() {
+        let f = |x: f64| x * x - 2.0;
+        let fp = |x: f64| 2.0 * x;
+        with_problem(&f, &fp, 0.0, 2.0, 1.5, 100, |problem| {
+            for method in SolverMethod::ALL {
+                let result = solve(method, problem);
+                assert_eq!(result.status, SolveStatus::Converged, "{}", method.label());
+                assert!((result.root - 2f64.sqrt()).abs() < 1e-8, "{}: {}", method.label(), result.root);
+                assert_eq!(result.residuals.len(), result.history.len(), "{}", method.label());
+                assert!(result.residuals.last().unwrap().abs() < 1e-8, "{}", method.label());
+                assert!(result.estimated_error.is_finite(), "{}", method.label());
+            }
+        });
+    }
+
+    #[test]
+    fn newton_stops_on_zero_derivative() {
+        let f = |x: f64| x * x - 2.0;
+        let fp = |x: f64| 2.0 * x;
+        with_problem(&f, &fp, 0.0, 2.0, 0.0, 100, |problem| {
+            let result = solve(SolverMethod::NewtonRaphson, problem);
+            assert_eq!(result.status, SolveStatus::ZeroDerivative);
+        });
+    }
+
+    #[test]
+    fn iteration_limit_reports_max_iterations() {
+        let f = |x: f64| x * x - 2.0;
+        let fp = |x: f64| 2.0 * x;
+        with_problem(&f, &fp, 0.0, 2.0, 1.5, 3, |problem| {
+            let result = solve(SolverMethod::Bisection, problem);
+            assert_eq!(result.status, SolveStatus::MaxIterations);
+            assert_eq!(result.iterations, 3);
+            assert!(result.estimated_error > 1e-10);
+        });
+    }
+
+    #[test]
+    fn bracketed_methods_reject_interval_without_sign_change() {
+        let f = |x: f64| x * x + 1.0;
+        let fp = |x: f64| 2.0 * x;
+        with_problem(&f, &fp, 0.0, 2.0, 1.0, 100, |problem| {
+            for method in BRACKETED {
+                assert_eq!(solve(method, problem).status, SolveStatus::NoBracket, "{}", method.label());
+            }
+        });
+    }
+}
@@ -0,0 +1,488 @@
+// src/sqlite_db.rs
+// Penyimpanan lokal berbasis SQLite untuk lokasi greenhouse tanpa server MongoDB.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+
+use crate::db::{NEWTON_RAPHSON_HOURLY_COLLECTION, PHOTODIODE_HOURLY_COLLECTION};
+use crate::calibration_profile::CalibrationProfile;
+use crate::crop::Bed;
+use crate::csv_import::ImportRow;
+use crate::dli::DailyLightIntegral;
+use crate::outliers::OutlierFlag;
+use crate::recompute::{self, RecomputedResult};
+use crate::storage::{self, AggregateRecord, ResultMetadata, RetentionPolicy, SeriesKind, Storage, StorageResult, StoredRecord};
+use crate::uncertainty::ConfidenceInterval;
+
+const HOUR_MS: i64 = 3_600_000;
+// Selang pemeriksaan TTL, sama dengan TTL monitor MongoDB.
+const TTL_CHECK_MS: i64 = 60_000;
+
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+    // SQLite tidak punya TTL index: TTL dari `ensure_schema` ditegakkan saat menyimpan data baru.
+    raw_ttl: Mutex<Option<std::time::Duration>>,
+    last_ttl_check_ms: AtomicI64,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> StorageResult<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS photodiode_data (
+                id INTEGER PRIMARY KEY,
+                sensor_id TEXT,
+                photodiode_value REAL NOT NULL,
+                timestamp INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS newton_raphson_results (
+                id INTEGER PRIMARY KEY,
+                sensor_id TEXT,
+                akar_terakhir REAL NOT NULL,
+                riwayat_iterasi TEXT NOT NULL,
+                timestamp INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS calibration_profiles (
+                id TEXT PRIMARY KEY,
+                sensor_id TEXT NOT NULL,
+                profile TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS calibration_activations (
+                id INTEGER PRIMARY KEY,
+                sensor_id TEXT NOT NULL,
+                profile_id TEXT NOT NULL,
+                timestamp INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS daily_light_integrals (
+                sensor_id TEXT NOT NULL,
+                date TEXT NOT NULL,
+                record TEXT NOT NULL,
+                PRIMARY KEY (sensor_id, date)
+            );
+            CREATE TABLE IF NOT EXISTS beds (
+                id TEXT PRIMARY KEY,
+                sensor_id TEXT NOT NULL,
+                bed TEXT NOT NULL
+            );",
+        )?;
+        for table in [PHOTODIODE_HOURLY_COLLECTION, NEWTON_RAPHSON_HOURLY_COLLECTION] {
+            // Ringkasan lama tanpa `source` punya primary key berbeda, jadi tabelnya dibangun ulang.
+            let migrate = table_exists(&conn, table)? && !has_column(&conn, table, "source")?;
+            if migrate {
+                conn.execute_batch(&format!("ALTER TABLE {0} RENAME TO {0}_lama", table))?;
+            }
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    sensor_id TEXT NOT NULL DEFAULT '',
+                    source TEXT NOT NULL DEFAULT '',
+                    timestamp INTEGER NOT NULL,
+                    sum REAL NOT NULL,
+                    count INTEGER NOT NULL,
+                    min REAL NOT NULL,
+                    max REAL NOT NULL,
+                    avg REAL NOT NULL,
+                    PRIMARY KEY (sensor_id, source, timestamp)
+                );",
+                table
+            ))?;
+            if migrate {
+                conn.execute_batch(&format!(
+                    "INSERT INTO {0} (sensor_id, timestamp, sum, count, min, max, avg)
+                     SELECT sensor_id, timestamp, sum, count, min, max, avg FROM {0}_lama;
+                     DROP TABLE {0}_lama;",
+                    table
+                ))?;
+            }
+        }
+        // File lama dibuat sebelum ada kolom `sensor_id` dan `source`.
+        for table in ["photodiode_data", "newton_raphson_results"] {
+            add_column_if_missing(&conn, table, "sensor_id", "TEXT")?;
+            add_column_if_missing(&conn, table, "source", "TEXT")?;
+        }
+        add_column_if_missing(&conn, "newton_raphson_results", "calibration_profile", "TEXT")?;
+        add_column_if_missing(&conn, "newton_raphson_results", "lux_lower", "REAL")?;
+        add_column_if_missing(&conn, "newton_raphson_results", "lux_upper", "REAL")?;
+        add_column_if_missing(&conn, "newton_raphson_results", "ppfd", "REAL")?;
+        for table in ["photodiode_data", "newton_raphson_results"] {
+            add_column_if_missing(&conn, table, "outlier", "TEXT")?;
+        }
+        Ok(Self { conn: Mutex::new(conn), raw_ttl: Mutex::new(None), last_ttl_check_ms: AtomicI64::new(0) })
+    }
+
+    fn expire_raw_data(&self, conn: &Connection, now_ms: i64) -> rusqlite::Result<()> {
+        let Some(ttl) = *self.raw_ttl.lock().unwrap() else {
+            return Ok(());
+        };
+        self.last_ttl_check_ms.store(now_ms, Ordering::Relaxed);
+        conn.execute("DELETE FROM photodiode_data WHERE timestamp < ?1", params![now_ms - ttl.as_millis() as i64])?;
+        Ok(())
+    }
+
+    // (tabel data mentah, kolom nilai, tabel ringkasan per jam).
+    fn table_for(kind: SeriesKind) -> (&'static str, &'static str, &'static str) {
+        match kind {
+            SeriesKind::Photodiode => ("photodiode_data", "photodiode_value", PHOTODIODE_HOURLY_COLLECTION),
+            SeriesKind::NewtonRaphson => ("newton_raphson_results", "akar_terakhir", NEWTON_RAPHSON_HOURLY_COLLECTION),
+        }
+    }
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1", params![table], |row| row.get::<_, i64>(0))
+        .map(|count| count > 0)
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt.query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, column_type: &str) -> rusqlite::Result<()> {
+    if !has_column(conn, table, column)? {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, column_type))?;
+    }
+    Ok(())
+}
+
+fn millis_to_utc(millis: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(millis).single()
+}
+
+fn range_bounds(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> (i64, i64) {
+    (
+        from.map(|dt| dt.timestamp_millis()).unwrap_or(i64::MIN),
+        to.map(|dt| dt.timestamp_millis()).unwrap_or(i64::MAX),
+    )
+}
+
+impl Storage for SqliteStorage {
+    fn insert_reading(&self, sensor_id: &str, photodiode_value: f64, outlier: Option<OutlierFlag>) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO photodiode_data (sensor_id, photodiode_value, outlier, timestamp) VALUES (?1, ?2, ?3, ?4)",
+            params![sensor_id, photodiode_value, outlier.map(|flag| flag.key()), Utc::now().timestamp_millis()],
+        )?;
+        let now_ms = Utc::now().timestamp_millis();
+        if now_ms - self.last_ttl_check_ms.load(Ordering::Relaxed) >= TTL_CHECK_MS {
+            self.expire_raw_data(&conn, now_ms)?;
+        }
+        Ok(())
+    }
+
+    fn insert_result(&self, sensor_id: &str, akar: f64, iterations_history: Vec<f64>, metadata: &ResultMetadata) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO newton_raphson_results (sensor_id, akar_terakhir, riwayat_iterasi, calibration_profile, lux_lower, lux_upper, ppfd, outlier, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                sensor_id,
+                akar,
+                serde_json::to_string(&iterations_history)?,
+                metadata.calibration_profile,
+                metadata.confidence_interval.map(|ci| ci.lower),
+                metadata.confidence_interval.map(|ci| ci.upper),
+                metadata.ppfd,
+                metadata.outlier.map(|flag| flag.key()),
+                Utc::now().timestamp_millis(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_imported(&self, sensor_id: &str, source: &str, rows: &[ImportRow]) -> StorageResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut insert_reading = tx.prepare(
+                "INSERT INTO photodiode_data (sensor_id, source, photodiode_value, timestamp) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            let mut insert_result = tx.prepare(
+                "INSERT INTO newton_raphson_results (sensor_id, source, akar_terakhir, riwayat_iterasi, timestamp) VALUES (?1, ?2, ?3, '[]', ?4)",
+            )?;
+            for row in rows {
+                let timestamp_ms = row.timestamp.timestamp_millis();
+                if let Some(value) = row.raw_value {
+                    inserted += insert_reading.execute(params![sensor_id, source, value, timestamp_ms])?;
+                }
+                if let Some(lux) = row.lux {
+                    inserted += insert_result.execute(params![sensor_id, source, lux, timestamp_ms])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    fn insert_recomputed(&self, sensor_id: &str, result_set: &str, calibration_profile: &str, results: &[RecomputedResult]) -> StorageResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut insert_result = tx.prepare(
+                "INSERT INTO newton_raphson_results (sensor_id, source, akar_terakhir, riwayat_iterasi, calibration_profile, lux_lower, lux_upper, ppfd, outlier, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for result in results {
+                inserted += insert_result.execute(params![
+                    sensor_id,
+                    result_set,
+                    result.lux,
+                    serde_json::to_string(&result.iterations_history)?,
+                    calibration_profile,
+                    result.confidence_interval.map(|ci| ci.lower),
+                    result.confidence_interval.map(|ci| ci.upper),
+                    result.ppfd,
+                    result.outlier.map(|flag| flag.key()),
+                    result.timestamp.timestamp_millis(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    fn query_range(&self, kind: SeriesKind, sensor_id: Option<&str>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> StorageResult<Vec<StoredRecord>> {
+        let (table, value_column, hourly_table) = Self::table_for(kind);
+        let (history_column, profile_column, interval_columns) = match kind {
+            SeriesKind::Photodiode => ("NULL", "NULL", "NULL, NULL, NULL"),
+            SeriesKind::NewtonRaphson => ("riwayat_iterasi", "calibration_profile", "lux_lower, lux_upper, ppfd"),
+        };
+        let (from_ms, to_ms) = range_bounds(from, to);
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT timestamp, sensor_id, source, {}, {}, {}, {}, outlier, NULL FROM {}
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND (?3 IS NULL OR sensor_id = ?3)
+             UNION ALL
+             SELECT timestamp, NULLIF(sensor_id, ''), NULLIF(source, ''), avg, NULL, NULL, NULL, NULL, NULL, NULL, count FROM {}
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND (?3 IS NULL OR sensor_id = ?3)
+             ORDER BY timestamp",
+            value_column, history_column, profile_column, interval_columns, table, hourly_table
+        ))?;
+        let rows = stmt.query_map(params![from_ms, to_ms, sensor_id], |row| {
+            let history: Option<String> = row.get(4)?;
+            Ok(StoredRecord {
+                timestamp: millis_to_utc(row.get(0)?),
+                sensor_id: row.get(1)?,
+                source: row.get(2)?,
+                value: row.get(3)?,
+                iterations_history: history
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                calibration_profile: row.get(5)?,
+                confidence_interval: match (row.get(6)?, row.get(7)?) {
+                    (Some(lower), Some(upper)) => Some(ConfidenceInterval { lower, upper }),
+                    _ => None,
+                },
+                ppfd: row.get(8)?,
+                outlier: row.get::<_, Option<String>>(9)?.and_then(|key| OutlierFlag::from_key(&key)),
+                summarized_count: row.get::<_, Option<i64>>(10)?.map(|count| count as u64),
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn aggregate(&self, kind: SeriesKind, sensor_id: Option<&str>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>, bucket: chrono::Duration) -> StorageResult<Vec<AggregateRecord>> {
+        let (table, value_column, hourly_table) = Self::table_for(kind);
+        let (from_ms, to_ms) = range_bounds(from, to);
+        let bucket_ms = bucket.num_milliseconds().max(1);
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT bucket, SUM(n), MIN(low), MAX(high), SUM(total) * 1.0 / SUM(n) FROM (
+                SELECT timestamp - (timestamp % ?3) AS bucket, 1 AS n, {0} AS low, {0} AS high, {0} AS total
+                FROM {1} WHERE timestamp >= ?1 AND timestamp < ?2 AND (?4 IS NULL OR sensor_id = ?4)
+                  AND (source IS NULL OR substr(source, 1, length(?5)) <> ?5)
+                UNION ALL
+                SELECT timestamp - (timestamp % ?3), count, min, max, sum
+                FROM {2} WHERE timestamp >= ?1 AND timestamp < ?2 AND (?4 IS NULL OR sensor_id = ?4)
+                  AND substr(source, 1, length(?5)) <> ?5
+             ) GROUP BY bucket ORDER BY bucket",
+            value_column, table, hourly_table
+        ))?;
+        // Set hasil hitung ulang memakai timestamp data asli, jadi tidak ikut dirata-rata dengan data live.
+        let rows = stmt.query_map(params![from_ms, to_ms, bucket_ms, sensor_id, recompute::RESULT_SET_PREFIX], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            let (bucket_start, count, min, max, avg) = row?;
+            if let Some(bucket_start) = millis_to_utc(bucket_start) {
+                results.push(AggregateRecord { bucket_start, count: count as u64, min, max, avg });
+            }
+        }
+        Ok(results)
+    }
+
+    fn ensure_schema(&self, policy: &RetentionPolicy) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS photodiode_data_timestamp ON photodiode_data (timestamp);
+             CREATE INDEX IF NOT EXISTS newton_raphson_results_timestamp ON newton_raphson_results (timestamp);",
+        )?;
+        *self.raw_ttl.lock().unwrap() = policy.raw_ttl();
+        self.expire_raw_data(&conn, Utc::now().timestamp_millis())?;
+        Ok(())
+    }
+
+    fn save_calibration_profile(&self, profile: &CalibrationProfile) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO calibration_profiles (id, sensor_id, profile) VALUES (?1, ?2, ?3)",
+            params![profile.id, profile.sensor_id, serde_json::to_string(profile)?],
+        )?;
+        Ok(())
+    }
+
+    fn list_calibration_profiles(&self, sensor_id: Option<&str>) -> StorageResult<Vec<CalibrationProfile>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT profile FROM calibration_profiles WHERE ?1 IS NULL OR sensor_id = ?1")?;
+        let mut profiles = Vec::new();
+        for json in stmt.query_map(params![sensor_id], |row| row.get::<_, String>(0))? {
+            profiles.push(serde_json::from_str::<CalibrationProfile>(&json?)?);
+        }
+        storage::sort_profiles(&mut profiles);
+        Ok(profiles)
+    }
+
+    fn record_profile_activation(&self, sensor_id: &str, profile_id: &str) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO calibration_activations (sensor_id, profile_id, timestamp) VALUES (?1, ?2, ?3)",
+            params![sensor_id, profile_id, Utc::now().timestamp_millis()],
+        )?;
+        Ok(())
+    }
+
+    fn active_calibration_profiles(&self) -> StorageResult<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT sensor_id, profile_id FROM calibration_activations
+             WHERE id IN (SELECT MAX(id) FROM calibration_activations GROUP BY sensor_id)",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn save_daily_light_integral(&self, dli: &DailyLightIntegral) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO daily_light_integrals (sensor_id, date, record) VALUES (?1, ?2, ?3)
+             ON CONFLICT(sensor_id, date) DO UPDATE SET record = excluded.record",
+            params![dli.sensor_id, dli.date.to_string(), serde_json::to_string(dli)?],
+        )?;
+        Ok(())
+    }
+
+    fn list_daily_light_integrals(&self, sensor_id: Option<&str>, from: Option<NaiveDate>) -> StorageResult<Vec<DailyLightIntegral>> {
+        let conn = self.conn.lock().unwrap();
+        // Tanggal ISO (YYYY-MM-DD) terurut dengan benar sebagai teks.
+        let mut stmt = conn.prepare(
+            "SELECT record FROM daily_light_integrals
+             WHERE (?1 IS NULL OR sensor_id = ?1) AND (?2 IS NULL OR date >= ?2)
+             ORDER BY date, sensor_id",
+        )?;
+        let mut totals = Vec::new();
+        for json in stmt.query_map(params![sensor_id, from.map(|date| date.to_string())], |row| row.get::<_, String>(0))? {
+            totals.push(serde_json::from_str::<DailyLightIntegral>(&json?)?);
+        }
+        Ok(totals)
+    }
+
+    fn save_bed(&self, bed: &Bed) -> StorageResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO beds (id, sensor_id, bed) VALUES (?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET sensor_id = excluded.sensor_id, bed = excluded.bed",
+            params![bed.id, bed.sensor_id, serde_json::to_string(bed)?],
+        )?;
+        Ok(())
+    }
+
+    fn list_beds(&self) -> StorageResult<Vec<Bed>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT bed FROM beds ORDER BY id")?;
+        let mut beds = Vec::new();
+        for json in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            beds.push(serde_json::from_str::<Bed>(&json?)?);
+        }
+        Ok(beds)
+    }
+
+    fn apply_retention_policy(&self, policy: &RetentionPolicy) -> StorageResult<()> {
+        let now_ms = Utc::now().timestamp_millis();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        if policy.downsampling_enabled {
+            let cutoff_ms = now_ms - policy.raw_retention_days as i64 * 24 * HOUR_MS;
+            let cutoff_ms = cutoff_ms - cutoff_ms.rem_euclid(HOUR_MS);
+
+            for (table, value_column, target) in [
+                ("photodiode_data", "photodiode_value", PHOTODIODE_HOURLY_COLLECTION),
+                ("newton_raphson_results", "akar_terakhir", NEWTON_RAPHSON_HOURLY_COLLECTION),
+            ] {
+                tx.execute(&format!(
+                    "INSERT INTO {2} (sensor_id, source, timestamp, sum, count, min, max, avg)
+                     SELECT COALESCE(sensor_id, '') AS sensor, COALESCE(source, '') AS origin, timestamp - (timestamp % {3}) AS hour,
+                            SUM({1}), COUNT(*), MIN({1}), MAX({1}), AVG({1})
+                     FROM {0} WHERE timestamp < ?1 GROUP BY sensor, origin, hour
+                     ON CONFLICT(sensor_id, source, timestamp) DO UPDATE SET
+                        sum = sum + excluded.sum,
+                        count = count + excluded.count,
+                        min = MIN(min, excluded.min),
+                        max = MAX(max, excluded.max),
+                        avg = (sum + excluded.sum) / (count + excluded.count)",
+                    table, value_column, target, HOUR_MS
+                ), params![cutoff_ms])?;
+                let deleted = tx.execute(&format!("DELETE FROM {} WHERE timestamp < ?1", table), params![cutoff_ms])?;
+                if deleted > 0 {
+                    eprintln!("[Retensi] {} baris lama dari '{}' diringkas ke '{}'.", deleted, table, target);
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarized_history_stays_readable() {
+        let storage = SqliteStorage::open(Path::new(":memory:")).unwrap();
+        let now = Utc::now();
+        let old_hour = Utc.timestamp_millis_opt((now.timestamp_millis() - 40 * 24 * HOUR_MS) / HOUR_MS * HOUR_MS).unwrap();
+        let rows = [
+            ImportRow { timestamp: old_hour + chrono::Duration::minutes(10), raw_value: Some(10.0), lux: Some(100.0) },
+            ImportRow { timestamp: old_hour + chrono::Duration::minutes(20), raw_value: Some(20.0), lux: Some(300.0) },
+            ImportRow { timestamp: now - chrono::Duration::hours(1), raw_value: Some(40.0), lux: Some(500.0) },
+        ];
+        storage.insert_imported("A0", "lux-meter", &rows).unwrap();
+        let policy = RetentionPolicy { downsampling_enabled: true, ..RetentionPolicy::new() };
+        storage.apply_retention_policy(&policy).unwrap();
+
+        let readings = storage.query_range(SeriesKind::Photodiode, Some("A0"), None, None).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0].timestamp, Some(old_hour));
+        assert_eq!(readings[0].value, Some(15.0));
+        assert_eq!(readings[0].summarized_count, Some(2));
+        assert_eq!(readings[0].source.as_deref(), Some("lux-meter"));
+        assert_eq!(readings[1].summarized_count, None);
+
+        let buckets = storage.aggregate(SeriesKind::NewtonRaphson, Some("A0"), None, None, chrono::Duration::days(365 * 10)).unwrap();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].count, 3);
+        assert_eq!((buckets[0].min, buckets[0].max), (100.0, 500.0));
+        assert!((buckets[0].avg - 300.0).abs() < 1e-9, "{}", buckets[0].avg);
+    }
+}
@@ -0,0 +1,192 @@
+// src/storage.rs
+// Abstraksi penyimpanan: MongoDB (server) atau SQLite (file lokal) dipilih saat aplikasi dijalankan.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::calibration_profile::CalibrationProfile;
+use crate::crop::Bed;
+use crate::csv_import::ImportRow;
+use crate::db::MongoStorage;
+use crate::dli::DailyLightIntegral;
+use crate::outliers::OutlierFlag;
+use crate::recompute::RecomputedResult;
+use crate::sqlite_db::SqliteStorage;
+use crate::uncertainty::ConfidenceInterval;
+
+pub type StorageResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+pub const DEFAULT_SQLITE_PATH: &str = "alprog.sqlite3";
+// Lebar bucket ringkasan retensi (`*_hourly`); timestamp ringkasan adalah awal jamnya.
+pub const SUMMARY_BUCKET_MS: i64 = 3_600_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StorageBackend {
+    MongoDb,
+    Sqlite { path: PathBuf },
+}
+
+impl StorageBackend {
+    // `--storage mongodb|sqlite` dan `--sqlite-path <file>`; default MongoDB.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut backend_name = "mongodb".to_string();
+        let mut sqlite_path = PathBuf::from(DEFAULT_SQLITE_PATH);
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--storage" => {
+                    backend_name = iter.next().ok_or("--storage membutuhkan nilai (mongodb/sqlite)")?.to_lowercase();
+                },
+                "--sqlite-path" => {
+                    sqlite_path = PathBuf::from(iter.next().ok_or("--sqlite-path membutuhkan nama file")?);
+                },
+                _ => {}
+            }
+        }
+
+        match backend_name.as_str() {
+            "mongodb" | "mongo" => Ok(StorageBackend::MongoDb),
+            "sqlite" => Ok(StorageBackend::Sqlite { path: sqlite_path }),
+            other => Err(format!("Backend penyimpanan tidak dikenal: '{}'", other)),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorageBackend::MongoDb => "MongoDB",
+            StorageBackend::Sqlite { .. } => "SQLite",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeriesKind {
+    Photodiode,
+    NewtonRaphson,
+}
+
+#[derive(Clone, Debug)]
+pub struct StoredRecord {
+    pub timestamp: Option<DateTime<Utc>>,
+    pub sensor_id: Option<String>,
+    // `None` untuk data live dari serial, selain itu label sumber impor atau set hasil hitung ulang.
+    pub source: Option<String>,
+    pub value: Option<f64>,
+    pub iterations_history: Vec<f64>,
+    // ID profil kalibrasi yang aktif saat lux dihitung; `None` untuk data photodiode, impor, atau kalibrasi manual.
+    pub calibration_profile: Option<String>,
+    // Interval kepercayaan 95% lux; `None` untuk data photodiode, impor, atau data sebelum propagasi ada.
+    pub confidence_interval: Option<ConfidenceInterval>,
+    // PPFD (µmol/m²/s) dari lux dengan faktor sumber cahaya saat data disimpan.
+    pub ppfd: Option<f64>,
+    // Tanda detektor lonjakan saat data live disimpan; `None` untuk sampel wajar atau data lama.
+    pub outlier: Option<OutlierFlag>,
+    // Jumlah sampel mentah bila baris ini ringkasan per jam dari kebijakan retensi; `value` adalah rata-ratanya.
+    pub summarized_count: Option<u64>,
+}
+
+// Keterangan hasil lux live selain nilai dan riwayat iterasi.
+#[derive(Clone, Debug, Default)]
+pub struct ResultMetadata {
+    pub calibration_profile: Option<String>,
+    pub confidence_interval: Option<ConfidenceInterval>,
+    pub ppfd: Option<f64>,
+    pub outlier: Option<OutlierFlag>,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct AggregateRecord {
+    pub bucket_start: DateTime<Utc>,
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetentionPolicy {
+    pub downsampling_enabled: bool,
+    pub raw_retention_days: u32,
+    pub ttl_enabled: bool,
+    pub raw_ttl_days: u32,
+}
+
+impl RetentionPolicy {
+    pub fn new() -> Self {
+        Self {
+            downsampling_enabled: false,
+            raw_retention_days: 30,
+            ttl_enabled: false,
+            raw_ttl_days: 90,
+        }
+    }
+
+    pub fn raw_ttl(&self) -> Option<std::time::Duration> {
+        if self.ttl_enabled {
+            Some(std::time::Duration::from_secs(self.raw_ttl_days as u64 * 86_400))
+        } else {
+            None
+        }
+    }
+}
+
+pub trait Storage: Send + Sync {
+    fn insert_reading(&self, sensor_id: &str, photodiode_value: f64, outlier: Option<OutlierFlag>) -> StorageResult<()>;
+
+    fn insert_result(&self, sensor_id: &str, akar: f64, iterations_history: Vec<f64>, metadata: &ResultMetadata) -> StorageResult<()>;
+
+    // Nilai mentah masuk ke data photodiode, lux ke hasil Newton-Raphson. Mengembalikan jumlah dokumen tersimpan.
+    fn insert_imported(&self, sensor_id: &str, source: &str, rows: &[ImportRow]) -> StorageResult<usize>;
+
+    // Hasil lux hasil hitung ulang, disimpan dengan label set hasil sebagai sumber.
+    fn insert_recomputed(&self, sensor_id: &str, result_set: &str, calibration_profile: &str, results: &[RecomputedResult]) -> StorageResult<usize>;
+
+    // Rentang `[from, to)`, `None` berarti tanpa batas (sensor `None` = semua sensor). Hasil diurutkan dari yang terlama.
+    // Data yang sudah diringkas retensi ikut sebagai satu baris rata-rata per jam (`summarized_count`).
+    fn query_range(&self, kind: SeriesKind, sensor_id: Option<&str>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> StorageResult<Vec<StoredRecord>>;
+
+    // Set hasil hitung ulang (`recompute::is_result_set`) tidak ikut; `query_range` tetap mengembalikan semua sumber.
+    // Ringkasan per jam dari retensi digabung dengan bobot jumlah sampelnya.
+    fn aggregate(&self, kind: SeriesKind, sensor_id: Option<&str>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>, bucket: chrono::Duration) -> StorageResult<Vec<AggregateRecord>>;
+
+    fn ensure_schema(&self, policy: &RetentionPolicy) -> StorageResult<()>;
+
+    fn save_calibration_profile(&self, profile: &CalibrationProfile) -> StorageResult<()>;
+
+    // Diurutkan per sensor, nama, lalu versi.
+    fn list_calibration_profiles(&self, sensor_id: Option<&str>) -> StorageResult<Vec<CalibrationProfile>>;
+
+    fn record_profile_activation(&self, sensor_id: &str, profile_id: &str) -> StorageResult<()>;
+
+    // Pasangan (sensor, ID profil) yang terakhir diaktifkan untuk setiap sensor.
+    fn active_calibration_profiles(&self) -> StorageResult<Vec<(String, String)>>;
+
+    fn apply_retention_policy(&self, policy: &RetentionPolicy) -> StorageResult<()>;
+
+    // Menimpa total harian yang sudah ada untuk sensor dan tanggal yang sama.
+    fn save_daily_light_integral(&self, dli: &DailyLightIntegral) -> StorageResult<()>;
+
+    // Diurutkan per tanggal; `from` adalah tanggal lokal pertama yang diambil.
+    fn list_daily_light_integrals(&self, sensor_id: Option<&str>, from: Option<NaiveDate>) -> StorageResult<Vec<DailyLightIntegral>>;
+
+    // Bedengan dengan ID yang sama ditimpa (misalnya saat tanam ulang).
+    fn save_bed(&self, bed: &Bed) -> StorageResult<()>;
+
+    // Diurutkan menurut ID bedengan.
+    fn list_beds(&self) -> StorageResult<Vec<Bed>>;
+}
+
+pub fn sort_profiles(profiles: &mut [CalibrationProfile]) {
+    profiles.sort_by(|a, b| (&a.sensor_id, &a.name, a.version).cmp(&(&b.sensor_id, &b.name, b.version)));
+}
+
+pub fn open_storage(backend: &StorageBackend) -> StorageResult<Arc<dyn Storage>> {
+    match backend {
+        StorageBackend::MongoDb => Ok(Arc::new(MongoStorage::connect()?)),
+        StorageBackend::Sqlite { path } => Ok(Arc::new(SqliteStorage::open(path)?)),
+    }
+}
@@ -0,0 +1,193 @@
+use egui::{Color32, Grid, RichText, ScrollArea, Ui};
+use chrono::{DateTime, Utc};
+use crate::photoperiod::{self, DailyLightStats, LuxSample, PhotoperiodSettings};
+use std::sync::{Arc, Mutex};
+
+pub struct SummaryScreen {
+    pub settings: PhotoperiodSettings,
+    pub summary_days: u32,
+    pub summary_request: bool,
+    // Agregat lux per menit dari database, diisi main.rs.
+    pub stored_samples: Arc<Mutex<Vec<LuxSample>>>,
+    // Lux live sejak aplikasi dimulai (dari `Measurements`), diisi main.rs setiap frame.
+    pub live_samples: Vec<LuxSample>,
+    pub status: Arc<Mutex<String>>,
+}
+
+impl SummaryScreen {
+    pub fn new() -> Self {
+        Self {
+            settings: PhotoperiodSettings::new(),
+            summary_days: 7,
+            summary_request: false,
+            stored_samples: Arc::new(Mutex::new(Vec::new())),
+            live_samples: Vec::new(),
+            status: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    // Fotoperiode hari terakhir yang sudah lewat dari agregat tersimpan.
+    pub fn latest_photoperiod(&self) -> Option<f64> {
+        let today = Utc::now().with_timezone(&self.settings.utc_offset).date_naive();
+        let samples = self.stored_samples.lock().unwrap();
+        photoperiod::daily_stats(&samples, &self.settings).into_iter()
+            .rev()
+            .find(|day| day.date < today)
+            .map(|day| day.photoperiod_hours)
+    }
+
+    fn local_time(&self, timestamp: DateTime<Utc>) -> String {
+        timestamp.with_timezone(&self.settings.utc_offset).format("%H:%M").to_string()
+    }
+
+    fn show_settings(&mut self, ui: &mut Ui) {
+        Grid::new("photoperiod_settings_grid")
+            .num_columns(2)
+            .spacing([40.0, 8.0])
+            .show(ui, |ui_grid| {
+                ui_grid.label(RichText::new("Ambang Terang:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.settings.light_threshold_lux)
+                    .speed(1.0)
+                    .range(1.0..=100_000.0)
+                    .suffix(" Lux"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Histeresis:").color(Color32::WHITE));
+                let mut hysteresis_percent = self.settings.hysteresis * 100.0;
+                if ui_grid.add(egui::DragValue::new(&mut hysteresis_percent)
+                    .speed(0.5)
+                    .range(0.0..=90.0)
+                    .suffix(" %")).changed() {
+                    self.settings.hysteresis = hysteresis_percent / 100.0;
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Durasi Minimum Keadaan:").color(Color32::WHITE));
+                let mut debounce_minutes = self.settings.min_state_seconds / 60.0;
+                if ui_grid.add(egui::DragValue::new(&mut debounce_minutes)
+                    .speed(0.5)
+                    .range(0.0..=120.0)
+                    .suffix(" menit")).changed() {
+                    self.settings.min_state_seconds = debounce_minutes * 60.0;
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Ambang Kebocoran Malam:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.settings.leakage_threshold_lux)
+                    .speed(0.1)
+                    .range(0.0..=1_000.0)
+                    .suffix(" Lux"));
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Zona Waktu (UTC+):").color(Color32::WHITE));
+                let mut offset_hours = self.settings.utc_offset.local_minus_utc() / 3600;
+                if ui_grid.add(egui::DragValue::new(&mut offset_hours).range(-12..=14)).changed() {
+                    if let Some(offset) = chrono::FixedOffset::east_opt(offset_hours * 3600) {
+                        self.settings.utc_offset = offset;
+                    }
+                }
+                ui_grid.end_row();
+
+                ui_grid.label(RichText::new("Rentang Riwayat:").color(Color32::WHITE));
+                ui_grid.add(egui::DragValue::new(&mut self.summary_days)
+                    .speed(1.0)
+                    .range(1..=90)
+                    .suffix(" hari terakhir"));
+                ui_grid.end_row();
+            });
+    }
+
+    fn show_stats_table(&self, ui: &mut Ui, id: &str, stats: &[DailyLightStats]) {
+        Grid::new(id)
+            .num_columns(8)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui_grid| {
+                for header in ["Tanggal", "Fotoperiode", "Jam > Ambang", "Puncak Lux", "Waktu Puncak", "Kebocoran Malam", "Rata-rata Malam", "Cakupan Data"] {
+                    ui_grid.label(RichText::new(header).color(Color32::WHITE).strong());
+                }
+                ui_grid.end_row();
+
+                for day in stats {
+                    ui_grid.label(day.date.to_string());
+                    ui_grid.label(format!("{:.2} jam", day.photoperiod_hours));
+                    ui_grid.label(format!("{:.2} jam", day.hours_above_threshold));
+                    ui_grid.label(format!("{:.0}", day.peak_lux));
+                    ui_grid.label(day.peak_time.map(|t| self.local_time(t)).unwrap_or_else(|| "-".to_string()));
+                    let leakage = RichText::new(format!("{:.2} jam", day.night_leakage_hours));
+                    ui_grid.label(if day.night_leakage_hours > 0.0 { leakage.color(Color32::from_rgb(255, 180, 60)) } else { leakage });
+                    ui_grid.label(day.night_mean_lux.map(|lux| format!("{:.2} Lux", lux)).unwrap_or_else(|| "-".to_string()));
+                    ui_grid.label(format!("{:.1} jam ({} sampel)", day.covered_hours, day.sample_count));
+                    ui_grid.end_row();
+                }
+            });
+
+        let transitions: Vec<String> = stats.iter()
+            .flat_map(|day| day.transitions.iter().map(move |transition| (day.date, transition)))
+            .map(|(date, transition)| format!("{} {} {}", date, self.local_time(transition.timestamp), transition.kind.label()))
+            .collect();
+        if !transitions.is_empty() {
+            ui.add_space(5.0);
+            ui.label(RichText::new(format!("Transisi: {}", transitions.join(" · "))).color(Color32::GRAY));
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(20.0);
+                    ui.heading(RichText::new("Ringkasan Harian Terang/Gelap").color(Color32::WHITE).strong());
+                    ui.add_space(30.0);
+                });
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Deteksi Fotoperiode").color(Color32::LIGHT_GREEN).strong());
+                    ui.add_space(10.0);
+                    self.show_settings(ui);
+                    ui.add_space(10.0);
+                    ui.label(RichText::new(format!("Transisi cepat (perubahan lebih dari 2× ambang dalam {:.0} detik) dianggap lampu, transisi bertahap dianggap matahari.",
+                        self.settings.abrupt_window_seconds)).color(Color32::GRAY).italics());
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Sesi Ini (Data Live)").color(Color32::LIGHT_BLUE).strong());
+                    ui.add_space(10.0);
+                    if self.live_samples.is_empty() {
+                        ui.label(RichText::new("Menunggu hasil lux dari sensor...").color(Color32::GRAY).italics());
+                    } else {
+                        let stats = photoperiod::daily_stats(&self.live_samples, &self.settings);
+                        self.show_stats_table(ui, "live_daily_stats_grid", &stats);
+                    }
+                });
+
+                ui.add_space(30.0);
+
+                ui.group(|ui| {
+                    ui.add_space(5.0);
+                    ui.heading(RichText::new("Riwayat Harian (Agregat Lux per Menit)").color(Color32::YELLOW).strong());
+                    ui.add_space(10.0);
+                    if ui.button(RichText::new("🔄 Muat Ringkasan").color(Color32::WHITE)).clicked() {
+                        self.summary_request = true;
+                    }
+                    let status = self.status.lock().unwrap().clone();
+                    if !status.is_empty() {
+                        ui.label(RichText::new(status).color(Color32::WHITE));
+                    }
+                    ui.add_space(10.0);
+
+                    let stats = photoperiod::daily_stats(&self.stored_samples.lock().unwrap(), &self.settings);
+                    if stats.is_empty() {
+                        ui.label(RichText::new("Tekan 'Muat Ringkasan' untuk menghitung statistik dari database.").color(Color32::GRAY).italics());
+                    } else {
+                        self.show_stats_table(ui, "stored_daily_stats_grid", &stats);
+                    }
+                });
+            });
+    }
+}
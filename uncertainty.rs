@@ -0,0 +1,75 @@
+// src/uncertainty.rs
+// Propagasi ketidakpastian orde pertama ke lux: kuantisasi ADC, toleransi tegangan referensi, dan
+// kovarians parameter A, B hasil fit power law. Kontribusi dianggap saling bebas.
+
+use serde::{Deserialize, Serialize};
+
+use crate::calibration_model::{CalibrationModelKind, CalibrationSettings};
+
+// Faktor cakupan untuk interval kepercayaan 95% (distribusi normal).
+pub const COVERAGE_FACTOR: f64 = 1.96;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// Ketidakpastian standar (1σ) dalam lux per sumber.
+#[derive(Clone, Copy, Debug)]
+pub struct LuxUncertainty {
+    pub lux: f64,
+    pub quantization: f64,
+    pub reference_voltage: f64,
+    pub calibration: f64,
+}
+
+impl LuxUncertainty {
+    pub fn std_dev(&self) -> f64 {
+        (self.quantization.powi(2) + self.reference_voltage.powi(2) + self.calibration.powi(2)).sqrt()
+    }
+
+    // Batas bawah tidak boleh negatif karena lux tidak pernah negatif.
+    pub fn interval(&self) -> ConfidenceInterval {
+        let half_width = COVERAGE_FACTOR * self.std_dev();
+        ConfidenceInterval {
+            lower: (self.lux - half_width).max(0.0),
+            upper: self.lux + half_width,
+        }
+    }
+}
+
+pub fn propagate(settings: &CalibrationSettings, photodiode_value: f64, lux: f64) -> Option<LuxUncertainty> {
+    let adc = &settings.adc;
+    let model = settings.model();
+    // d lux / d V dari turunan model, berlaku untuk semua jenis model.
+    let slope = model.derivative(lux);
+    if !slope.is_finite() || slope == 0.0 || !lux.is_finite() {
+        return None;
+    }
+    let dlux_dv = 1.0 / slope;
+
+    // Galat kuantisasi seragam ±½ LSB memiliki simpangan baku 1/√12 LSB.
+    let volts_per_count = adc.gain.abs() * adc.reference_voltage / adc.max_value();
+    let quantization = (dlux_dv * volts_per_count / 12f64.sqrt()).abs();
+
+    // V_out sebanding dengan Vref, sehingga σ_V = |V| · toleransi relatif.
+    let v_out = adc.to_voltage(photodiode_value);
+    let reference_voltage = (dlux_dv * v_out * adc.reference_tolerance).abs();
+
+    let calibration = match (settings.kind, settings.power_law_covariance) {
+        (CalibrationModelKind::PowerLaw, Some(covariance)) if lux > 0.0 => {
+            // lux = (V/A)^(1/B): ∂lux/∂A = -lux/(A·B), ∂lux/∂B = -lux · ln(lux) / B.
+            let (a, b) = (settings.power_law.a, settings.power_law.b);
+            let gradient = [-lux / (a * b), -lux * lux.ln() / b];
+            let variance = gradient[0] * gradient[0] * covariance[0][0]
+                + 2.0 * gradient[0] * gradient[1] * covariance[0][1]
+                + gradient[1] * gradient[1] * covariance[1][1];
+            variance.max(0.0).sqrt()
+        },
+        _ => 0.0,
+    };
+
+    let uncertainty = LuxUncertainty { lux, quantization, reference_voltage, calibration };
+    uncertainty.std_dev().is_finite().then_some(uncertainty)
+}